@@ -0,0 +1,23 @@
+#!/usr/bin/env sh
+# Fill in the sha256 of every bundled model in src-tauri/models.json
+#
+# The digests are the LFS object ids Hugging Face publishes for the files in
+# ggerganov/whisper.cpp, so the manifest only ever carries published values.
+# Needs curl and jq.
+set -eu
+
+manifest="$(dirname "$0")/../models.json"
+api="https://huggingface.co/api/models/ggerganov/whisper.cpp/tree/main"
+
+oids=$(curl -fsSL "$api" | jq 'map(select(.lfs) | {key: .path, value: .lfs.oid}) | from_entries')
+
+updated=$(mktemp)
+jq --indent 2 --argjson oids "$oids" '
+  .models |= map(
+    if $oids[.filename] then .sha256 = $oids[.filename]
+    else error("no published file for \(.id) (\(.filename))")
+    end
+  )
+' "$manifest" > "$updated"
+mv "$updated" "$manifest"
+echo "Updated $(jq '.models | length' "$manifest") models in $manifest"
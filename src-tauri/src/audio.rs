@@ -1,42 +1,314 @@
 //! Audio recording module for PROJ-3
 //!
 //! Handles microphone input, audio recording, and WAV export for Whisper.cpp
-
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, Host, Sample, SampleFormat, SampleRate, Stream, StreamConfig};
+//!
+//! Recordings are streamed to disk while they are captured: a writer thread
+//! drains the capture buffer, resamples to 16kHz and appends to the WAV file,
+//! rewriting the header on every flush so the file is always readable.
+//! The optional preprocessing chain (see `dsp`) runs on the 16kHz signal
+//! right before it is written.
+
+use crate::audio_quality::{QualityAnalyzer, QualityReport};
+use crate::audio_source::{
+    self, AudioSource, CpalSource, RunningSource, SourceErrorHandler, StreamHandle,
+};
+use crate::dsp::{DspChain, HighPassFilter, LoudnessNormalizer, NoiseSuppressor};
+use crate::encryption::{self, EncryptionError};
+use crate::recovery::{self, TakeJournal};
+use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::{
+    Device, Host, Sample, SampleFormat, SampleRate, SupportedStreamConfig,
+    SupportedStreamConfigRange,
+};
 use fs2::available_space;
 use hound::{WavSpec, WavWriter};
+use realfft::num_complex::Complex;
+use realfft::{RealFftPlanner, RealToComplex};
+use rtrb::{Consumer, Producer};
 use rubato::{FftFixedIn, Resampler};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::f32::consts::PI;
 use std::fs;
 use std::io::BufWriter;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
 use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
-/// Thread-safe wrapper for cpal::Stream
-/// Safety: The stream is only accessed from the main thread in Tauri
-struct SendableStream(Option<Stream>);
-
-// SAFETY: cpal::Stream is only created and used on the main thread
-// Tauri commands run on the main thread, so this is safe
-unsafe impl Send for SendableStream {}
-
 /// Target sample rate for Whisper.cpp (16kHz)
 pub const WHISPER_SAMPLE_RATE: u32 = 16000;
 
 /// Maximum recording duration in seconds (6 minutes)
 pub const MAX_RECORDING_SECONDS: u64 = 360;
 
-/// Audio level (0-100) for UI display
-pub type AudioLevel = u8;
+/// How long before the duration limit the recorder warns the user
+pub const LIMIT_WARNING_SECONDS: u64 = 30;
+
+/// How often the writer thread drains captured samples to disk
+const WRITER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Audio the lock-free capture ring can hold before the callback drops frames
+pub(crate) const CAPTURE_BUFFER_MS: u64 = 500;
+
+/// How often the capture worker drains the ring when it is empty
+const CAPTURE_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Minimum time between two overrun reports during a take
+const OVERRUN_REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a take waits for a replacement device before it is finalized
+pub const FAILOVER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pause between two attempts to find a replacement device
+pub const FAILOVER_RETRY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// VAD analysis frame length in milliseconds
+const VAD_FRAME_MS: u64 = 30;
+
+/// Consecutive speech frames needed to open a segment (90 ms)
+const VAD_MIN_SPEECH_FRAMES: u32 = 3;
+
+/// Quiet frames tolerated inside a segment before it is closed (300 ms)
+const VAD_HANGOVER_FRAMES: u32 = 10;
+
+/// Frames used to track the background level (3 s)
+const VAD_NOISE_WINDOW_FRAMES: usize = 100;
+
+/// RMS below which a frame never counts as speech (~ -46 dBFS)
+const VAD_MIN_SPEECH_RMS: f32 = 0.005;
+
+/// How far above the background a frame must be to count as speech (~ +10 dB)
+const VAD_NOISE_FACTOR: f32 = 3.0;
+
+/// Silence kept before the first and after the last speech segment when trimming
+const TRIM_PADDING_MS: u64 = 250;
+
+/// Default warm microphone pre-roll in milliseconds
+const DEFAULT_PRE_ROLL_MS: u32 = 1500;
+
+/// Allowed warm microphone pre-roll range in milliseconds
+const MIN_PRE_ROLL_MS: u32 = 500;
+const MAX_PRE_ROLL_MS: u32 = 2000;
+
+/// Time constant of the per-channel level the loudest-channel mode compares
+const LOUDEST_SMOOTHING_MS: f32 = 300.0;
+
+/// Power ratio another channel needs to take over in loudest-channel mode (3 dB)
+const LOUDEST_SWITCH_RATIO: f32 = 2.0;
+
+/// System audio the writer holds ahead of the microphone before dropping the oldest
+const SYSTEM_AUDIO_MAX_LAG_MS: u64 = 200;
+
+/// Name fragments of input devices that capture what the system plays
+/// (PulseAudio/PipeWire monitors, "Stereo Mix", virtual loopback drivers)
+const LOOPBACK_NAME_HINTS: &[&str] = &[
+    "monitor",
+    "loopback",
+    "stereo mix",
+    "stereomix",
+    "what u hear",
+    "blackhole",
+    "soundflower",
+];
 
 /// Information about an audio input device
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct AudioDevice {
+    /// Stable identity, see `DeviceIdentity`
     pub id: String,
     pub name: String,
     pub is_default: bool,
+    /// Audio host API the device belongs to (e.g. "CoreAudio", "WASAPI", "ALSA")
+    #[serde(default)]
+    pub host: String,
+    /// Most input channels the device offers (0 if it could not be probed)
+    #[serde(default)]
+    pub channels: u16,
+}
+
+/// Fingerprint used when a device's configurations cannot be probed
+const UNKNOWN_FINGERPRINT: &str = "00000000";
+
+/// Stable identity of an input device
+///
+/// Serialized as `host:index:fingerprint:name`, where `index` counts earlier
+/// devices with the same name (two identical USB mics get 0 and 1) and
+/// `fingerprint` is a short hash of the supported input configurations, which
+/// lets a renamed device be found again. The name comes last so it may
+/// contain colons itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceIdentity {
+    pub host: String,
+    pub index: usize,
+    pub fingerprint: String,
+    pub name: String,
+}
+
+impl DeviceIdentity {
+    /// Serialized form stored in `AudioSettings`
+    pub fn id(&self) -> String {
+        format!(
+            "{}:{}:{}:{}",
+            self.host, self.index, self.fingerprint, self.name
+        )
+    }
+
+    /// Parse a serialized identity (None for legacy name-only ids)
+    pub fn parse(id: &str) -> Option<Self> {
+        let mut parts = id.splitn(4, ':');
+        let host = parts.next()?;
+        let index = parts.next()?.parse().ok()?;
+        let fingerprint = parts.next()?;
+        let name = parts.next()?;
+
+        let valid_host = !host.is_empty() && host.chars().all(|c| c.is_ascii_alphanumeric());
+        let valid_fingerprint = fingerprint.len() == UNKNOWN_FINGERPRINT.len()
+            && fingerprint.chars().all(|c| c.is_ascii_hexdigit());
+        if !valid_host || !valid_fingerprint || name.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            host: host.to_string(),
+            index,
+            fingerprint: fingerprint.to_string(),
+            name: name.to_string(),
+        })
+    }
+}
+
+/// Most input channels any of the device's configurations offers
+///
+/// Falls back to the default config when the device is busy and cannot be
+/// probed, and to 0 if that fails too.
+pub(crate) fn max_input_channels(device: &Device) -> u16 {
+    device
+        .supported_input_configs()
+        .ok()
+        .and_then(|configs| configs.map(|c| c.channels()).max())
+        .or_else(|| device.default_input_config().ok().map(|c| c.channels()))
+        .unwrap_or(0)
+}
+
+/// Short hash of the device's supported input configurations
+fn config_fingerprint(device: &Device) -> Option<String> {
+    let mut configs: Vec<String> = device
+        .supported_input_configs()
+        .ok()?
+        .map(|c| {
+            format!(
+                "{}x{}-{}{}",
+                c.channels(),
+                c.min_sample_rate().0,
+                c.max_sample_rate().0,
+                c.sample_format()
+            )
+        })
+        .collect();
+    if configs.is_empty() {
+        return None;
+    }
+    configs.sort();
+    configs.dedup();
+
+    let digest = Sha256::digest(configs.join(";").as_bytes());
+    Some(digest[..4].iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Find the device a stored id refers to among the present identities
+///
+/// Tries, in order: the same host, name and index; the only device with that
+/// name (an identical twin was unplugged); the only device with the same
+/// configuration fingerprint (the device was renamed). Legacy name-only ids
+/// match the first device with that name.
+fn resolve_device(identities: &[DeviceIdentity], id: &str) -> Option<usize> {
+    let Some(wanted) = DeviceIdentity::parse(id) else {
+        return identities.iter().position(|i| i.name == id);
+    };
+
+    let exact = identities
+        .iter()
+        .position(|i| i.host == wanted.host && i.name == wanted.name && i.index == wanted.index);
+    if exact.is_some() {
+        return exact;
+    }
+
+    let unique = |matches: &dyn Fn(&DeviceIdentity) -> bool| {
+        let mut found = identities
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| i.host == wanted.host && matches(i));
+        match (found.next(), found.next()) {
+            (Some((pos, _)), None) => Some(pos),
+            _ => None,
+        }
+    };
+
+    unique(&|i| i.name == wanted.name).or_else(|| {
+        if wanted.fingerprint == UNKNOWN_FINGERPRINT {
+            None
+        } else {
+            unique(&|i| i.fingerprint == wanted.fingerprint)
+        }
+    })
+}
+
+/// Rewrite a legacy name-only id to a stable identity (None if nothing to do)
+fn migrate_device_id(identities: &[DeviceIdentity], id: &str) -> Option<String> {
+    if DeviceIdentity::parse(id).is_some() {
+        return None;
+    }
+    resolve_device(identities, id).map(|pos| identities[pos].id())
+}
+
+/// How the input channels are turned into the mono signal that is recorded
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ChannelMode {
+    /// Average all channels
+    #[default]
+    Average,
+    /// Record a single channel (0-based)
+    Channel(u16),
+    /// Weighted sum with one gain per channel, missing gains count as 0
+    Mix(Vec<f32>),
+    /// Follow whichever channel currently carries the most signal
+    Loudest,
+}
+
+impl ChannelMode {
+    /// Fewest input channels the mode needs; `available` is the most the device offers
+    fn required_channels(&self, available: u16) -> u16 {
+        match self {
+            ChannelMode::Average => 1,
+            ChannelMode::Channel(channel) => channel.saturating_add(1),
+            ChannelMode::Mix(gains) => gains
+                .iter()
+                .rposition(|gain| *gain != 0.0)
+                .map_or(1, |last| last as u16 + 1),
+            ChannelMode::Loudest => available.max(1),
+        }
+    }
+}
+
+/// Whether and how system audio (what the speakers play) is recorded
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum SystemAudioMode {
+    /// Microphone only
+    #[default]
+    Off,
+    /// Add the system audio to the microphone in one track
+    Mix,
+    /// Record the system audio to its own file, transcribed separately
+    SeparateTrack,
+}
+
+/// Whether a device name looks like a monitor or loopback source
+fn is_loopback_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    LOOPBACK_NAME_HINTS.iter().any(|hint| name.contains(hint))
 }
 
 /// Audio recording settings
@@ -44,22 +316,114 @@ pub struct AudioDevice {
 pub struct AudioSettings {
     /// Selected microphone device ID (None = default)
     pub device_id: Option<String>,
+    /// Devices to fall back to, in order, when the selected one is missing
+    #[serde(default)]
+    pub device_priority: Vec<String>,
     /// Maximum recording time in minutes (1-10)
     pub max_duration_minutes: u8,
     /// Privacy mode: auto-delete recordings after processing
     pub privacy_mode: bool,
+    /// Cut leading and trailing silence before the take goes to Whisper
+    #[serde(default = "default_true")]
+    pub trim_silence: bool,
+    /// Toggle mode: stop automatically after this many seconds of silence (None = off)
+    #[serde(default)]
+    pub silence_auto_stop_seconds: Option<u8>,
+    /// Preprocessing: remove DC offset, rumble and mains hum
+    #[serde(default)]
+    pub high_pass_filter: bool,
+    /// Preprocessing: suppress stationary background noise
+    #[serde(default)]
+    pub noise_suppression: bool,
+    /// Preprocessing: normalize quiet or loud recordings to a common level
+    #[serde(default)]
+    pub loudness_normalization: bool,
+    /// Warm microphone: keep the input stream open between takes and prepend
+    /// the last `pre_roll_ms` to the next take (held in memory only)
+    #[serde(default)]
+    pub warm_mic: bool,
+    /// Warm microphone pre-roll length in milliseconds (500-2000)
+    #[serde(default = "default_pre_roll_ms")]
+    pub pre_roll_ms: u32,
+    /// Add FFT frequency bands to the published meter frames
+    #[serde(default)]
+    pub meter_spectrum: bool,
+    /// Skip transcription when the quality check finds no speech in a take
+    #[serde(default = "default_true")]
+    pub skip_silent_takes: bool,
+    /// Which input channels end up in the recording
+    #[serde(default)]
+    pub channel_mode: ChannelMode,
+    /// Record system audio alongside the microphone
+    #[serde(default)]
+    pub system_audio: SystemAudioMode,
+    /// Monitor or loopback source for system audio (None = first one found)
+    #[serde(default)]
+    pub system_audio_device: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_pre_roll_ms() -> u32 {
+    DEFAULT_PRE_ROLL_MS
 }
 
 impl Default for AudioSettings {
     fn default() -> Self {
         Self {
             device_id: None,
+            device_priority: Vec::new(),
             max_duration_minutes: 6,
             privacy_mode: true,
+            trim_silence: true,
+            silence_auto_stop_seconds: None,
+            high_pass_filter: false,
+            noise_suppression: false,
+            loudness_normalization: false,
+            warm_mic: false,
+            pre_roll_ms: DEFAULT_PRE_ROLL_MS,
+            meter_spectrum: false,
+            skip_silent_takes: true,
+            channel_mode: ChannelMode::Average,
+            system_audio: SystemAudioMode::Off,
+            system_audio_device: None,
         }
     }
 }
 
+impl AudioSettings {
+    /// Maximum recording duration in seconds (clamped to the 1-10 minute range)
+    pub fn max_duration_seconds(&self) -> u64 {
+        self.max_duration_minutes.clamp(1, 10) as u64 * 60
+    }
+
+    /// Pre-roll length in frames at `sample_rate` (0 when the warm microphone is off)
+    pub fn pre_roll_frames(&self, sample_rate: u32) -> usize {
+        if !self.warm_mic {
+            return 0;
+        }
+        let ms = self.pre_roll_ms.clamp(MIN_PRE_ROLL_MS, MAX_PRE_ROLL_MS) as u64;
+        (ms * sample_rate as u64 / 1000) as usize
+    }
+
+    /// Build the preprocessing chain for the enabled stages
+    pub fn dsp_chain(&self) -> DspChain {
+        let mut chain = DspChain::new();
+        if self.high_pass_filter {
+            chain = chain.with_stage(HighPassFilter::new(WHISPER_SAMPLE_RATE));
+        }
+        if self.noise_suppression {
+            chain = chain.with_stage(NoiseSuppressor::new());
+        }
+        if self.loudness_normalization {
+            chain = chain.with_stage(LoudnessNormalizer::new(WHISPER_SAMPLE_RATE));
+        }
+        chain
+    }
+}
+
 /// Result of a completed recording
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct RecordingResult {
@@ -69,6 +433,549 @@ pub struct RecordingResult {
     pub duration_ms: u64,
     /// Whether privacy mode will auto-delete the file
     pub privacy_mode: bool,
+    /// Speech regions in the exported file (empty if no speech was detected)
+    #[serde(default)]
+    pub speech_segments: Vec<SpeechSegment>,
+    /// Frames the capture callback had to drop because the worker fell behind
+    #[serde(default)]
+    pub dropped_frames: u64,
+    /// Input configuration the device was opened with (None for imported files)
+    #[serde(default)]
+    pub capture_config: Option<CaptureConfig>,
+    /// Places where the input device was lost and the take continued on another
+    #[serde(default)]
+    pub device_gaps: Vec<DeviceGap>,
+    /// Places where the take was paused; the paused time is not in the file
+    #[serde(default)]
+    pub pause_markers: Vec<PauseMarker>,
+    /// Quality check of the raw input (None for imported files without audio)
+    #[serde(default)]
+    pub quality: Option<QualityReport>,
+    /// System audio recorded next to the microphone (`SystemAudioMode::SeparateTrack`)
+    #[serde(default)]
+    pub system_track: Option<SystemTrack>,
+    /// Silence cut from the start of the take; the live transcription saw it
+    #[serde(default)]
+    pub trim_offset_ms: u64,
+}
+
+/// Second file of a take holding the system audio, on the same timeline
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SystemTrack {
+    /// Path to the WAV file
+    pub file_path: String,
+    /// Speech regions in the system audio
+    pub speech_segments: Vec<SpeechSegment>,
+}
+
+/// Extension of the system audio track, which replaces the take's `wav`
+pub const SYSTEM_TRACK_EXTENSION: &str = "system.wav";
+
+/// Path of the system audio track that belongs to the take at `path`
+pub fn system_track_path(path: &Path) -> PathBuf {
+    path.with_extension(SYSTEM_TRACK_EXTENSION)
+}
+
+/// Marker for a pause within a take, usable as a paragraph break
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PauseMarker {
+    /// Position in the recording where the take was paused
+    pub at_ms: u64,
+    /// Wall-clock time the take stayed paused
+    pub paused_ms: u64,
+}
+
+/// Marker for audio missing from a take while the recorder switched devices
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DeviceGap {
+    /// Position in the recording where the gap sits
+    pub at_ms: u64,
+    /// Wall-clock time that passed until capture resumed
+    pub lost_ms: u64,
+}
+
+/// Input configuration negotiated with the capture device
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CaptureConfig {
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// cpal sample format, e.g. "f32" or "i16" (24-bit devices report "i32")
+    pub sample_format: String,
+}
+
+impl From<&SupportedStreamConfig> for CaptureConfig {
+    fn from(config: &SupportedStreamConfig) -> Self {
+        Self {
+            sample_rate: config.sample_rate().0,
+            channels: config.channels(),
+            sample_format: config.sample_format().to_string(),
+        }
+    }
+}
+
+/// Events raised by the recorder from its background threads
+#[derive(Clone, Debug, serde::Serialize)]
+pub enum RecorderEvent {
+    /// The take is about to hit `max_duration_minutes`
+    LimitWarning { elapsed_ms: u64, remaining_ms: u64 },
+    /// The take hit `max_duration_minutes`: capture stopped and the WAV file
+    /// is finalized, `stop_recording` returns it as usual
+    LimitReached { elapsed_ms: u64 },
+    /// Toggle mode silence auto-stop fired; the take is finalized like above
+    SilenceAutoStop { elapsed_ms: u64 },
+    /// The capture ring overflowed and audio was lost (total for this take)
+    CaptureOverrun { dropped_frames: u64 },
+    /// The input device failed mid-take; the take stays open until `fail_over`
+    /// finds a replacement or `abandon_failover` gives up
+    DeviceLost { elapsed_ms: u64 },
+    /// The take continues on another input device
+    DeviceChanged { device: String },
+    /// New level meter reading, published at `METER_RATE_HZ` while recording
+    Meter(MeterFrame),
+    /// System audio was requested but could not be captured; the take
+    /// continues with the microphone only
+    SystemAudioUnavailable { reason: String },
+}
+
+/// Callback that receives recorder events (set by the Tauri layer to emit them)
+pub type RecorderEventHandler = Arc<dyn Fn(RecorderEvent) + Send + Sync>;
+
+/// Processed 16kHz audio of the running take, as it is written to the file
+///
+/// The writer appends, a live consumer such as the streaming transcription
+/// takes what has arrived.
+pub type LiveAudio = Arc<Mutex<Vec<f32>>>;
+
+/// Convert a frame count at `sample_rate` to milliseconds
+fn frames_to_ms(frames: u64, sample_rate: u32) -> u64 {
+    frames * 1000 / sample_rate.max(1) as u64
+}
+
+// ============================================================================
+// Voice Activity Detection
+// ============================================================================
+
+/// A region of detected speech, in milliseconds from the start of the recording
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SpeechSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// Energy-based voice activity detector
+///
+/// Splits the signal into 30 ms frames and compares each frame's RMS with the
+/// background level (the quietest frame of the last 3 s). A segment opens after
+/// a short run of loud frames and closes after a hangover of quiet ones, so the
+/// gaps between words don't split it.
+pub struct VoiceActivityDetector {
+    frame_len: usize,
+    frame: Vec<f32>,
+    frame_index: u64,
+    recent_rms: std::collections::VecDeque<f32>,
+    speech_run: u32,
+    silence_run: u32,
+    /// First frame of the current run of loud frames
+    run_start: u64,
+    /// First frame of the open segment
+    segment_start: Option<u64>,
+    /// Last frame that belonged to a segment
+    last_speech_frame: Option<u64>,
+    segments: Vec<SpeechSegment>,
+}
+
+impl VoiceActivityDetector {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            frame_len: ((sample_rate as u64 * VAD_FRAME_MS / 1000) as usize).max(1),
+            frame: Vec::new(),
+            frame_index: 0,
+            recent_rms: std::collections::VecDeque::with_capacity(VAD_NOISE_WINDOW_FRAMES),
+            speech_run: 0,
+            silence_run: 0,
+            run_start: 0,
+            segment_start: None,
+            last_speech_frame: None,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Feed mono samples at the detector's sample rate
+    pub fn push(&mut self, samples: &[f32]) {
+        for &sample in samples {
+            self.frame.push(sample);
+            if self.frame.len() == self.frame_len {
+                self.process_frame();
+                self.frame.clear();
+            }
+        }
+    }
+
+    /// Silence since the last speech, or None if nobody has spoken yet
+    pub fn trailing_silence_ms(&self) -> Option<u64> {
+        self.last_speech_frame
+            .map(|last| (self.frame_index - last - 1) * VAD_FRAME_MS)
+    }
+
+    /// Close any open segment and return all detected segments
+    pub fn finish(mut self) -> Vec<SpeechSegment> {
+        self.close_segment();
+        self.segments
+    }
+
+    fn process_frame(&mut self) {
+        let rms = (self.frame.iter().map(|s| s * s).sum::<f32>() / self.frame.len() as f32).sqrt();
+
+        let noise_floor = self.recent_rms.iter().copied().fold(f32::MAX, f32::min);
+        let threshold = if noise_floor == f32::MAX {
+            VAD_MIN_SPEECH_RMS
+        } else {
+            (noise_floor * VAD_NOISE_FACTOR).max(VAD_MIN_SPEECH_RMS)
+        };
+
+        if self.recent_rms.len() == VAD_NOISE_WINDOW_FRAMES {
+            self.recent_rms.pop_front();
+        }
+        self.recent_rms.push_back(rms);
+
+        if rms > threshold {
+            if self.speech_run == 0 {
+                self.run_start = self.frame_index;
+            }
+            self.speech_run += 1;
+            self.silence_run = 0;
+            if self.segment_start.is_none() && self.speech_run >= VAD_MIN_SPEECH_FRAMES {
+                self.segment_start = Some(self.run_start);
+            }
+            if self.segment_start.is_some() {
+                self.last_speech_frame = Some(self.frame_index);
+            }
+        } else {
+            self.speech_run = 0;
+            self.silence_run += 1;
+            if self.silence_run >= VAD_HANGOVER_FRAMES {
+                self.close_segment();
+            }
+        }
+
+        self.frame_index += 1;
+    }
+
+    fn close_segment(&mut self) {
+        if let (Some(start), Some(last)) = (self.segment_start.take(), self.last_speech_frame) {
+            self.segments.push(SpeechSegment {
+                start_ms: start * VAD_FRAME_MS,
+                end_ms: (last + 1) * VAD_FRAME_MS,
+            });
+        }
+    }
+}
+
+/// Run the voice activity detector over a complete buffer
+pub fn detect_speech(samples: &[f32], sample_rate: u32) -> Vec<SpeechSegment> {
+    let mut vad = VoiceActivityDetector::new(sample_rate);
+    vad.push(samples);
+    vad.finish()
+}
+
+/// Run the voice activity detector over a finished take on disk
+///
+/// For takes whose speech segments did not come along from the recorder.
+pub fn detect_speech_in_file(path: &Path) -> Result<Vec<SpeechSegment>, AudioError> {
+    let data = encryption::read(path).map_err(|e| AudioError::Encryption(e.to_string()))?;
+    let reader = hound::WavReader::new(std::io::Cursor::new(data))
+        .map_err(|e| AudioError::WavWriteError(e.to_string()))?;
+    let mut vad = VoiceActivityDetector::new(reader.spec().sample_rate);
+    let mut chunk = Vec::with_capacity(4096);
+    for sample in reader.into_samples::<i16>() {
+        let sample = sample.map_err(|e| AudioError::WavWriteError(e.to_string()))?;
+        chunk.push(sample as f32 / i16::MAX as f32);
+        if chunk.len() == chunk.capacity() {
+            vad.push(&chunk);
+            chunk.clear();
+        }
+    }
+    vad.push(&chunk);
+    Ok(vad.finish())
+}
+
+/// Where trimming starts for a take whose first speech segment is `first`
+fn trim_start_ms(first: &SpeechSegment) -> u64 {
+    first.start_ms.saturating_sub(TRIM_PADDING_MS)
+}
+
+/// Cut leading and trailing silence from a finished 16kHz recording in place
+///
+/// Keeps `TRIM_PADDING_MS` around the speech. Returns the new sample count and
+/// the segments shifted to the trimmed file. Without any speech the file is left
+/// untouched, so a silent take is never turned into an empty one.
+fn trim_to_speech(
+    path: &Path,
+    total_samples: u64,
+    segments: &[SpeechSegment],
+) -> Result<(u64, Vec<SpeechSegment>), AudioError> {
+    let (Some(first), Some(last)) = (segments.first(), segments.last()) else {
+        return Ok((total_samples, Vec::new()));
+    };
+
+    let samples_per_ms = WHISPER_SAMPLE_RATE as u64 / 1000;
+    let start_ms = trim_start_ms(first);
+    let start = (start_ms * samples_per_ms).min(total_samples);
+    let end = ((last.end_ms + TRIM_PADDING_MS) * samples_per_ms).min(total_samples);
+
+    let shift = |segment: &SpeechSegment| SpeechSegment {
+        start_ms: segment.start_ms - start_ms,
+        end_ms: segment.end_ms.min(frames_to_ms(end, WHISPER_SAMPLE_RATE)) - start_ms,
+    };
+
+    if start == 0 && end == total_samples {
+        return Ok((total_samples, segments.iter().map(shift).collect()));
+    }
+
+    let mut reader =
+        hound::WavReader::open(path).map_err(|e| AudioError::WavWriteError(e.to_string()))?;
+    let spec = reader.spec();
+    let kept: Vec<i16> = reader
+        .samples::<i16>()
+        .skip(start as usize)
+        .take((end - start) as usize)
+        .collect::<Result<_, _>>()
+        .map_err(|e| AudioError::WavWriteError(e.to_string()))?;
+    drop(reader);
+
+    // Write next to the original and swap, so a failure never loses the take
+    let temp_path = path.with_extension("trimming");
+    let mut writer = WavWriter::create(&temp_path, spec)
+        .map_err(|e| AudioError::WavWriteError(e.to_string()))?;
+    for sample in &kept {
+        writer
+            .write_sample(*sample)
+            .map_err(|e| AudioError::WavWriteError(e.to_string()))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| AudioError::WavWriteError(e.to_string()))?;
+    fs::rename(&temp_path, path).map_err(|e| AudioError::WavWriteError(e.to_string()))?;
+
+    log::info!(
+        "Trimmed silence: kept {}ms to {}ms of {}ms",
+        start_ms,
+        frames_to_ms(end, WHISPER_SAMPLE_RATE),
+        frames_to_ms(total_samples, WHISPER_SAMPLE_RATE)
+    );
+
+    Ok((kept.len() as u64, segments.iter().map(shift).collect()))
+}
+
+/// Speech of two tracks on the same timeline, with overlapping segments joined
+fn merge_segments(a: &[SpeechSegment], b: &[SpeechSegment]) -> Vec<SpeechSegment> {
+    let mut sorted: Vec<SpeechSegment> = a.iter().chain(b).copied().collect();
+    sorted.sort_by_key(|segment| segment.start_ms);
+
+    let mut merged: Vec<SpeechSegment> = Vec::with_capacity(sorted.len());
+    for segment in sorted {
+        match merged.last_mut() {
+            Some(last) if segment.start_ms <= last.end_ms => {
+                last.end_ms = last.end_ms.max(segment.end_ms);
+            }
+            _ => merged.push(segment),
+        }
+    }
+    merged
+}
+
+/// Move segments to a file that was cut to start at `offset_ms` and last `duration_ms`
+fn shift_segments(
+    segments: &[SpeechSegment],
+    offset_ms: u64,
+    duration_ms: u64,
+) -> Vec<SpeechSegment> {
+    segments
+        .iter()
+        .map(|segment| SpeechSegment {
+            start_ms: segment.start_ms.saturating_sub(offset_ms).min(duration_ms),
+            end_ms: segment.end_ms.saturating_sub(offset_ms).min(duration_ms),
+        })
+        .collect()
+}
+
+// ============================================================================
+// Level Metering
+// ============================================================================
+
+/// How often the meter publishes a frame while recording
+pub const METER_RATE_HZ: u32 = 30;
+
+/// Points in the downsampled waveform of each meter frame
+const METER_WAVEFORM_POINTS: usize = 48;
+
+/// Frequency bands in a meter frame when the spectrum is enabled
+const METER_SPECTRUM_BANDS: usize = 16;
+
+/// Lowest frequency covered by the spectrum bands
+const METER_SPECTRUM_MIN_HZ: f32 = 60.0;
+
+/// Level reported for digital silence
+pub const METER_FLOOR_DBFS: f32 = -96.0;
+
+/// Samples at or above this magnitude count as clipped
+pub const CLIP_THRESHOLD: f32 = 0.999;
+
+/// One published meter reading, covering the last `1 / METER_RATE_HZ` seconds
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct MeterFrame {
+    pub peak_dbfs: f32,
+    pub rms_dbfs: f32,
+    /// Clipped samples since the take started
+    pub clip_count: u64,
+    /// Peak magnitude (0.0-1.0) per slice of the interval
+    pub waveform: Vec<f32>,
+    /// Level in dBFS per log-spaced frequency band (empty unless enabled)
+    pub bands: Vec<f32>,
+}
+
+/// Convert a linear magnitude to dBFS, floored at `METER_FLOOR_DBFS`
+fn to_dbfs(magnitude: f32) -> f32 {
+    if magnitude <= 0.0 {
+        return METER_FLOOR_DBFS;
+    }
+    (20.0 * magnitude.log10()).clamp(METER_FLOOR_DBFS, 0.0)
+}
+
+/// Spectrum analysis for the meter: a Hann-windowed FFT over the newest
+/// samples of each interval, summed into log-spaced bands
+struct MeterSpectrum {
+    fft: Arc<dyn RealToComplex<f32>>,
+    window: Vec<f32>,
+    input: Vec<f32>,
+    output: Vec<Complex<f32>>,
+    /// FFT bin range of each band
+    bands: Vec<(usize, usize)>,
+}
+
+impl MeterSpectrum {
+    fn new(sample_rate: u32, interval: usize) -> Self {
+        // Largest power of two that fits into one interval
+        let fft_len = 1usize << (usize::BITS - 1 - interval.max(64).leading_zeros());
+        let fft = RealFftPlanner::<f32>::new().plan_fft_forward(fft_len);
+        let window = (0..fft_len)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / fft_len as f32).cos())
+            .collect();
+
+        let bin_hz = sample_rate as f32 / fft_len as f32;
+        let max_hz = sample_rate as f32 / 2.0;
+        let ratio = (max_hz / METER_SPECTRUM_MIN_HZ).powf(1.0 / METER_SPECTRUM_BANDS as f32);
+        let bands = (0..METER_SPECTRUM_BANDS)
+            .map(|band| {
+                let low = METER_SPECTRUM_MIN_HZ * ratio.powi(band as i32);
+                let start = ((low / bin_hz) as usize).max(1);
+                let end = ((low * ratio / bin_hz) as usize).clamp(start + 1, fft_len / 2 + 1);
+                (start.min(fft_len / 2), end)
+            })
+            .collect();
+
+        Self {
+            input: fft.make_input_vec(),
+            output: fft.make_output_vec(),
+            fft,
+            window,
+            bands,
+        }
+    }
+
+    fn analyze(&mut self, samples: &[f32]) -> Vec<f32> {
+        let newest = &samples[samples.len().saturating_sub(self.window.len())..];
+        self.input.fill(0.0);
+        for ((input, &sample), &weight) in self.input.iter_mut().zip(newest).zip(&self.window) {
+            *input = sample * weight;
+        }
+        if self.fft.process(&mut self.input, &mut self.output).is_err() {
+            return Vec::new();
+        }
+
+        // A full-scale sine peaks at N/4 with a Hann window
+        let scale = 4.0 / self.window.len() as f32;
+        self.bands
+            .iter()
+            .map(|&(start, end)| {
+                let peak = self.output[start..end]
+                    .iter()
+                    .map(|bin| bin.norm())
+                    .fold(0.0f32, f32::max);
+                to_dbfs(peak * scale)
+            })
+            .collect()
+    }
+}
+
+/// Turns the mono take stream into `MeterFrame`s at `METER_RATE_HZ`
+///
+/// Frames are cut by sample count, so the rate follows the audio clock and
+/// does not depend on how the capture worker happens to chunk the stream.
+pub struct LevelMeter {
+    interval: usize,
+    pending: Vec<f32>,
+    clip_count: u64,
+    spectrum: Option<MeterSpectrum>,
+}
+
+impl LevelMeter {
+    /// Create a meter for audio at `sample_rate`, optionally with spectrum bands
+    pub fn new(sample_rate: u32, spectrum: bool) -> Self {
+        let interval = (sample_rate / METER_RATE_HZ).max(1) as usize;
+        Self {
+            interval,
+            pending: Vec::with_capacity(interval),
+            clip_count: 0,
+            spectrum: spectrum.then(|| MeterSpectrum::new(sample_rate, interval)),
+        }
+    }
+
+    /// Feed samples; returns the newest frame completed by them, if any
+    pub fn push(&mut self, samples: &[f32]) -> Option<MeterFrame> {
+        let mut frame = None;
+        for chunk in samples.chunks(self.interval) {
+            let take = chunk.len().min(self.interval - self.pending.len());
+            self.pending.extend_from_slice(&chunk[..take]);
+            if self.pending.len() == self.interval {
+                frame = Some(self.measure());
+                self.pending.clear();
+            }
+            self.pending.extend_from_slice(&chunk[take..]);
+        }
+        frame
+    }
+
+    fn measure(&mut self) -> MeterFrame {
+        let samples = &self.pending;
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+        self.clip_count += samples.iter().filter(|s| s.abs() >= CLIP_THRESHOLD).count() as u64;
+
+        let len = samples.len();
+        let waveform = (0..METER_WAVEFORM_POINTS)
+            .map(|point| {
+                let slice = &samples[point * len / METER_WAVEFORM_POINTS
+                    ..(point + 1) * len / METER_WAVEFORM_POINTS];
+                slice
+                    .iter()
+                    .fold(0.0f32, |peak, s| peak.max(s.abs()))
+                    .min(1.0)
+            })
+            .collect();
+        let bands = match self.spectrum.as_mut() {
+            Some(spectrum) => spectrum.analyze(samples),
+            None => Vec::new(),
+        };
+
+        MeterFrame {
+            peak_dbfs: to_dbfs(peak),
+            rms_dbfs: to_dbfs(rms),
+            clip_count: self.clip_count,
+            waveform,
+            bands,
+        }
+    }
 }
 
 /// Errors that can occur during audio operations
@@ -96,29 +1003,756 @@ pub enum AudioError {
     PermissionDenied,
     #[error("Microphone in use by another application")]
     DeviceBusy,
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+}
+
+impl serde::Serialize for AudioError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Resampler that converts an unbounded sample stream chunk by chunk
+///
+/// Wraps rubato's fixed-input FFT resampler: input is buffered until a full
+/// chunk is available, the resampler's startup delay is discarded and `finish`
+/// flushes the tail so the output length matches the input duration exactly.
+struct StreamingResampler {
+    resampler: FftFixedIn<f32>,
+    /// Input samples waiting for a full chunk
+    input: Vec<f32>,
+    /// Output samples still to discard (resampler delay)
+    delay_remaining: usize,
+    frames_in: u64,
+    frames_out: u64,
+    ratio: f64,
+}
+
+impl StreamingResampler {
+    /// Input chunk size for the FFT resampler
+    const CHUNK_FRAMES: usize = 1024;
+
+    fn new(source_rate: u32, target_rate: u32) -> Result<Self, AudioError> {
+        let resampler = FftFixedIn::<f32>::new(
+            source_rate as usize,
+            target_rate as usize,
+            Self::CHUNK_FRAMES,
+            2,
+            1, // mono
+        )
+        .map_err(|e| AudioError::WavWriteError(format!("Resampler init failed: {}", e)))?;
+
+        Ok(Self {
+            delay_remaining: resampler.output_delay(),
+            resampler,
+            input: Vec::with_capacity(Self::CHUNK_FRAMES * 2),
+            frames_in: 0,
+            frames_out: 0,
+            ratio: target_rate as f64 / source_rate as f64,
+        })
+    }
+
+    /// Feed samples and return whatever output is ready
+    fn process(&mut self, samples: &[f32]) -> Result<Vec<f32>, AudioError> {
+        self.input.extend_from_slice(samples);
+        self.frames_in += samples.len() as u64;
+
+        let mut output = Vec::new();
+        while self.input.len() >= self.resampler.input_frames_next() {
+            let needed = self.resampler.input_frames_next();
+            let chunk: Vec<f32> = self.input.drain(..needed).collect();
+            self.process_chunk(chunk, &mut output)?;
+        }
+        Ok(output)
+    }
+
+    /// Flush buffered input and the resampler delay
+    fn finish(mut self) -> Result<Vec<f32>, AudioError> {
+        let expected = (self.frames_in as f64 * self.ratio).round() as u64;
+        let mut output = Vec::new();
+
+        while self.frames_out < expected {
+            let needed = self.resampler.input_frames_next();
+            let mut chunk: Vec<f32> = self.input.drain(..).collect();
+            chunk.resize(needed, 0.0);
+            self.process_chunk(chunk, &mut output)?;
+        }
+
+        // Drop the zero padding that went past the real end of the input
+        let excess = (self.frames_out - expected) as usize;
+        output.truncate(output.len().saturating_sub(excess));
+        Ok(output)
+    }
+
+    fn process_chunk(&mut self, chunk: Vec<f32>, output: &mut Vec<f32>) -> Result<(), AudioError> {
+        let result = self
+            .resampler
+            .process(&[chunk], None)
+            .map_err(|e| AudioError::WavWriteError(format!("Resampling failed: {}", e)))?;
+
+        let Some(channel) = result.into_iter().next() else {
+            return Ok(());
+        };
+        let skip = self.delay_remaining.min(channel.len());
+        self.delay_remaining -= skip;
+        self.frames_out += (channel.len() - skip) as u64;
+        output.extend_from_slice(&channel[skip..]);
+        Ok(())
+    }
+}
+
+/// Incremental 16kHz WAV writer for a recording in progress
+///
+/// Samples arrive at the device rate and are resampled on the fly. `flush`
+/// rewrites the WAV header, so a crash only loses what came after the last flush.
+pub struct StreamingWavWriter {
+    writer: WavWriter<BufWriter<fs::File>>,
+    resampler: Option<StreamingResampler>,
+    dsp: DspChain,
+    samples_written: u64,
+    tap: Option<LiveAudio>,
+}
+
+impl StreamingWavWriter {
+    /// Create the WAV file and prepare resampling from `source_rate`
+    pub fn create(path: &Path, source_rate: u32) -> Result<Self, AudioError> {
+        let spec = WavSpec {
+            channels: 1,
+            sample_rate: WHISPER_SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        let file = fs::File::create(path).map_err(|e| AudioError::WavWriteError(e.to_string()))?;
+        let writer = WavWriter::new(BufWriter::new(file), spec)
+            .map_err(|e| AudioError::WavWriteError(e.to_string()))?;
+
+        let resampler = if source_rate != WHISPER_SAMPLE_RATE {
+            log::info!(
+                "Resampling from {} Hz to {} Hz while recording",
+                source_rate,
+                WHISPER_SAMPLE_RATE
+            );
+            Some(StreamingResampler::new(source_rate, WHISPER_SAMPLE_RATE)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            writer,
+            resampler,
+            dsp: DspChain::new(),
+            samples_written: 0,
+            tap: None,
+        })
+    }
+
+    /// Run the 16kHz signal through `chain` before it is written
+    pub fn with_dsp(mut self, chain: DspChain) -> Self {
+        self.dsp = chain;
+        self
+    }
+
+    /// Also hand every written sample to `tap`
+    pub fn with_tap(mut self, tap: LiveAudio) -> Self {
+        self.tap = Some(tap);
+        self
+    }
+
+    /// Append mono samples at the source rate
+    pub fn write(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        let resampled = match self.resampler.as_mut() {
+            Some(resampler) => resampler.process(samples)?,
+            None => samples.to_vec(),
+        };
+        let processed = self.dsp.process(&resampled);
+        self.write_resampled(&processed)
+    }
+
+    /// Rewrite the header so the file is valid up to this point
+    pub fn flush(&mut self) -> Result<(), AudioError> {
+        self.writer
+            .flush()
+            .map_err(|e| AudioError::WavWriteError(e.to_string()))
+    }
+
+    /// Number of 16kHz samples written so far
+    pub fn samples_written(&self) -> u64 {
+        self.samples_written
+    }
+
+    /// Flush the resampler and preprocessing tails and close the file
+    /// Returns the total number of 16kHz samples in the file
+    pub fn finalize(mut self) -> Result<u64, AudioError> {
+        if let Some(resampler) = self.resampler.take() {
+            let tail = resampler.finish()?;
+            let processed = self.dsp.process(&tail);
+            self.write_resampled(&processed)?;
+        }
+        let dsp_tail = self.dsp.finish();
+        self.write_resampled(&dsp_tail)?;
+        let samples_written = self.samples_written;
+        self.writer
+            .finalize()
+            .map_err(|e| AudioError::WavWriteError(e.to_string()))?;
+        Ok(samples_written)
+    }
+
+    fn write_resampled(&mut self, samples: &[f32]) -> Result<(), AudioError> {
+        for sample in samples {
+            // Clamp and convert
+            let clamped = sample.clamp(-1.0, 1.0);
+            let sample_i16 = (clamped * i16::MAX as f32) as i16;
+            self.writer
+                .write_sample(sample_i16)
+                .map_err(|e| AudioError::WavWriteError(e.to_string()))?;
+        }
+        self.samples_written += samples.len() as u64;
+        if let Some(Ok(mut tap)) = self.tap.as_ref().map(|tap| tap.lock()) {
+            tap.extend(samples.iter().map(|sample| sample.clamp(-1.0, 1.0)));
+        }
+        Ok(())
+    }
+}
+
+/// Preference among sample formats when a device offers several (lower is better)
+///
+/// Float and wide integer formats keep the most headroom; 24-bit interfaces
+/// show up as I32 since cpal has no packed 24-bit format.
+fn sample_format_rank(format: SampleFormat) -> u8 {
+    match format {
+        SampleFormat::F32 => 0,
+        SampleFormat::I32 => 1,
+        SampleFormat::F64 => 2,
+        SampleFormat::I64 => 3,
+        SampleFormat::I16 => 4,
+        SampleFormat::U32 => 5,
+        SampleFormat::U64 => 6,
+        SampleFormat::U16 => 7,
+        SampleFormat::I8 => 8,
+        SampleFormat::U8 => 9,
+        _ => u8::MAX,
+    }
+}
+
+/// Input configs to try, best first
+///
+/// 1. A 16kHz config, so no resampling is needed (fewest channels preferred)
+/// 2. The device default, which the OS is most likely to run glitch-free
+/// 3. Otherwise the rate closest to 16kHz, preferring rates above it
+///
+/// Only configs with at least `min_channels` are considered, unless the
+/// device has none; the channel mixer then falls back to averaging.
+pub(crate) fn negotiate_input_configs(
+    ranges: &[SupportedStreamConfigRange],
+    default: Option<&SupportedStreamConfig>,
+    min_channels: u16,
+) -> Vec<SupportedStreamConfig> {
+    let target = SampleRate(WHISPER_SAMPLE_RATE);
+    let mut candidates = Vec::new();
+
+    let enough: Vec<SupportedStreamConfigRange> = ranges
+        .iter()
+        .filter(|range| range.channels() >= min_channels)
+        .cloned()
+        .collect();
+    let ranges = if enough.is_empty() { ranges } else { &enough };
+    let default = default.filter(|config| config.channels() >= min_channels || enough.is_empty());
+
+    if let Some(native) = ranges
+        .iter()
+        .filter(|range| range.min_sample_rate() <= target && target <= range.max_sample_rate())
+        .min_by_key(|range| (range.channels(), sample_format_rank(range.sample_format())))
+    {
+        candidates.push((*native).with_sample_rate(target));
+    }
+
+    if let Some(default) = default {
+        candidates.push(default.clone());
+    }
+
+    if let Some(closest) = ranges
+        .iter()
+        .map(|range| {
+            let rate = target.clamp(range.min_sample_rate(), range.max_sample_rate());
+            (*range).with_sample_rate(rate)
+        })
+        .min_by_key(|config| {
+            let rate = config.sample_rate().0;
+            (
+                rate < WHISPER_SAMPLE_RATE,
+                rate.abs_diff(WHISPER_SAMPLE_RATE),
+                config.channels(),
+                sample_format_rank(config.sample_format()),
+            )
+        })
+    {
+        candidates.push(closest);
+    }
+
+    candidates.dedup();
+    candidates
+}
+
+/// A running input stream and the worker that drains its ring buffer
+struct CaptureStream {
+    source: Option<StreamHandle>,
+    stop: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Drop for CaptureStream {
+    fn drop(&mut self) {
+        // Stop the callback first, the worker then does its final drain and exits
+        self.source = None;
+        self.stop.store(true, Ordering::Release);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Where the capture worker delivers mixed-down audio
+#[derive(Clone)]
+struct CaptureTargets {
+    samples: Arc<Mutex<Vec<f32>>>,
+    pre_roll: Arc<Mutex<PreRollBuffer>>,
+    is_recording: Arc<AtomicBool>,
+    /// Set while the take is paused; checked under the samples lock
+    paused: Arc<AtomicBool>,
+    current_level: Arc<AtomicU8>,
+    meter: Arc<Mutex<LevelMeter>>,
+    events: Option<RecorderEventHandler>,
+}
+
+impl CaptureTargets {
+    /// Hand mono samples to the running take, or to the pre-roll while idle
+    fn deliver(&self, mono: &[f32]) {
+        if !self.is_recording.load(Ordering::Acquire) {
+            match self.pre_roll.lock() {
+                // start_recording flips the flag while holding this lock
+                Ok(mut pre_roll) if !self.is_recording.load(Ordering::Acquire) => {
+                    for &sample in mono {
+                        pre_roll.push(sample);
+                    }
+                    return;
+                }
+                Ok(_) => {}
+                Err(_) => return,
+            }
+        }
+
+        // pause_recording flips the flag while holding this lock, so the
+        // writer sees exactly where the pause starts
+        match self.samples.lock() {
+            Ok(_) if self.paused.load(Ordering::Acquire) => return,
+            Ok(mut samples) => samples.extend_from_slice(mono),
+            Err(_) => {}
+        }
+
+        // Calculate RMS level (0-100)
+        if !mono.is_empty() {
+            let rms = (mono.iter().map(|s| s * s).sum::<f32>() / mono.len() as f32).sqrt();
+            // Convert to 0-100 scale (assuming max amplitude of 1.0)
+            let level = (rms * 100.0 * 3.0).min(100.0) as u8; // x3 for better sensitivity
+            self.current_level.store(level, Ordering::Relaxed);
+        }
+
+        let frame = self
+            .meter
+            .lock()
+            .ok()
+            .and_then(|mut meter| meter.push(mono));
+        if let (Some(frame), Some(handler)) = (frame, &self.events) {
+            handler(RecorderEvent::Meter(frame));
+        }
+    }
+}
+
+/// Real-time side: copy interleaved samples into the ring without blocking
+///
+/// Only whole frames are written. Whatever does not fit is dropped and added
+/// to `overruns` (in frames) instead of stalling the audio thread.
+pub(crate) fn push_interleaved<T>(
+    producer: &mut Producer<f32>,
+    data: &[T],
+    channels: usize,
+    overruns: &AtomicU64,
+) where
+    T: cpal::Sample,
+    f32: cpal::FromSample<T>,
+{
+    let writable = data.len().min(producer.slots()) / channels * channels;
+    if let Ok(chunk) = producer.write_chunk_uninit(writable) {
+        chunk.fill_from_iter(data[..writable].iter().map(|s| f32::from_sample(*s)));
+    }
+
+    let dropped = (data.len() - writable) / channels;
+    if dropped > 0 {
+        overruns.fetch_add(dropped as u64, Ordering::Relaxed);
+    }
+}
+
+/// Turns interleaved frames into mono according to a `ChannelMode`
+struct ChannelMixer {
+    channels: usize,
+    mode: MixerMode,
+}
+
+enum MixerMode {
+    /// Fixed gain per channel (average, single channel or custom mix)
+    Gains(Vec<f32>),
+    /// Smoothed power per channel and the channel currently followed
+    Loudest {
+        power: Vec<f32>,
+        current: usize,
+        /// Per-frame weight of the newest power reading
+        smoothing: f32,
+    },
+}
+
+impl ChannelMixer {
+    fn new(mode: &ChannelMode, channels: usize, sample_rate: u32) -> Self {
+        let channels = channels.max(1);
+        let average = || vec![1.0 / channels as f32; channels];
+        let mode = match mode {
+            ChannelMode::Average => MixerMode::Gains(average()),
+            ChannelMode::Channel(channel) if (*channel as usize) < channels => {
+                let mut gains = vec![0.0; channels];
+                gains[*channel as usize] = 1.0;
+                MixerMode::Gains(gains)
+            }
+            ChannelMode::Channel(channel) => {
+                log::warn!(
+                    "Input channel {} not available on a {}-channel stream, averaging instead",
+                    channel + 1,
+                    channels
+                );
+                MixerMode::Gains(average())
+            }
+            ChannelMode::Mix(gains) if gains.iter().take(channels).any(|g| *g != 0.0) => {
+                let mut gains: Vec<f32> = gains.iter().copied().take(channels).collect();
+                gains.resize(channels, 0.0);
+                MixerMode::Gains(gains)
+            }
+            ChannelMode::Mix(_) => {
+                log::warn!("Channel mix is silent on this stream, averaging instead");
+                MixerMode::Gains(average())
+            }
+            ChannelMode::Loudest => MixerMode::Loudest {
+                power: vec![0.0; channels],
+                current: 0,
+                smoothing: 1.0
+                    - (-1000.0 / (LOUDEST_SMOOTHING_MS * sample_rate.max(1) as f32)).exp(),
+            },
+        };
+        Self { channels, mode }
+    }
+
+    /// Mix whole interleaved frames into `mono`
+    fn mix(&mut self, interleaved: &[f32], mono: &mut Vec<f32>) {
+        let channels = self.channels;
+        let frames = interleaved.chunks_exact(channels);
+        match &mut self.mode {
+            MixerMode::Gains(gains) => {
+                mono.extend(frames.map(|frame| {
+                    frame
+                        .iter()
+                        .zip(gains.iter())
+                        .map(|(s, g)| s * g)
+                        .sum::<f32>()
+                }));
+            }
+            MixerMode::Loudest {
+                power,
+                current,
+                smoothing,
+            } => {
+                let frame_count = interleaved.len() / channels;
+                if frame_count == 0 {
+                    return;
+                }
+                for (channel, level) in power.iter_mut().enumerate() {
+                    let chunk_power = interleaved[channel..]
+                        .iter()
+                        .step_by(channels)
+                        .map(|s| s * s)
+                        .sum::<f32>()
+                        / frame_count as f32;
+                    let weight = 1.0 - (1.0 - *smoothing).powi(frame_count as i32);
+                    *level += weight * (chunk_power - *level);
+                }
+
+                // Hysteresis keeps two similar channels from trading places
+                let previous = *current;
+                let loudest = (0..channels)
+                    .max_by(|a, b| power[*a].total_cmp(&power[*b]))
+                    .unwrap_or(previous);
+                if power[loudest] > power[previous] * LOUDEST_SWITCH_RATIO {
+                    *current = loudest;
+                }
+
+                // Crossfade over the chunk of a switch so it does not click
+                let next = *current;
+                mono.extend(frames.enumerate().map(|(i, frame)| {
+                    if next == previous {
+                        frame[next]
+                    } else {
+                        let t = (i + 1) as f32 / frame_count as f32;
+                        frame[previous] * (1.0 - t) + frame[next] * t
+                    }
+                }));
+            }
+        }
+    }
+}
+
+/// Worker side: mix the ring down to mono and deliver it until the stream is gone
+///
+/// With a `resampler` the audio is converted to the rate of the running take,
+/// used when a replacement device runs at a different rate.
+fn run_capture_worker(
+    mut consumer: Consumer<f32>,
+    mut mixer: ChannelMixer,
+    targets: CaptureTargets,
+    stop: Arc<AtomicBool>,
+    mut resampler: Option<StreamingResampler>,
+) {
+    let channels = mixer.channels;
+    let mut interleaved = Vec::new();
+    let mut mono = Vec::new();
+    loop {
+        // Checked before draining, so the drain after the callback is gone is the last one
+        let finished = consumer.is_abandoned() || stop.load(Ordering::Acquire);
+
+        let available = consumer.slots() / channels * channels;
+        if available > 0 {
+            if let Ok(chunk) = consumer.read_chunk(available) {
+                let (first, second) = chunk.as_slices();
+                interleaved.clear();
+                interleaved.extend_from_slice(first);
+                interleaved.extend_from_slice(second);
+                chunk.commit_all();
+                mono.clear();
+                mixer.mix(&interleaved, &mut mono);
+            }
+            match resampler.as_mut() {
+                Some(resampler) => match resampler.process(&mono) {
+                    Ok(resampled) => targets.deliver(&resampled),
+                    Err(e) => log::warn!("Capture resampling failed: {}", e),
+                },
+                None => targets.deliver(&mono),
+            }
+        } else if finished {
+            if let Some(Ok(tail)) = resampler.map(StreamingResampler::finish) {
+                targets.deliver(&tail);
+            }
+            return;
+        } else {
+            thread::sleep(CAPTURE_POLL_INTERVAL);
+        }
+    }
+}
+
+/// Start the worker that drains a started source
+fn spawn_capture_worker(
+    source: RunningSource,
+    mixer: ChannelMixer,
+    targets: CaptureTargets,
+    resampler: Option<StreamingResampler>,
+) -> CaptureStream {
+    let stop = Arc::new(AtomicBool::new(false));
+    let worker_stop = Arc::clone(&stop);
+    let consumer = source.consumer;
+    let worker =
+        thread::spawn(move || run_capture_worker(consumer, mixer, targets, worker_stop, resampler));
+
+    // From here on dropping the capture stream also shuts the worker down
+    CaptureStream {
+        source: Some(source.handle),
+        stop,
+        worker: Some(worker),
+    }
+}
+
+/// Ring of the most recent mono samples captured while no take is running
+///
+/// Only used by the warm microphone. The storage is allocated once, so the
+/// capture callback never reallocates; with a capacity of 0 it keeps nothing.
+struct PreRollBuffer {
+    samples: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl PreRollBuffer {
+    fn new() -> Self {
+        Self {
+            samples: VecDeque::new(),
+            capacity: 0,
+        }
+    }
+
+    /// Drop everything and resize to `capacity` frames
+    fn reset(&mut self, capacity: usize) {
+        self.samples = VecDeque::with_capacity(capacity);
+        self.capacity = capacity;
+    }
+
+    fn push(&mut self, sample: f32) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Move the buffered audio out, oldest sample first
+    fn drain_into(&mut self, target: &mut Vec<f32>) {
+        target.extend(self.samples.drain(..));
+    }
+}
+
+/// Lines system audio up with the microphone in the writer thread
+///
+/// The microphone is the clock: each of its chunks takes as many system
+/// samples. Missing system audio (loopback devices deliver nothing while the
+/// system is silent) becomes silence, and a backlog beyond
+/// `SYSTEM_AUDIO_MAX_LAG_MS` is dropped so the two streams cannot drift apart.
+struct SystemAudioAligner {
+    pending: VecDeque<f32>,
+    max_lag: usize,
+}
+
+impl SystemAudioAligner {
+    fn new(sample_rate: u32) -> Self {
+        Self {
+            pending: VecDeque::new(),
+            max_lag: (SYSTEM_AUDIO_MAX_LAG_MS * sample_rate as u64 / 1000) as usize,
+        }
+    }
+
+    /// Queue silence, for microphone audio from before the system audio started
+    fn pad(&mut self, frames: usize) {
+        self.pending.resize(self.pending.len() + frames, 0.0);
+    }
+
+    fn push(&mut self, samples: &[f32]) {
+        self.pending.extend(samples);
+    }
+
+    /// System audio for the next `len` microphone samples
+    fn take(&mut self, len: usize) -> Vec<f32> {
+        let excess = self.pending.len().saturating_sub(len + self.max_lag);
+        self.pending.drain(..excess);
+
+        let mut taken: Vec<f32> = self.pending.drain(..len.min(self.pending.len())).collect();
+        taken.resize(len, 0.0);
+        taken
+    }
 }
 
-impl serde::Serialize for AudioError {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_str(&self.to_string())
+/// Separate file the system audio of a take is written to
+struct SystemTrackWriter {
+    path: PathBuf,
+    writer: StreamingWavWriter,
+    vad: VoiceActivityDetector,
+}
+
+/// System audio side of a running take
+struct SystemTake {
+    aligner: SystemAudioAligner,
+    /// Separate track, None when the system audio is mixed into the take
+    track: Option<SystemTrackWriter>,
+}
+
+impl SystemTake {
+    /// Close the separate track, returning its sample count and speech
+    fn finish(self) -> Option<(u64, Vec<SpeechSegment>)> {
+        let track = self.track?;
+        match track.writer.finalize() {
+            Ok(samples_written) => Some((samples_written, track.vad.finish())),
+            Err(e) => {
+                log::error!("Failed to finalize system audio track: {}", e);
+                let _ = fs::remove_file(&track.path);
+                None
+            }
+        }
     }
 }
 
+/// What the writer thread hands back when a take ends
+struct TakeSummary {
+    samples_written: u64,
+    speech_segments: Vec<SpeechSegment>,
+    device_gaps: Vec<DeviceGap>,
+    pause_markers: Vec<PauseMarker>,
+    /// Statistics of the raw input, before resampling and preprocessing
+    quality: QualityAnalyzer,
+    /// Samples and speech of the separate system audio track
+    system_track: Option<(u64, Vec<SpeechSegment>)>,
+}
+
+/// A take that is currently being written to disk
+struct RecordingSession {
+    /// Path of the WAV file being written
+    file_path: PathBuf,
+    /// Path of the separate system audio track, if one is written
+    system_path: Option<PathBuf>,
+    /// Tells the writer thread to drain the remaining samples and finalize
+    stop: Arc<AtomicBool>,
+    /// Writer thread, returns the summary of the finished take
+    writer: JoinHandle<Result<TakeSummary, AudioError>>,
+}
+
 /// Audio recorder state
 pub struct AudioRecorder {
     host: Host,
-    stream: SendableStream,
+    stream: Option<CaptureStream>,
+    /// Captured mono samples not yet picked up by the writer thread
     samples: Arc<Mutex<Vec<f32>>>,
+    /// Warm microphone audio from before the hotkey, prepended to the next take
+    pre_roll: Arc<Mutex<PreRollBuffer>>,
+    /// Frames dropped by the capture callback since the take started
+    overruns: Arc<AtomicU64>,
+    /// Set while the running take is paused
+    paused: Arc<AtomicBool>,
+    /// Level meter of the running take
+    meter: Arc<Mutex<LevelMeter>>,
+    /// Configuration of the open input stream
+    capture_config: Option<CaptureConfig>,
+    /// Set by the stream error callback when the device fails during a take
+    device_lost: Arc<AtomicBool>,
+    /// Name of the device the open stream captures from
+    device_name: Option<String>,
+    /// Last known config fingerprint per device slot, for devices that cannot
+    /// be probed while busy
+    fingerprints: Mutex<HashMap<String, String>>,
     is_recording: Arc<AtomicBool>,
     current_level: Arc<AtomicU8>,
     source_sample_rate: u32,
-    recording_start: Option<std::time::Instant>,
     settings: AudioSettings,
     /// Stream error (set when device disconnects or other stream errors occur)
     stream_error: Arc<Mutex<Option<String>>>,
+    /// Take currently being streamed to disk
+    session: Option<RecordingSession>,
+    /// Receives limit warnings and auto-stop notifications
+    event_handler: Option<RecorderEventHandler>,
+    /// Whether `silence_auto_stop_seconds` applies to the next take (toggle mode only)
+    silence_auto_stop: bool,
+    /// Monitor or loopback stream, open only while a take records system audio
+    system_stream: Option<CaptureStream>,
+    /// Captured system audio not yet picked up by the writer thread
+    system_samples: Arc<Mutex<Vec<f32>>>,
+    /// Replaces the microphone, e.g. a WAV replay or a synthetic signal
+    source: Option<Box<dyn AudioSource>>,
+    /// Receives the processed audio of the next take while it records
+    live_tap: Option<LiveAudio>,
 }
 
 impl AudioRecorder {
@@ -127,44 +1761,213 @@ impl AudioRecorder {
         let host = cpal::default_host();
         Self {
             host,
-            stream: SendableStream(None),
+            stream: None,
             samples: Arc::new(Mutex::new(Vec::new())),
+            pre_roll: Arc::new(Mutex::new(PreRollBuffer::new())),
+            overruns: Arc::new(AtomicU64::new(0)),
+            paused: Arc::new(AtomicBool::new(false)),
+            meter: Arc::new(Mutex::new(LevelMeter::new(WHISPER_SAMPLE_RATE, false))),
+            capture_config: None,
+            device_lost: Arc::new(AtomicBool::new(false)),
+            device_name: None,
+            fingerprints: Mutex::new(HashMap::new()),
             is_recording: Arc::new(AtomicBool::new(false)),
             current_level: Arc::new(AtomicU8::new(0)),
             source_sample_rate: 44100, // Will be updated when recording starts
-            recording_start: None,
             settings: AudioSettings::default(),
             stream_error: Arc::new(Mutex::new(None)),
+            session: None,
+            event_handler: None,
+            silence_auto_stop: false,
+            system_stream: None,
+            system_samples: Arc::new(Mutex::new(Vec::new())),
+            source: None,
+            live_tap: None,
+        }
+    }
+
+    /// Create a recorder that captures from `source` instead of a microphone
+    pub fn with_source(source: Box<dyn AudioSource>) -> Self {
+        let mut recorder = Self::new();
+        recorder.set_source(Some(source));
+        recorder
+    }
+
+    /// Capture from `source` instead of the configured device, `None` goes back to it
+    ///
+    /// Takes effect when the next stream is opened.
+    pub fn set_source(&mut self, source: Option<Box<dyn AudioSource>>) {
+        self.source = source;
+        // A warm stream still reads from the previous source
+        if self.session.is_none() {
+            self.stream = None;
         }
     }
 
+    /// Hand the processed audio of the next take to `tap` while it records
+    ///
+    /// Used once: the tap is attached to the take that starts next.
+    pub fn set_live_tap(&mut self, tap: Option<LiveAudio>) {
+        self.live_tap = tap;
+    }
+
+    /// Set the callback that receives recorder events
+    pub fn set_event_handler(&mut self, handler: RecorderEventHandler) {
+        self.event_handler = Some(handler);
+    }
+
+    /// Enable the silence auto-stop for upcoming takes
+    ///
+    /// Only meant for toggle mode; in push-to-talk the key release ends the take.
+    pub fn set_silence_auto_stop(&mut self, enabled: bool) {
+        self.silence_auto_stop = enabled;
+    }
+
     /// Update audio settings
+    ///
+    /// Call `sync_warm_mic` afterwards to apply `warm_mic` changes.
     pub fn update_settings(&mut self, settings: AudioSettings) {
         self.settings = settings;
     }
 
+    /// Open or release the idle input stream to match `warm_mic`
+    ///
+    /// The stream is reopened when enabled so device and pre-roll changes apply.
+    /// While a take is open nothing changes; `stop_recording` applies the setting.
+    pub fn sync_warm_mic(&mut self) -> Result<(), AudioError> {
+        if self.has_active_take() {
+            return Ok(());
+        }
+
+        if self.stream.take().is_some() {
+            log::info!("Warm microphone released");
+        }
+        self.reset_pre_roll();
+        if !self.settings.warm_mic {
+            return Ok(());
+        }
+
+        self.clear_stream_error();
+        let stream = self.open_stream()?;
+        self.stream = Some(stream);
+        log::info!(
+            "Warm microphone active, keeping {}ms of pre-roll",
+            frames_to_ms(
+                self.settings.pre_roll_frames(self.source_sample_rate) as u64,
+                self.source_sample_rate
+            )
+        );
+        Ok(())
+    }
+
+    /// Check if the warm microphone currently holds the input stream open
+    pub fn is_warm(&self) -> bool {
+        self.settings.warm_mic && self.stream.is_some() && !self.has_stream_error()
+    }
+
+    /// Discard the pre-roll and size it for the current settings and device rate
+    fn reset_pre_roll(&self) {
+        let capacity = self.settings.pre_roll_frames(self.source_sample_rate);
+        if let Ok(mut pre_roll) = self.pre_roll.lock() {
+            pre_roll.reset(capacity);
+        }
+    }
+
     /// Get current settings
     pub fn get_settings(&self) -> &AudioSettings {
         &self.settings
     }
 
+    /// Rewrite legacy name-only device ids in the settings to stable identities
+    ///
+    /// Ids of devices that are not connected stay as they are (they still
+    /// resolve by name) and are migrated on a later start. Returns whether
+    /// the settings changed and need to be saved.
+    pub fn migrate_device_ids(&mut self) -> bool {
+        let identities: Vec<DeviceIdentity> = match self.enumerate_devices() {
+            Ok(devices) => devices.into_iter().map(|(identity, _)| identity).collect(),
+            Err(_) => return false,
+        };
+
+        let mut changed = false;
+        if let Some(id) = self
+            .settings
+            .device_id
+            .as_deref()
+            .and_then(|id| migrate_device_id(&identities, id))
+        {
+            log::info!("Migrated microphone setting to device id {}", id);
+            self.settings.device_id = Some(id);
+            changed = true;
+        }
+        for entry in &mut self.settings.device_priority {
+            if let Some(id) = migrate_device_id(&identities, entry) {
+                *entry = id;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Enumerate input devices together with their stable identities
+    fn enumerate_devices(&self) -> Result<Vec<(DeviceIdentity, Device)>, AudioError> {
+        let host = self.host.id().name().to_string();
+        let mut fingerprints = self.fingerprints.lock().unwrap();
+        let mut devices: Vec<(DeviceIdentity, Device)> = Vec::new();
+
+        for device in self
+            .host
+            .input_devices()
+            .map_err(|e| AudioError::ConfigError(e.to_string()))?
+        {
+            let Ok(name) = device.name() else {
+                continue;
+            };
+            let index = devices.iter().filter(|(i, _)| i.name == name).count();
+
+            // A device that is busy (e.g. our own open stream on ALSA) cannot
+            // be probed, so keep the fingerprint from the last time it could
+            let slot = format!("{}:{}", index, name);
+            let fingerprint = match config_fingerprint(&device) {
+                Some(fingerprint) => {
+                    fingerprints.insert(slot, fingerprint.clone());
+                    fingerprint
+                }
+                None => fingerprints
+                    .get(&slot)
+                    .cloned()
+                    .unwrap_or_else(|| UNKNOWN_FINGERPRINT.to_string()),
+            };
+
+            devices.push((
+                DeviceIdentity {
+                    host: host.clone(),
+                    index,
+                    fingerprint,
+                    name,
+                },
+                device,
+            ));
+        }
+        Ok(devices)
+    }
+
     /// List available audio input devices
     pub fn list_devices(&self) -> Result<Vec<AudioDevice>, AudioError> {
         let default_device = self.host.default_input_device();
         let default_name = default_device.as_ref().and_then(|d| d.name().ok());
 
         let devices: Vec<AudioDevice> = self
-            .host
-            .input_devices()
-            .map_err(|e| AudioError::ConfigError(e.to_string()))?
-            .filter_map(|device| {
-                let name = device.name().ok()?;
-                let is_default = default_name.as_ref().map(|d| d == &name).unwrap_or(false);
-                Some(AudioDevice {
-                    id: name.clone(),
-                    name,
-                    is_default,
-                })
+            .enumerate_devices()?
+            .into_iter()
+            .map(|(identity, device)| AudioDevice {
+                id: identity.id(),
+                channels: max_input_channels(&device),
+                // The system default is reported by name only; the first of
+                // several identical devices stands in for it
+                is_default: identity.index == 0 && default_name.as_ref() == Some(&identity.name),
+                name: identity.name,
+                host: identity.host,
             })
             .collect();
 
@@ -175,15 +1978,133 @@ impl AudioRecorder {
         Ok(devices)
     }
 
+    /// Enumerate sources that capture what the system plays
+    ///
+    /// These are input devices named like a monitor or loopback device and, on
+    /// WASAPI, every output device, which cpal records in loopback mode. The
+    /// flag marks output devices.
+    fn enumerate_system_audio_sources(
+        &self,
+    ) -> Result<Vec<(DeviceIdentity, Device, bool)>, AudioError> {
+        let mut sources: Vec<(DeviceIdentity, Device, bool)> = self
+            .enumerate_devices()?
+            .into_iter()
+            .filter(|(identity, _)| is_loopback_name(&identity.name))
+            .map(|(identity, device)| (identity, device, false))
+            .collect();
+
+        let host = self.host.id().name().to_string();
+        if host == "WASAPI" {
+            for device in self
+                .host
+                .output_devices()
+                .map_err(|e| AudioError::ConfigError(e.to_string()))?
+            {
+                let Ok(name) = device.name() else {
+                    continue;
+                };
+                let index = sources.iter().filter(|(i, _, _)| i.name == name).count();
+                sources.push((
+                    DeviceIdentity {
+                        host: host.clone(),
+                        index,
+                        fingerprint: UNKNOWN_FINGERPRINT.to_string(),
+                        name,
+                    },
+                    device,
+                    true,
+                ));
+            }
+        }
+        Ok(sources)
+    }
+
+    /// List sources system audio can be recorded from (may be empty)
+    pub fn list_system_audio_sources(&self) -> Result<Vec<AudioDevice>, AudioError> {
+        let default_output = self
+            .host
+            .default_output_device()
+            .and_then(|d| d.name().ok());
+
+        Ok(self
+            .enumerate_system_audio_sources()?
+            .into_iter()
+            .map(|(identity, device, output)| AudioDevice {
+                id: identity.id(),
+                channels: if output {
+                    device
+                        .default_output_config()
+                        .map_or(0, |config| config.channels())
+                } else {
+                    max_input_channels(&device)
+                },
+                is_default: output
+                    && identity.index == 0
+                    && default_output.as_ref() == Some(&identity.name),
+                name: identity.name,
+                host: identity.host,
+            })
+            .collect())
+    }
+
+    /// Get the source to record system audio from
+    ///
+    /// Without a selection this is the loopback of the default output device,
+    /// or else the first monitor source.
+    fn get_system_audio_device(&self) -> Result<(DeviceIdentity, Device, bool), AudioError> {
+        let mut sources = self.enumerate_system_audio_sources()?;
+        let identities: Vec<DeviceIdentity> = sources
+            .iter()
+            .map(|(identity, _, _)| identity.clone())
+            .collect();
+
+        let pos = match &self.settings.system_audio_device {
+            Some(id) => resolve_device(&identities, id)
+                .ok_or_else(|| AudioError::DeviceNotFound(id.clone()))?,
+            None => {
+                let default_output = self
+                    .host
+                    .default_output_device()
+                    .and_then(|d| d.name().ok());
+                sources
+                    .iter()
+                    .position(|(identity, _, output)| {
+                        *output && default_output.as_ref() == Some(&identity.name)
+                    })
+                    .or((!sources.is_empty()).then_some(0))
+                    .ok_or_else(|| {
+                        AudioError::DeviceNotFound("no monitor or loopback source".into())
+                    })?
+            }
+        };
+        Ok(sources.swap_remove(pos))
+    }
+
     /// Get the device to use for recording
+    ///
+    /// A missing selected device falls back to the first present entry of
+    /// `device_priority`.
     fn get_device(&self) -> Result<Device, AudioError> {
         if let Some(device_id) = &self.settings.device_id {
-            // Find specific device
-            self.host
-                .input_devices()
-                .map_err(|e| AudioError::ConfigError(e.to_string()))?
-                .find(|d| d.name().ok().as_ref() == Some(device_id))
-                .ok_or_else(|| AudioError::DeviceNotFound(device_id.clone()))
+            let mut devices = self.enumerate_devices()?;
+            let identities: Vec<DeviceIdentity> = devices
+                .iter()
+                .map(|(identity, _)| identity.clone())
+                .collect();
+
+            if let Some(pos) = resolve_device(&identities, device_id) {
+                return Ok(devices.swap_remove(pos).1);
+            }
+            for id in &self.settings.device_priority {
+                if let Some(pos) = resolve_device(&identities, id) {
+                    log::info!(
+                        "Selected microphone not found, using {} from the priority list",
+                        identities[pos].name
+                    );
+                    return Ok(devices.swap_remove(pos).1);
+                }
+            }
+            Err(AudioError::DeviceNotFound(device_id.clone()))
         } else {
             // Use default device
             self.host
@@ -236,16 +2157,45 @@ impl AudioRecorder {
         }
     }
 
-    /// Get current audio level (0-100)
-    pub fn get_level(&self) -> AudioLevel {
-        self.current_level.load(Ordering::Relaxed)
-    }
-
     /// Check if currently recording
     pub fn is_recording(&self) -> bool {
         self.is_recording.load(Ordering::Relaxed)
     }
 
+    /// Check if the running take is paused
+    pub fn is_paused(&self) -> bool {
+        self.is_recording() && self.paused.load(Ordering::Acquire)
+    }
+
+    /// Pause the running take
+    ///
+    /// The stream stays open; captured audio is discarded until
+    /// `resume_recording`, so the pause is neither in the file nor in
+    /// `duration_ms`. The writer records a `PauseMarker` at the spot.
+    pub fn pause_recording(&mut self) -> Result<(), AudioError> {
+        if !self.is_recording() {
+            return Err(AudioError::NotRecording);
+        }
+        let _samples = self.samples.lock().unwrap();
+        if !self.paused.swap(true, Ordering::AcqRel) {
+            self.current_level.store(0, Ordering::Relaxed);
+            log::info!("Recording paused");
+        }
+        Ok(())
+    }
+
+    /// Continue a paused take
+    pub fn resume_recording(&mut self) -> Result<(), AudioError> {
+        if !self.is_recording() {
+            return Err(AudioError::NotRecording);
+        }
+        let _samples = self.samples.lock().unwrap();
+        if self.paused.swap(false, Ordering::AcqRel) {
+            log::info!("Recording resumed");
+        }
+        Ok(())
+    }
+
     /// Get stream error if any (BUG-2 fix: Device disconnect handling)
     pub fn get_stream_error(&self) -> Option<String> {
         self.stream_error.lock().ok().and_then(|e| e.clone())
@@ -273,250 +2223,834 @@ impl AudioRecorder {
             return Ok(()); // Already recording
         }
 
+        // The take is sealed when it ends, which needs the key
+        if encryption::is_locked() {
+            return Err(AudioError::Encryption(EncryptionError::Locked.to_string()));
+        }
+
+        // A take whose stream died but that was never stopped: close it so the
+        // partial recording stays on disk instead of being overwritten
+        if self.has_active_take() {
+            match self.stop_recording() {
+                Ok(result) => log::warn!("Finalized interrupted recording: {}", result.file_path),
+                Err(e) => log::warn!("Failed to finalize interrupted recording: {}", e),
+            }
+        }
+
+        // A warm stream that died while idle is useless, open a fresh one
+        if self.has_stream_error() {
+            self.stream = None;
+        }
+
         // Clear any previous stream error (BUG-2 fix)
         self.clear_stream_error();
 
         // Check disk space
         Self::check_disk_space()?;
 
-        let device = self.get_device()?;
-        let config = device
-            .default_input_config()
-            .map_err(|e| AudioError::ConfigError(e.to_string()))?;
+        // Reuse the warm stream, its pre-roll already holds the last moments
+        let warm = self.stream.is_some();
+        if !warm {
+            let stream = self.open_stream()?;
+            self.stream = Some(stream);
+        }
 
-        self.source_sample_rate = config.sample_rate().0;
-        log::info!(
-            "Starting recording with sample rate: {} Hz",
-            self.source_sample_rate
-        );
+        // Create the WAV file before capture starts so disk errors surface first
+        let file_path = Self::get_recordings_dir().join(format!("{}.wav", Uuid::new_v4()));
+        let writer = match StreamingWavWriter::create(&file_path, self.source_sample_rate) {
+            Ok(writer) => {
+                let writer = writer.with_dsp(self.settings.dsp_chain());
+                match self.live_tap.take() {
+                    Some(tap) => writer.with_tap(tap),
+                    None => writer,
+                }
+            }
+            Err(e) => {
+                if !warm {
+                    self.stream = None;
+                }
+                return Err(e);
+            }
+        };
 
-        // Clear previous samples
-        {
+        let mut system = self.start_system_audio(&file_path);
+
+        // The journal keeps the take and its system track from being cleaned
+        // up until they were processed, so they survive a crash
+        let journal = TakeJournal {
+            started_at: chrono::Local::now().to_rfc3339(),
+            device_name: self.device_name.clone(),
+            privacy_mode: self.settings.privacy_mode,
+            system_track: system.as_ref().is_some_and(|system| system.track.is_some()),
+        };
+        if let Err(e) = recovery::write_journal(&file_path, &journal) {
+            log::warn!("Take will not be recoverable after a crash: {}", e);
+        }
+
+        // Prepend the pre-roll and start capturing in one step: the capture worker
+        // re-checks `is_recording` under the pre-roll lock, so no sample is
+        // lost or duplicated at the seam
+        self.overruns.store(0, Ordering::Relaxed);
+        self.device_lost.store(false, Ordering::Release);
+        self.paused.store(false, Ordering::Release);
+        *self.meter.lock().unwrap() =
+            LevelMeter::new(self.source_sample_rate, self.settings.meter_spectrum);
+        let pre_roll_frames = {
+            let mut pre_roll = self.pre_roll.lock().unwrap();
             let mut samples = self.samples.lock().unwrap();
             samples.clear();
+            pre_roll.drain_into(&mut samples);
+            self.system_samples.lock().unwrap().clear();
+            self.is_recording.store(true, Ordering::Release);
+            samples.len() as u64
+        };
+        if pre_roll_frames > 0 {
+            log::info!(
+                "Prepending {}ms of warm microphone pre-roll",
+                frames_to_ms(pre_roll_frames, self.source_sample_rate)
+            );
+        }
+        // The system audio has no pre-roll, it starts with the take
+        if let Some(system) = system.as_mut() {
+            system.aligner.pad(pre_roll_frames as usize);
         }
 
-        let samples = Arc::clone(&self.samples);
-        let is_recording = Arc::clone(&self.is_recording);
-        let current_level = Arc::clone(&self.current_level);
+        self.session = Some(self.spawn_writer(writer, file_path, system));
+
+        log::info!("Recording started");
+        Ok(())
+    }
+
+    /// Open and start an input stream on the configured source
+    ///
+    /// That is the custom source if one is set, otherwise the configured device.
+    fn open_stream(&mut self) -> Result<CaptureStream, AudioError> {
+        match self.source.take() {
+            Some(source) => {
+                let (source, capture) = self.start_source(source, None);
+                self.source = Some(source);
+                capture
+            }
+            None => {
+                let device = self.get_device()?;
+                self.start_source(Box::new(CpalSource::new(device)), None).1
+            }
+        }
+    }
+
+    /// Start `source` and the worker that drains it
+    ///
+    /// With `take_rate` the source rate of the running take is kept and the
+    /// worker resamples if the source has to run at another rate. The source
+    /// only copies samples into a lock-free ring; the worker mixes them down
+    /// and delivers them. Until `is_recording` is set they go to the pre-roll,
+    /// which keeps nothing unless the warm microphone is enabled.
+    ///
+    /// The source runs on the audio thread and is handed back either way.
+    fn start_source(
+        &mut self,
+        source: Box<dyn AudioSource>,
+        take_rate: Option<u32>,
+    ) -> (Box<dyn AudioSource>, Result<CaptureStream, AudioError>) {
+        let min_channels = self
+            .settings
+            .channel_mode
+            .required_channels(source.max_channels().max(1));
+        let (source, started) = audio_source::start_on_audio_thread(
+            source,
+            min_channels,
+            Arc::clone(&self.overruns),
+            self.stream_error_handler(),
+        );
+        let capture =
+            started.and_then(|started| self.drain_source(source.name(), started, take_rate));
+        (source, capture)
+    }
+
+    /// Start the worker that drains the started source `name`
+    fn drain_source(
+        &mut self,
+        name: String,
+        started: RunningSource,
+        take_rate: Option<u32>,
+    ) -> Result<CaptureStream, AudioError> {
+        let config = started.config.clone();
+        log::info!(
+            "Capturing from {}: {} Hz, {} channel(s), {}",
+            name,
+            config.sample_rate,
+            config.channels,
+            config.sample_format
+        );
+        let resampler = match take_rate {
+            Some(rate) if rate != config.sample_rate => {
+                Some(StreamingResampler::new(config.sample_rate, rate)?)
+            }
+            _ => None,
+        };
+        self.source_sample_rate = take_rate.unwrap_or(config.sample_rate);
+        self.reset_pre_roll();
+
+        let targets = CaptureTargets {
+            samples: Arc::clone(&self.samples),
+            pre_roll: Arc::clone(&self.pre_roll),
+            is_recording: Arc::clone(&self.is_recording),
+            paused: Arc::clone(&self.paused),
+            current_level: Arc::clone(&self.current_level),
+            meter: Arc::clone(&self.meter),
+            events: self.event_handler.clone(),
+        };
+        let mixer = ChannelMixer::new(
+            &self.settings.channel_mode,
+            config.channels as usize,
+            config.sample_rate,
+        );
+        let capture = spawn_capture_worker(started, mixer, targets, resampler);
+        self.capture_config = Some(config);
+        self.device_name = Some(name);
+        Ok(capture)
+    }
+
+    /// Open the system audio source for a new take and prepare its side of it
+    ///
+    /// Any failure leaves the take with the microphone only and is reported
+    /// as `SystemAudioUnavailable`.
+    fn start_system_audio(&mut self, file_path: &Path) -> Option<SystemTake> {
+        let mode = self.settings.system_audio;
+        if mode == SystemAudioMode::Off {
+            return None;
+        }
+
+        let opened = self.open_system_stream().and_then(|capture| {
+            let track = match mode {
+                SystemAudioMode::SeparateTrack => {
+                    let path = system_track_path(file_path);
+                    let writer = StreamingWavWriter::create(&path, self.source_sample_rate)?
+                        .with_dsp(self.settings.dsp_chain());
+                    Some(SystemTrackWriter {
+                        path,
+                        writer,
+                        vad: VoiceActivityDetector::new(self.source_sample_rate),
+                    })
+                }
+                _ => None,
+            };
+            Ok((capture, track))
+        });
+
+        match opened {
+            Ok((capture, track)) => {
+                self.system_stream = Some(capture);
+                Some(SystemTake {
+                    aligner: SystemAudioAligner::new(self.source_sample_rate),
+                    track,
+                })
+            }
+            Err(e) => {
+                log::warn!("Recording without system audio: {}", e);
+                if let Some(handler) = &self.event_handler {
+                    handler(RecorderEvent::SystemAudioUnavailable {
+                        reason: e.to_string(),
+                    });
+                }
+                None
+            }
+        }
+    }
 
-        // Create stream based on sample format
-        let stream = match config.sample_format() {
-            SampleFormat::F32 => self.build_stream::<f32>(
-                &device,
-                &config.into(),
-                samples,
-                is_recording.clone(),
-                current_level,
-            )?,
-            SampleFormat::I16 => self.build_stream::<i16>(
-                &device,
-                &config.into(),
-                samples,
-                is_recording.clone(),
-                current_level,
-            )?,
-            SampleFormat::U16 => self.build_stream::<u16>(
-                &device,
-                &config.into(),
-                samples,
-                is_recording.clone(),
-                current_level,
-            )?,
-            _ => return Err(AudioError::ConfigError("Unsupported sample format".into())),
+    /// Open the configured monitor or loopback source
+    ///
+    /// Its audio is resampled to the rate of the take and collected in
+    /// `system_samples` while the take runs.
+    fn open_system_stream(&self) -> Result<CaptureStream, AudioError> {
+        let (identity, device, output) = self.get_system_audio_device()?;
+        let source = if output {
+            CpalSource::loopback(device)
+        } else {
+            CpalSource::new(device)
         };
 
-        stream
-            .play()
-            .map_err(|e| AudioError::StreamError(e.to_string()))?;
+        // A failing loopback source must not fail the take over to another
+        // microphone: the take goes on and the missing audio becomes silence
+        let (_, started) = audio_source::start_on_audio_thread(
+            Box::new(source),
+            1,
+            Arc::new(AtomicU64::new(0)),
+            Arc::new(|error| log::warn!("System audio stream error: {}", error)),
+        );
+        let started = started?;
+        log::info!("Capturing system audio from {}", identity.name);
 
-        self.stream.0 = Some(stream);
-        self.is_recording.store(true, Ordering::Relaxed);
-        self.recording_start = Some(std::time::Instant::now());
+        let take_rate = self.source_sample_rate;
+        let device_rate = started.config.sample_rate;
+        let resampler = if device_rate != take_rate {
+            Some(StreamingResampler::new(device_rate, take_rate)?)
+        } else {
+            None
+        };
 
-        log::info!("Recording started");
-        Ok(())
+        // Level and meter keep showing the microphone only
+        let targets = CaptureTargets {
+            samples: Arc::clone(&self.system_samples),
+            pre_roll: Arc::new(Mutex::new(PreRollBuffer::new())),
+            is_recording: Arc::clone(&self.is_recording),
+            paused: Arc::clone(&self.paused),
+            current_level: Arc::new(AtomicU8::new(0)),
+            meter: Arc::new(Mutex::new(LevelMeter::new(take_rate, false))),
+            events: None,
+        };
+        let mixer = ChannelMixer::new(
+            &ChannelMode::Average,
+            started.config.channels as usize,
+            device_rate,
+        );
+        Ok(spawn_capture_worker(started, mixer, targets, resampler))
     }
 
-    /// Build the audio input stream
-    fn build_stream<T: cpal::Sample + cpal::SizedSample + Send + 'static>(
-        &self,
-        device: &Device,
-        config: &StreamConfig,
-        samples: Arc<Mutex<Vec<f32>>>,
-        is_recording: Arc<AtomicBool>,
-        current_level: Arc<AtomicU8>,
-    ) -> Result<Stream, AudioError>
-    where
-        f32: cpal::FromSample<T>,
-    {
-        let channels = config.channels as usize;
+    /// Candidate devices to continue a take on, in order of preference
+    ///
+    /// The configured device comes first (it may have reconnected), then the
+    /// `device_priority` list, then the system default, then every other input.
+    fn failover_devices(&self) -> Vec<Device> {
+        // A custom source has no replacement
+        if self.source.is_some() {
+            return Vec::new();
+        }
+        let Ok(present) = self.enumerate_devices() else {
+            return self.host.default_input_device().into_iter().collect();
+        };
+        let identities: Vec<DeviceIdentity> = present
+            .iter()
+            .map(|(identity, _)| identity.clone())
+            .collect();
 
-        // Clone Arcs for error callback (BUG-2 fix: Device disconnect handling)
-        let is_recording_err = Arc::clone(&is_recording);
-        let stream_error = Arc::clone(&self.stream_error);
+        let mut order: Vec<usize> = self
+            .settings
+            .device_id
+            .iter()
+            .chain(&self.settings.device_priority)
+            .filter_map(|id| resolve_device(&identities, id))
+            .collect();
+        if let Some(name) = self.host.default_input_device().and_then(|d| d.name().ok()) {
+            order.extend(identities.iter().position(|i| i.name == name));
+        }
+        order.extend(0..identities.len());
+
+        // Each device is taken once, at its first position in the order
+        let mut slots: Vec<Option<Device>> = present.into_iter().map(|(_, d)| Some(d)).collect();
+        order
+            .into_iter()
+            .filter_map(|pos| slots[pos].take())
+            .collect()
+    }
 
-        let err_fn = move |err: cpal::StreamError| {
-            log::error!("Audio stream error: {}", err);
+    /// Move the running take to another input device after the current one failed
+    ///
+    /// The take keeps appending to the same file and a `DeviceGap` marks the spot.
+    /// Returns the new device name, or None if there is nothing to recover.
+    pub fn fail_over(&mut self) -> Result<Option<String>, AudioError> {
+        if !self.has_active_take()
+            || !self.is_recording()
+            || !self.device_lost.load(Ordering::Acquire)
+        {
+            return Ok(None);
+        }
 
-            // Store the error for later retrieval
-            if let Ok(mut error_lock) = stream_error.lock() {
-                *error_lock = Some(format!("Stream error: {}", err));
+        // Release the dead stream, its worker hands over what it still had
+        self.stream = None;
+
+        let take_rate = self.source_sample_rate;
+        let mut last_error = AudioError::NoDevicesFound;
+        for device in self.failover_devices() {
+            let device_name = device.name().ok();
+            match self
+                .start_source(Box::new(CpalSource::new(device)), Some(take_rate))
+                .1
+            {
+                Ok(capture) => {
+                    self.stream = Some(capture);
+                    self.device_lost.store(false, Ordering::Release);
+
+                    let name = self.device_name.clone().unwrap_or_default();
+                    log::info!("Recording continues on input device: {}", name);
+                    if let Some(handler) = &self.event_handler {
+                        handler(RecorderEvent::DeviceChanged {
+                            device: name.clone(),
+                        });
+                    }
+                    return Ok(Some(name));
+                }
+                Err(e) => {
+                    log::warn!("Failover to {:?} failed: {}", device_name, e);
+                    last_error = e;
+                }
             }
+        }
+        Err(last_error)
+    }
 
-            // Stop recording on error (device likely disconnected)
-            is_recording_err.store(false, Ordering::Relaxed);
-        };
+    /// Stop waiting for a replacement device
+    ///
+    /// Reports the loss like any other stream error, so the partial take gets
+    /// finalized with `stop_recording`.
+    pub fn abandon_failover(&mut self, reason: &str) {
+        if !self.device_lost.swap(false, Ordering::AcqRel) {
+            return;
+        }
+        log::error!("No replacement input device found: {}", reason);
+        self.is_recording.store(false, Ordering::Release);
+        if let Ok(mut error) = self.stream_error.lock() {
+            *error = Some(format!("Stream error: {}", reason));
+        }
+    }
+
+    /// Spawn the thread that streams captured samples into the WAV file
+    ///
+    /// The thread also enforces `max_duration_minutes`: it counts captured frames
+    /// at the device rate, warns `LIMIT_WARNING_SECONDS` before the limit and
+    /// finalizes the take itself once the limit is reached. Every chunk runs
+    /// through the voice activity detector, which drives the silence auto-stop.
+    /// With `system` the system audio is lined up with each chunk and either
+    /// added to it or written to its own track.
+    fn spawn_writer(
+        &self,
+        mut writer: StreamingWavWriter,
+        file_path: PathBuf,
+        mut system: Option<SystemTake>,
+    ) -> RecordingSession {
+        let samples = Arc::clone(&self.samples);
+        let system_samples = Arc::clone(&self.system_samples);
+        let system_path = system
+            .as_ref()
+            .and_then(|system| system.track.as_ref())
+            .map(|track| track.path.clone());
+        let is_recording = Arc::clone(&self.is_recording);
+        let current_level = Arc::clone(&self.current_level);
+        let stream_error = Arc::clone(&self.stream_error);
+        let overruns = Arc::clone(&self.overruns);
+        let device_lost = Arc::clone(&self.device_lost);
+        let paused = Arc::clone(&self.paused);
+        let events = self.event_handler.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&stop);
+
+        let source_rate = self.source_sample_rate;
+        let limit_frames = self.settings.max_duration_seconds() * source_rate as u64;
+        let warning_frames =
+            limit_frames.saturating_sub(LIMIT_WARNING_SECONDS * source_rate as u64);
+        let silence_stop_ms = self
+            .settings
+            .silence_auto_stop_seconds
+            .filter(|seconds| self.silence_auto_stop && *seconds > 0)
+            .map(|seconds| seconds as u64 * 1000);
+
+        let handle = thread::spawn(move || -> Result<TakeSummary, AudioError> {
+            let emit = |event: RecorderEvent| {
+                if let Some(handler) = &events {
+                    handler(event);
+                }
+            };
+            let mut vad = VoiceActivityDetector::new(source_rate);
+            let mut quality = QualityAnalyzer::new(source_rate);
+            let mut frames_captured: u64 = 0;
+            let mut warned = false;
+            let mut overruns_reported: u64 = 0;
+            let mut last_overrun_report: Option<Instant> = None;
+            let mut device_gaps = Vec::new();
+            // Position and time of a device loss that has not been recovered yet
+            let mut lost_since: Option<(u64, Instant)> = None;
+            let mut pause_markers = Vec::new();
+            // Position and time of a pause that has not been resumed yet
+            let mut paused_since: Option<(u64, Instant)> = None;
+
+            loop {
+                // Read the flag before draining: once it is set the stream is gone,
+                // so this drain is guaranteed to be the last one
+                let finished = stop_flag.load(Ordering::Acquire);
+
+                // The pause flag only changes under this lock, so everything
+                // captured before a pause is in this chunk or an earlier one
+                let (mut chunk, is_paused) = samples
+                    .lock()
+                    .map(|mut pending| {
+                        (
+                            std::mem::take(&mut *pending),
+                            paused.load(Ordering::Acquire),
+                        )
+                    })
+                    .unwrap_or_default();
+
+                // Cut the take at exactly the configured duration
+                let remaining = limit_frames - frames_captured;
+                let limit_reached = chunk.len() as u64 >= remaining;
+                if limit_reached {
+                    chunk.truncate(remaining as usize);
+                }
+                frames_captured += chunk.len() as u64;
+                quality.push(&chunk);
+
+                let system_error = system.as_mut().and_then(|system| {
+                    let pending = system_samples
+                        .lock()
+                        .map(|mut pending| std::mem::take(&mut *pending))
+                        .unwrap_or_default();
+                    system.aligner.push(&pending);
+                    let system_chunk = system.aligner.take(chunk.len());
+
+                    match system.track.as_mut() {
+                        Some(track) => {
+                            track.vad.push(&system_chunk);
+                            track
+                                .writer
+                                .write(&system_chunk)
+                                .and_then(|_| track.writer.flush())
+                                .err()
+                        }
+                        None => {
+                            for (sample, system_sample) in chunk.iter_mut().zip(&system_chunk) {
+                                *sample = (*sample + system_sample).clamp(-1.0, 1.0);
+                            }
+                            None
+                        }
+                    }
+                });
+                // The take itself is fine, it just goes on without system audio
+                if let Some(e) = system_error {
+                    log::error!("Failed to write system audio track: {}", e);
+                    if let Some(track) = system.take().and_then(|system| system.track) {
+                        drop(track.writer);
+                        let _ = fs::remove_file(&track.path);
+                    }
+                }
+                vad.push(&chunk);
 
-        let stream = device
-            .build_input_stream(
-                config,
-                move |data: &[T], _: &cpal::InputCallbackInfo| {
-                    if !is_recording.load(Ordering::Relaxed) {
-                        return;
+                if !chunk.is_empty() {
+                    if let Err(e) = writer.write(&chunk).and_then(|_| writer.flush()) {
+                        log::error!("Failed to write recording to disk: {}", e);
+                        if let Ok(mut error_lock) = stream_error.lock() {
+                            *error_lock = Some(format!("Write error: {}", e));
+                        }
+                        is_recording.store(false, Ordering::Relaxed);
+                        return Err(e);
                     }
+                }
+
+                // Lost audio is reported, throttled so a struggling system isn't flooded
+                let dropped_frames = overruns.load(Ordering::Relaxed);
+                if dropped_frames > overruns_reported
+                    && !matches!(last_overrun_report, Some(t) if t.elapsed() < OVERRUN_REPORT_INTERVAL)
+                {
+                    log::warn!(
+                        "Capture overrun: {} frames dropped in this take",
+                        dropped_frames
+                    );
+                    emit(RecorderEvent::CaptureOverrun { dropped_frames });
+                    overruns_reported = dropped_frames;
+                    last_overrun_report = Some(Instant::now());
+                }
 
-                    let mut samples_lock = samples.lock().unwrap();
+                // A device loss opens a gap, the replacement device closes it
+                match (device_lost.load(Ordering::Acquire), lost_since) {
+                    (true, None) => {
+                        let elapsed_ms = frames_to_ms(frames_captured, source_rate);
+                        log::warn!("Input device lost after {}ms", elapsed_ms);
+                        lost_since = Some((frames_captured, Instant::now()));
+                        emit(RecorderEvent::DeviceLost { elapsed_ms });
+                    }
+                    (false, Some((at_frames, since))) => {
+                        device_gaps.push(DeviceGap {
+                            at_ms: frames_to_ms(at_frames, source_rate),
+                            lost_ms: since.elapsed().as_millis() as u64,
+                        });
+                        lost_since = None;
+                    }
+                    _ => {}
+                }
 
-                    // Convert to f32 and mix to mono
-                    let mut sum_squared = 0.0f32;
-                    let mut count = 0;
+                match (is_paused, paused_since) {
+                    (true, None) => paused_since = Some((frames_captured, Instant::now())),
+                    (false, Some((at_frames, since))) => {
+                        pause_markers.push(PauseMarker {
+                            at_ms: frames_to_ms(at_frames, source_rate),
+                            paused_ms: since.elapsed().as_millis() as u64,
+                        });
+                        paused_since = None;
+                    }
+                    _ => {}
+                }
 
-                    for frame in data.chunks(channels) {
-                        // Average all channels to mono
-                        let mono_sample: f32 =
-                            frame.iter().map(|s| f32::from_sample(*s)).sum::<f32>()
-                                / channels as f32;
+                if !warned && frames_captured >= warning_frames && !limit_reached {
+                    warned = true;
+                    emit(RecorderEvent::LimitWarning {
+                        elapsed_ms: frames_to_ms(frames_captured, source_rate),
+                        remaining_ms: frames_to_ms(limit_frames - frames_captured, source_rate),
+                    });
+                }
 
-                        samples_lock.push(mono_sample);
-                        sum_squared += mono_sample * mono_sample;
-                        count += 1;
+                // Only counts once something was said, so a slow start never ends the take
+                let silence_reached = match (silence_stop_ms, vad.trailing_silence_ms()) {
+                    (Some(limit), Some(silence)) => silence >= limit,
+                    _ => false,
+                };
+
+                if limit_reached || silence_reached {
+                    // Stop capturing; the stream itself is released by stop_recording
+                    is_recording.store(false, Ordering::Relaxed);
+                    current_level.store(0, Ordering::Relaxed);
+                    let result = writer.finalize();
+
+                    let elapsed_ms = frames_to_ms(frames_captured, source_rate);
+                    if limit_reached {
+                        log::info!(
+                            "Recording limit reached after {}ms, take finalized",
+                            elapsed_ms
+                        );
+                        emit(RecorderEvent::LimitReached { elapsed_ms });
+                    } else {
+                        log::info!("Silence auto-stop after {}ms, take finalized", elapsed_ms);
+                        emit(RecorderEvent::SilenceAutoStop { elapsed_ms });
                     }
+                    return result.map(|samples_written| TakeSummary {
+                        samples_written,
+                        speech_segments: vad.finish(),
+                        device_gaps,
+                        pause_markers,
+                        quality,
+                        system_track: system.and_then(SystemTake::finish),
+                    });
+                }
+
+                if finished {
+                    return writer.finalize().map(|samples_written| TakeSummary {
+                        samples_written,
+                        speech_segments: vad.finish(),
+                        device_gaps,
+                        pause_markers,
+                        quality,
+                        system_track: system.and_then(SystemTake::finish),
+                    });
+                }
+
+                thread::sleep(WRITER_INTERVAL);
+            }
+        });
+
+        RecordingSession {
+            file_path,
+            system_path,
+            stop,
+            writer: handle,
+        }
+    }
+
+    /// Error callback of the microphone source
+    fn stream_error_handler(&self) -> SourceErrorHandler {
+        // Clone Arcs for error callback (BUG-2 fix: Device disconnect handling)
+        let is_recording_err = Arc::clone(&self.is_recording);
+        let stream_error = Arc::clone(&self.stream_error);
+        let device_lost = Arc::clone(&self.device_lost);
+
+        Arc::new(move |err: String| {
+            log::error!("Audio stream error: {}", err);
+
+            // Mid-take the take stays open and the recorder fails over to another device
+            if is_recording_err.load(Ordering::Acquire) {
+                device_lost.store(true, Ordering::Release);
+                return;
+            }
 
-                    // Calculate RMS level (0-100)
-                    if count > 0 {
-                        let rms = (sum_squared / count as f32).sqrt();
-                        // Convert to 0-100 scale (assuming max amplitude of 1.0)
-                        let level = (rms * 100.0 * 3.0).min(100.0) as u8; // x3 for better sensitivity
-                        current_level.store(level, Ordering::Relaxed);
-                    }
-                },
-                err_fn,
-                None, // No timeout
-            )
-            .map_err(|e| AudioError::StreamError(e.to_string()))?;
+            // Store the error for later retrieval
+            if let Ok(mut error_lock) = stream_error.lock() {
+                *error_lock = Some(format!("Stream error: {}", err));
+            }
+        })
+    }
 
-        Ok(stream)
+    /// Check if a take is still open on disk
+    ///
+    /// This stays true after a stream error stopped the capture, so the
+    /// partial recording can still be finalized with `stop_recording`.
+    pub fn has_active_take(&self) -> bool {
+        self.session.is_some()
     }
 
-    /// Stop recording and export to WAV
+    /// Stop recording and finalize the WAV file
     pub fn stop_recording(&mut self) -> Result<RecordingResult, AudioError> {
-        if !self.is_recording() {
-            return Err(AudioError::NotRecording);
+        let session = self.session.take().ok_or(AudioError::NotRecording)?;
+
+        // Drop the stream to release the microphone, unless the warm microphone
+        // keeps it open. Dropping it first lets the capture worker hand the last
+        // frames to the take before it exits
+        let device_lost = self.device_lost.swap(false, Ordering::AcqRel);
+        if !self.settings.warm_mic || device_lost || self.has_stream_error() {
+            self.stream = None;
         }
+        self.system_stream = None;
 
-        // Stop recording
-        self.is_recording.store(false, Ordering::Relaxed);
+        // Stop recording; the pre-roll starts over so the take never repeats
+        self.is_recording.store(false, Ordering::Release);
+        self.paused.store(false, Ordering::Release);
         self.current_level.store(0, Ordering::Relaxed);
+        self.reset_pre_roll();
+
+        // Let the writer drain what is left and close the file
+        // (returns immediately if the duration limit already finalized it)
+        session.stop.store(true, Ordering::Release);
+        let summary = session
+            .writer
+            .join()
+            .map_err(|_| AudioError::WavWriteError("Recording writer thread panicked".into()))??;
+
+        if summary.samples_written == 0 {
+            let _ = fs::remove_file(&session.file_path);
+            if let Some(system_path) = &session.system_path {
+                let _ = fs::remove_file(system_path);
+            }
+            recovery::clear_journal(&session.file_path);
+            return Err(AudioError::WavWriteError("No audio data recorded".into()));
+        }
 
-        // Drop the stream to release the microphone
-        self.stream.0 = None;
+        // Rated on the full take, the raw input still contains the trimmed silence
+        let quality = summary
+            .quality
+            .report(&summary.speech_segments, self.device_name.as_deref());
+        if quality.is_silent() {
+            log::info!("No speech in this take, transcription can be skipped");
+        } else if !quality.issues.is_empty() {
+            log::warn!(
+                "Recording quality {:?}: {:?}",
+                quality.quality,
+                quality.issues
+            );
+        }
 
-        // Calculate duration
-        let duration_ms = self
-            .recording_start
-            .map(|start| start.elapsed().as_millis() as u64)
-            .unwrap_or(0);
+        // Both tracks are cut at the same place, so the speech of either is kept
+        let system_track = session.system_path.zip(summary.system_track);
+        let trim_segments = match &system_track {
+            Some((_, (_, system_segments))) => {
+                merge_segments(&summary.speech_segments, system_segments)
+            }
+            None => summary.speech_segments.clone(),
+        };
 
-        // Get samples
-        let samples = {
-            let lock = self.samples.lock().unwrap();
-            lock.clone()
+        let untrimmed = (summary.samples_written, 0);
+        let (samples_written, trim_offset_ms) = if self.settings.trim_silence {
+            match trim_to_speech(&session.file_path, summary.samples_written, &trim_segments) {
+                Ok((samples, _)) => (samples, trim_segments.first().map_or(0, trim_start_ms)),
+                Err(e) => {
+                    // The untrimmed take is still valid, just longer than needed
+                    log::warn!("Failed to trim silence: {}", e);
+                    untrimmed
+                }
+            }
+        } else {
+            untrimmed
         };
 
-        if samples.is_empty() {
-            return Err(AudioError::WavWriteError("No audio data recorded".into()));
-        }
+        // Sample-accurate duration of what actually ended up in the file
+        let duration_ms = frames_to_ms(samples_written, WHISPER_SAMPLE_RATE);
+        let speech_segments = shift_segments(&summary.speech_segments, trim_offset_ms, duration_ms);
 
-        log::info!(
-            "Recording stopped. {} samples at {} Hz, duration: {}ms",
-            samples.len(),
-            self.source_sample_rate,
-            duration_ms
-        );
+        // The system track has as many samples as the take, so the same cut
+        // keeps the two aligned; a track that cannot follow is dropped
+        let system_track = system_track.and_then(|(path, (system_samples, system_segments))| {
+            let trimmed = if samples_written == summary.samples_written {
+                Ok(system_samples)
+            } else {
+                trim_to_speech(&path, system_samples, &trim_segments).map(|(samples, _)| samples)
+            };
+            match trimmed {
+                Ok(samples) if samples > 0 => Some(SystemTrack {
+                    file_path: path.to_string_lossy().to_string(),
+                    speech_segments: shift_segments(&system_segments, trim_offset_ms, duration_ms),
+                }),
+                Ok(_) => {
+                    let _ = fs::remove_file(&path);
+                    None
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Dropping system audio track that could not be trimmed: {}",
+                        e
+                    );
+                    let _ = fs::remove_file(&path);
+                    None
+                }
+            }
+        });
+
+        // Gap markers follow the trimmed timeline
+        let device_gaps: Vec<DeviceGap> = summary
+            .device_gaps
+            .iter()
+            .map(|gap| DeviceGap {
+                at_ms: gap.at_ms.saturating_sub(trim_offset_ms).min(duration_ms),
+                lost_ms: gap.lost_ms,
+            })
+            .collect();
+        let pause_markers: Vec<PauseMarker> = summary
+            .pause_markers
+            .iter()
+            .map(|pause| PauseMarker {
+                at_ms: pause.at_ms.saturating_sub(trim_offset_ms).min(duration_ms),
+                paused_ms: pause.paused_ms,
+            })
+            .collect();
 
-        // Resample to 16kHz if necessary
-        let resampled = if self.source_sample_rate != WHISPER_SAMPLE_RATE {
-            self.resample(&samples, self.source_sample_rate, WHISPER_SAMPLE_RATE)?
-        } else {
-            samples
-        };
+        let dropped_frames = self.overruns.load(Ordering::Relaxed);
+        if dropped_frames > 0 {
+            log::warn!(
+                "{} frames ({}ms) were lost to capture overruns",
+                dropped_frames,
+                frames_to_ms(dropped_frames, self.source_sample_rate)
+            );
+        }
 
-        // Export to WAV
-        let file_path = self.export_wav(&resampled)?;
+        // Sealed only now: while the take ran, the crash recovery needed it readable
+        let seal = |path: &Path| {
+            encryption::seal_file(path).map_err(|e| AudioError::Encryption(e.to_string()))
+        };
+        seal(&session.file_path)?;
+        if let Some(track) = &system_track {
+            seal(Path::new(&track.file_path))?;
+        }
 
-        log::info!("Recording exported to: {}", file_path);
+        let file_path = session.file_path.to_string_lossy().to_string();
+        log::info!(
+            "Recording stopped. {} samples at {} Hz, duration: {}ms, {} speech segments, saved to: {}",
+            samples_written,
+            WHISPER_SAMPLE_RATE,
+            duration_ms,
+            speech_segments.len(),
+            file_path
+        );
 
         Ok(RecordingResult {
             file_path,
             duration_ms,
             privacy_mode: self.settings.privacy_mode,
+            speech_segments,
+            dropped_frames,
+            capture_config: self.capture_config.clone(),
+            device_gaps,
+            pause_markers,
+            quality: Some(quality),
+            system_track,
+            trim_offset_ms,
         })
     }
 
-    /// Resample audio to target sample rate
-    fn resample(
-        &self,
+    /// Resample a complete buffer to the target sample rate
+    pub fn resample(
         samples: &[f32],
         source_rate: u32,
         target_rate: u32,
     ) -> Result<Vec<f32>, AudioError> {
         log::info!("Resampling from {} Hz to {} Hz", source_rate, target_rate);
 
-        let params = rubato::SincInterpolationParameters {
-            sinc_len: 256,
-            f_cutoff: 0.95,
-            interpolation: rubato::SincInterpolationType::Linear,
-            oversampling_factor: 256,
-            window: rubato::WindowFunction::BlackmanHarris2,
-        };
-
-        let mut resampler = FftFixedIn::<f32>::new(
-            source_rate as usize,
-            target_rate as usize,
-            samples.len().min(1024),
-            2,
-            1, // mono
-        )
-        .map_err(|e| AudioError::WavWriteError(format!("Resampler init failed: {}", e)))?;
-
-        // Process in chunks
-        let chunk_size = resampler.input_frames_max();
-        let mut output = Vec::new();
-
-        for chunk in samples.chunks(chunk_size) {
-            let input = vec![chunk.to_vec()];
-
-            // Pad if necessary
-            let input = if chunk.len() < resampler.input_frames_next() {
-                let mut padded = chunk.to_vec();
-                padded.resize(resampler.input_frames_next(), 0.0);
-                vec![padded]
-            } else {
-                input
-            };
-
-            match resampler.process(&input, None) {
-                Ok(result) => {
-                    if !result.is_empty() && !result[0].is_empty() {
-                        output.extend_from_slice(&result[0]);
-                    }
-                }
-                Err(e) => {
-                    log::warn!("Resampling chunk failed: {}", e);
-                }
-            }
-        }
+        let mut resampler = StreamingResampler::new(source_rate, target_rate)?;
+        let mut output = resampler.process(samples)?;
+        output.extend(resampler.finish()?);
 
         log::info!(
             "Resampling complete: {} -> {} samples",
@@ -526,42 +3060,6 @@ impl AudioRecorder {
         Ok(output)
     }
 
-    /// Export samples to WAV file
-    fn export_wav(&self, samples: &[f32]) -> Result<String, AudioError> {
-        let recordings_dir = Self::get_recordings_dir();
-        let filename = format!("{}.wav", Uuid::new_v4());
-        let file_path = recordings_dir.join(&filename);
-
-        let spec = WavSpec {
-            channels: 1,
-            sample_rate: WHISPER_SAMPLE_RATE,
-            bits_per_sample: 16,
-            sample_format: hound::SampleFormat::Int,
-        };
-
-        let file =
-            fs::File::create(&file_path).map_err(|e| AudioError::WavWriteError(e.to_string()))?;
-        let writer = BufWriter::new(file);
-        let mut wav_writer =
-            WavWriter::new(writer, spec).map_err(|e| AudioError::WavWriteError(e.to_string()))?;
-
-        // Convert f32 samples to i16
-        for sample in samples {
-            // Clamp and convert
-            let clamped = sample.clamp(-1.0, 1.0);
-            let sample_i16 = (clamped * i16::MAX as f32) as i16;
-            wav_writer
-                .write_sample(sample_i16)
-                .map_err(|e| AudioError::WavWriteError(e.to_string()))?;
-        }
-
-        wav_writer
-            .finalize()
-            .map_err(|e| AudioError::WavWriteError(e.to_string()))?;
-
-        Ok(file_path.to_string_lossy().to_string())
-    }
-
     /// Delete a recording file (for privacy mode)
     /// SECURITY: Only allows deletion of files within the recordings directory
     pub fn delete_recording(file_path: &str) -> Result<(), AudioError> {
@@ -587,17 +3085,27 @@ impl AudioRecorder {
             ));
         }
 
-        fs::remove_file(&canonical_path)
+        // Overwritten first, so the audio cannot be recovered from the free blocks
+        encryption::secure_delete(&canonical_path)
             .map_err(|e| AudioError::WavWriteError(format!("Failed to delete file: {}", e)))?;
+        let _ = encryption::secure_delete(&system_track_path(&canonical_path));
+        recovery::clear_journal(&canonical_path);
         log::info!("Recording deleted: {}", file_path);
         Ok(())
     }
 
     /// Clean up old recordings in the cache directory
+    ///
+    /// Takes that were never processed keep their journal and are left for
+    /// crash recovery, together with the journal and their system track.
     pub fn cleanup_old_recordings() -> Result<(), AudioError> {
         let recordings_dir = Self::get_recordings_dir();
         if let Ok(entries) = fs::read_dir(&recordings_dir) {
             for entry in entries.flatten() {
+                let path = entry.path();
+                if recovery::is_unprocessed(&path) {
+                    continue;
+                }
                 if let Ok(metadata) = entry.metadata() {
                     // Delete files older than 1 hour
                     if let Ok(modified) = metadata.modified() {
@@ -624,6 +3132,8 @@ impl Default for AudioRecorder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::{sine, write_wav, TempPath};
+    use rtrb::RingBuffer;
 
     #[test]
     fn test_list_devices() {
@@ -632,12 +3142,776 @@ mod tests {
         let _ = recorder.list_devices();
     }
 
+    fn identity(index: usize, fingerprint: &str, name: &str) -> DeviceIdentity {
+        DeviceIdentity {
+            host: "ALSA".to_string(),
+            index,
+            fingerprint: fingerprint.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_device_identity_round_trips_and_rejects_legacy_names() {
+        let id = identity(1, "a1b2c3d4", "front:CARD=USB,DEV=0");
+        assert_eq!(id.id(), "ALSA:1:a1b2c3d4:front:CARD=USB,DEV=0");
+        assert_eq!(DeviceIdentity::parse(&id.id()), Some(id));
+
+        // Plain device names from older versions, including ALSA names with colons
+        assert_eq!(DeviceIdentity::parse("USB Microphone"), None);
+        assert_eq!(DeviceIdentity::parse("sysdefault:CARD=Generic"), None);
+        assert_eq!(DeviceIdentity::parse("hw:0:CARD=x:y"), None);
+    }
+
+    #[test]
+    fn test_resolve_device_tells_identical_mics_apart_and_follows_renames() {
+        let present = vec![
+            identity(0, "11111111", "USB Mic"),
+            identity(1, "11111111", "USB Mic"),
+            identity(0, "22222222", "Headset (renamed)"),
+        ];
+
+        assert_eq!(resolve_device(&present, &present[1].id()), Some(1));
+        // Fingerprint drift alone does not lose the device
+        assert_eq!(
+            resolve_device(&present, &identity(1, "99999999", "USB Mic").id()),
+            Some(1)
+        );
+        // Renamed device found by its unique configuration fingerprint
+        assert_eq!(
+            resolve_device(&present, &identity(0, "22222222", "Headset").id()),
+            Some(2)
+        );
+        // Ambiguous fingerprint or unknown device resolves to nothing
+        assert_eq!(
+            resolve_device(&present, &identity(0, "11111111", "Old Mic").id()),
+            None
+        );
+        assert_eq!(resolve_device(&present, "Headset"), None);
+        // Legacy name-only ids match by name
+        assert_eq!(resolve_device(&present, "USB Mic"), Some(0));
+    }
+
+    #[test]
+    fn test_migrate_device_id_rewrites_only_present_legacy_names() {
+        let present = vec![identity(0, "11111111", "USB Mic")];
+
+        assert_eq!(
+            migrate_device_id(&present, "USB Mic"),
+            Some("ALSA:0:11111111:USB Mic".to_string())
+        );
+        assert_eq!(migrate_device_id(&present, "Unplugged Mic"), None);
+        assert_eq!(migrate_device_id(&present, &present[0].id()), None);
+
+        let settings: AudioSettings = serde_json::from_str(
+            r#"{"device_id":"USB Mic","max_duration_minutes":6,"privacy_mode":true}"#,
+        )
+        .unwrap();
+        assert!(settings.device_priority.is_empty());
+    }
+
+    #[test]
+    fn test_resample_preserves_duration() {
+        let samples = sine(440.0, 0.5, 48_000, 48_000);
+        let resampled = AudioRecorder::resample(&samples, 48_000, WHISPER_SAMPLE_RATE).unwrap();
+        assert_eq!(resampled.len(), 16_000);
+    }
+
+    #[test]
+    fn test_streaming_writer_is_readable_before_finalize() {
+        let path = TempPath::file("take.wav");
+        let mut writer = StreamingWavWriter::create(&path, 48_000).unwrap();
+
+        // Feed 1 second in odd-sized chunks like the capture callback does
+        for chunk in vec![0.25f32; 48_000].chunks(480 + 7) {
+            writer.write(chunk).unwrap();
+        }
+        writer.flush().unwrap();
+
+        // Simulates a crash: the file on disk must already be a valid WAV
+        let partial = hound::WavReader::open(&path).unwrap();
+        assert_eq!(partial.spec().sample_rate, WHISPER_SAMPLE_RATE);
+        assert_eq!(partial.len() as u64, writer.samples_written());
+        drop(partial);
+
+        let total = writer.finalize().unwrap();
+        assert_eq!(total, 16_000);
+        assert_eq!(hound::WavReader::open(&path).unwrap().len(), 16_000);
+    }
+
+    #[test]
+    fn test_writer_enforces_duration_limit() {
+        let mut recorder = AudioRecorder::new();
+        recorder.source_sample_rate = WHISPER_SAMPLE_RATE;
+        recorder.update_settings(AudioSettings {
+            max_duration_minutes: 1,
+            ..AudioSettings::default()
+        });
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        recorder.set_event_handler(Arc::new(move |event| sink.lock().unwrap().push(event)));
+
+        // 61 seconds of audio already waiting in the capture buffer
+        recorder
+            .samples
+            .lock()
+            .unwrap()
+            .extend(vec![0.1f32; 61 * WHISPER_SAMPLE_RATE as usize]);
+
+        let path = TempPath::file("take.wav");
+        let writer = StreamingWavWriter::create(&path, WHISPER_SAMPLE_RATE).unwrap();
+        let session = recorder.spawn_writer(writer, path.to_path_buf(), None);
+
+        // The writer stops on its own, without the stop flag being set
+        let summary = session.writer.join().unwrap().unwrap();
+        assert_eq!(summary.samples_written, 60 * WHISPER_SAMPLE_RATE as u64);
+        assert!(matches!(
+            events.lock().unwrap().as_slice(),
+            [RecorderEvent::LimitReached { elapsed_ms: 60_000 }]
+        ));
+    }
+
+    /// Silence, then `speech_ms` of a loud tone, then silence again
+    fn speech_fixture(lead_ms: usize, speech_ms: usize, tail_ms: usize) -> Vec<f32> {
+        let per_ms = WHISPER_SAMPLE_RATE as usize / 1000;
+        let mut samples = vec![0.001f32; lead_ms * per_ms];
+        samples.extend(sine(220.0, 0.3, WHISPER_SAMPLE_RATE, speech_ms * per_ms));
+        samples.extend(vec![0.001f32; tail_ms * per_ms]);
+        samples
+    }
+
+    #[test]
+    fn test_vad_finds_speech_segment() {
+        let segments = detect_speech(&speech_fixture(1000, 2000, 1000), WHISPER_SAMPLE_RATE);
+        assert_eq!(segments.len(), 1);
+        assert!(segments[0].start_ms.abs_diff(1000) <= VAD_FRAME_MS);
+        assert!(segments[0].end_ms.abs_diff(3000) <= VAD_FRAME_MS);
+
+        assert!(detect_speech(&vec![0.001f32; 16_000], WHISPER_SAMPLE_RATE).is_empty());
+    }
+
+    #[test]
+    fn test_vad_reads_takes_from_disk() {
+        let samples = speech_fixture(1000, 2000, 1000);
+        let speech = TempPath::file("speech.wav");
+        write_wav(&speech, WHISPER_SAMPLE_RATE, 1, &samples);
+        assert_eq!(
+            detect_speech_in_file(&speech).unwrap(),
+            detect_speech(&samples, WHISPER_SAMPLE_RATE)
+        );
+
+        let silence = TempPath::file("silence.wav");
+        write_wav(&silence, WHISPER_SAMPLE_RATE, 1, &[0.001f32; 48_000]);
+        assert!(detect_speech_in_file(&silence).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_trim_to_speech_cuts_leading_and_trailing_silence() {
+        let samples = speech_fixture(2000, 1000, 2000);
+        let segments = detect_speech(&samples, WHISPER_SAMPLE_RATE);
+
+        let path = TempPath::file("take.wav");
+        let mut writer = StreamingWavWriter::create(&path, WHISPER_SAMPLE_RATE).unwrap();
+        writer.write(&samples).unwrap();
+        let total = writer.finalize().unwrap();
+
+        let (kept, shifted) = trim_to_speech(&path, total, &segments).unwrap();
+        let expected_ms = segments[0].end_ms - segments[0].start_ms + 2 * TRIM_PADDING_MS;
+        assert_eq!(kept, expected_ms * 16);
+        assert_eq!(hound::WavReader::open(&path).unwrap().len() as u64, kept);
+        assert_eq!(shifted[0].start_ms, TRIM_PADDING_MS);
+    }
+
+    #[test]
+    fn test_writer_stops_after_silence() {
+        let mut recorder = AudioRecorder::new();
+        recorder.source_sample_rate = WHISPER_SAMPLE_RATE;
+        recorder.set_silence_auto_stop(true);
+        recorder.update_settings(AudioSettings {
+            silence_auto_stop_seconds: Some(2),
+            ..AudioSettings::default()
+        });
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        recorder.set_event_handler(Arc::new(move |event| sink.lock().unwrap().push(event)));
+
+        recorder
+            .samples
+            .lock()
+            .unwrap()
+            .extend(speech_fixture(500, 1000, 3000));
+
+        let path = TempPath::file("take.wav");
+        let writer = StreamingWavWriter::create(&path, WHISPER_SAMPLE_RATE).unwrap();
+        let summary = recorder
+            .spawn_writer(writer, path.to_path_buf(), None)
+            .writer
+            .join()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(summary.speech_segments.len(), 1);
+        assert!(matches!(
+            events.lock().unwrap().as_slice(),
+            [RecorderEvent::SilenceAutoStop { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_synthetic_take_runs_the_whole_pipeline() {
+        use crate::audio_source::{Pace, Signal, SyntheticSource};
+
+        // A fast source may run ahead of the take, the leading silence absorbs that
+        let source = SyntheticSource::new(48000, 2, Pace::Fast)
+            .then(Signal::Silence, Duration::from_secs(2))
+            .then(
+                Signal::Sine {
+                    frequency: 300.0,
+                    amplitude: 0.3,
+                },
+                Duration::from_secs(1),
+            )
+            .then(Signal::Silence, Duration::from_secs(3));
+        let mut recorder = AudioRecorder::with_source(Box::new(source));
+        recorder.set_silence_auto_stop(true);
+        recorder.update_settings(AudioSettings {
+            silence_auto_stop_seconds: Some(1),
+            ..AudioSettings::default()
+        });
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        recorder.set_event_handler(Arc::new(move |event| sink.lock().unwrap().push(event)));
+
+        recorder.start_recording().unwrap();
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while recorder.is_recording() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let result = recorder.stop_recording().unwrap();
+
+        assert_eq!(result.capture_config.map(|config| config.channels), Some(2));
+        assert_eq!(result.speech_segments.len(), 1);
+        let events = events.lock().unwrap();
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, RecorderEvent::SilenceAutoStop { .. })));
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, RecorderEvent::Meter(_))));
+
+        AudioRecorder::delete_recording(&result.file_path).unwrap();
+    }
+
+    #[test]
+    fn test_writer_marks_gap_while_device_is_lost() {
+        let mut recorder = AudioRecorder::new();
+        recorder.source_sample_rate = WHISPER_SAMPLE_RATE;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        recorder.set_event_handler(Arc::new(move |event| sink.lock().unwrap().push(event)));
+
+        // One second on the first device, then it disappears
+        recorder.samples.lock().unwrap().extend(vec![0.1f32; 16000]);
+        recorder.device_lost.store(true, Ordering::Release);
+
+        let path = TempPath::file("take.wav");
+        let writer = StreamingWavWriter::create(&path, WHISPER_SAMPLE_RATE).unwrap();
+        let session = recorder.spawn_writer(writer, path.to_path_buf(), None);
+        thread::sleep(Duration::from_millis(300));
+
+        // The replacement device delivers another half second
+        recorder.samples.lock().unwrap().extend(vec![0.1f32; 8000]);
+        recorder.device_lost.store(false, Ordering::Release);
+        thread::sleep(Duration::from_millis(300));
+
+        session.stop.store(true, Ordering::Release);
+        let summary = session.writer.join().unwrap().unwrap();
+
+        assert_eq!(summary.samples_written, 24000);
+        assert_eq!(summary.device_gaps.len(), 1);
+        assert_eq!(summary.device_gaps[0].at_ms, 1000);
+        assert!(summary.device_gaps[0].lost_ms >= 200);
+        assert!(matches!(
+            events.lock().unwrap().as_slice(),
+            [RecorderEvent::DeviceLost { elapsed_ms: 1000 }]
+        ));
+    }
+
+    #[test]
+    fn test_pause_drops_audio_and_leaves_a_marker() {
+        let mut recorder = AudioRecorder::new();
+        recorder.source_sample_rate = WHISPER_SAMPLE_RATE;
+        recorder.is_recording.store(true, Ordering::Release);
+        let targets = CaptureTargets {
+            samples: Arc::clone(&recorder.samples),
+            pre_roll: Arc::clone(&recorder.pre_roll),
+            is_recording: Arc::clone(&recorder.is_recording),
+            paused: Arc::clone(&recorder.paused),
+            current_level: Arc::clone(&recorder.current_level),
+            meter: Arc::clone(&recorder.meter),
+            events: None,
+        };
+
+        let path = TempPath::file("take.wav");
+        let writer = StreamingWavWriter::create(&path, WHISPER_SAMPLE_RATE).unwrap();
+        let session = recorder.spawn_writer(writer, path.to_path_buf(), None);
+
+        // One second, then a pause whose audio never reaches the take
+        targets.deliver(&[0.1f32; 16000]);
+        recorder.pause_recording().unwrap();
+        assert!(recorder.is_paused());
+        targets.deliver(&[0.1f32; 16000]);
+        thread::sleep(Duration::from_millis(300));
+
+        recorder.resume_recording().unwrap();
+        targets.deliver(&[0.1f32; 8000]);
+        thread::sleep(Duration::from_millis(300));
+
+        session.stop.store(true, Ordering::Release);
+        let summary = session.writer.join().unwrap().unwrap();
+
+        assert_eq!(summary.samples_written, 24000);
+        assert_eq!(summary.pause_markers.len(), 1);
+        assert_eq!(summary.pause_markers[0].at_ms, 1000);
+        assert!(summary.pause_markers[0].paused_ms >= 200);
+    }
+
+    #[test]
+    fn test_capture_worker_resamples_to_take_rate() {
+        let targets = capture_targets();
+        targets.is_recording.store(true, Ordering::Release);
+
+        // A 48kHz replacement device feeding a 16kHz take
+        let (mut producer, consumer) = RingBuffer::new(48000);
+        push_interleaved(&mut producer, &[0.25f32; 48000], 1, &AtomicU64::new(0));
+        drop(producer);
+        let resampler = StreamingResampler::new(48000, WHISPER_SAMPLE_RATE).unwrap();
+        run_capture_worker(
+            consumer,
+            ChannelMixer::new(&ChannelMode::Average, 1, 48000),
+            targets.clone(),
+            Arc::new(AtomicBool::new(false)),
+            Some(resampler),
+        );
+
+        assert_eq!(targets.samples.lock().unwrap().len(), 16000);
+    }
+
+    #[test]
+    fn test_writer_applies_dsp_without_changing_length() {
+        let settings = AudioSettings {
+            high_pass_filter: true,
+            noise_suppression: true,
+            loudness_normalization: true,
+            ..AudioSettings::default()
+        };
+        let path = TempPath::file("take.wav");
+        let mut writer = StreamingWavWriter::create(&path, 48_000)
+            .unwrap()
+            .with_dsp(settings.dsp_chain());
+
+        // DC offset only: the high-pass filter should take it out entirely
+        for chunk in vec![0.2f32; 48_000].chunks(480) {
+            writer.write(chunk).unwrap();
+        }
+        assert_eq!(writer.finalize().unwrap(), 16_000);
+
+        let samples: Vec<i16> = hound::WavReader::open(&path)
+            .unwrap()
+            .samples::<i16>()
+            .map(|s| s.unwrap())
+            .collect();
+        assert_eq!(samples.len(), 16_000);
+        // Skip the filter settling at the start and the resampler edge at the end
+        assert!(samples[4000..15000].iter().all(|s| s.abs() < 100));
+    }
+
+    #[test]
+    fn test_live_tap_sees_every_written_sample() {
+        let path = TempPath::file("take.wav");
+        let tap = LiveAudio::default();
+        let mut writer = StreamingWavWriter::create(&path, 48_000)
+            .unwrap()
+            .with_tap(Arc::clone(&tap));
+
+        for chunk in vec![0.5f32; 48_000].chunks(480) {
+            writer.write(chunk).unwrap();
+        }
+        // The consumer drains while the take is still running
+        let drained = std::mem::take(&mut *tap.lock().unwrap());
+        let total = writer.finalize().unwrap();
+
+        assert_eq!(drained.len() + tap.lock().unwrap().len(), total as usize);
+        assert!(drained[4000..8000].iter().all(|s| (s - 0.5).abs() < 0.01));
+    }
+
+    #[test]
+    fn test_pre_roll_keeps_most_recent_samples() {
+        let mut pre_roll = PreRollBuffer::new();
+        pre_roll.push(1.0);
+        assert!(
+            pre_roll.samples.is_empty(),
+            "disabled pre-roll keeps nothing"
+        );
+
+        pre_roll.reset(4);
+        for i in 0..10 {
+            pre_roll.push(i as f32);
+        }
+
+        let mut take = vec![];
+        pre_roll.drain_into(&mut take);
+        assert_eq!(take, vec![6.0, 7.0, 8.0, 9.0]);
+        assert!(pre_roll.samples.is_empty());
+    }
+
+    #[test]
+    fn test_level_meter_publishes_at_fixed_rate_with_dbfs_levels() {
+        let mut meter = LevelMeter::new(16000, false);
+        let signal = sine(440.0, 0.5, 16000, 16000);
+
+        // Chunking of the input does not change the frame rate
+        let frames: Vec<MeterFrame> = signal.chunks(77).filter_map(|c| meter.push(c)).collect();
+        assert_eq!(frames.len(), (16000 / (16000 / METER_RATE_HZ)) as usize);
+
+        let frame = frames.last().unwrap();
+        assert!(
+            (frame.peak_dbfs - -6.02).abs() < 0.1,
+            "peak {}",
+            frame.peak_dbfs
+        );
+        assert!(
+            (frame.rms_dbfs - -9.03).abs() < 0.1,
+            "rms {}",
+            frame.rms_dbfs
+        );
+        assert_eq!(frame.clip_count, 0);
+        assert_eq!(frame.waveform.len(), METER_WAVEFORM_POINTS);
+        assert!(frame.waveform.iter().all(|&p| p <= 0.5 + 1e-6));
+        assert!(frame.bands.is_empty());
+
+        // Two intervals, so the newest frame holds no leftover tone
+        let silence = meter.push(&[0.0; 1200]).unwrap();
+        assert_eq!(silence.peak_dbfs, METER_FLOOR_DBFS);
+        assert_eq!(silence.rms_dbfs, METER_FLOOR_DBFS);
+    }
+
+    #[test]
+    fn test_level_meter_counts_clipping_across_frames() {
+        let mut meter = LevelMeter::new(16000, false);
+        let mut chunk = vec![0.1f32; 600];
+        chunk[10] = 1.0;
+        chunk[20] = -1.0;
+        assert_eq!(meter.push(&chunk).unwrap().clip_count, 2);
+        assert_eq!(meter.push(&chunk).unwrap().clip_count, 4);
+    }
+
+    #[test]
+    fn test_level_meter_spectrum_peaks_in_the_tone_band() {
+        let mut meter = LevelMeter::new(48000, true);
+        let frame = meter.push(&sine(1000.0, 0.5, 48000, 1600)).unwrap();
+        assert_eq!(frame.bands.len(), METER_SPECTRUM_BANDS);
+
+        let loudest = frame
+            .bands
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap();
+        let (start, end) = meter.spectrum.as_ref().unwrap().bands[loudest.0];
+        let bin_hz = 48000.0 / 1024.0;
+        assert!((start as f32 * bin_hz..end as f32 * bin_hz).contains(&1000.0));
+        assert!((loudest.1 - -6.02).abs() < 1.5, "band level {}", loudest.1);
+    }
+
+    fn capture_targets() -> CaptureTargets {
+        CaptureTargets {
+            samples: Arc::new(Mutex::new(Vec::new())),
+            pre_roll: Arc::new(Mutex::new(PreRollBuffer::new())),
+            is_recording: Arc::new(AtomicBool::new(false)),
+            paused: Arc::new(AtomicBool::new(false)),
+            current_level: Arc::new(AtomicU8::new(0)),
+            meter: Arc::new(Mutex::new(LevelMeter::new(WHISPER_SAMPLE_RATE, false))),
+            events: None,
+        }
+    }
+
+    #[test]
+    fn test_capture_ring_counts_overruns_in_whole_frames() {
+        let (mut producer, mut consumer) = RingBuffer::new(5);
+        let overruns = AtomicU64::new(0);
+
+        // Stereo: only two whole frames fit, the other two are dropped
+        push_interleaved(&mut producer, &[0.1f32; 8], 2, &overruns);
+        assert_eq!(consumer.slots(), 4);
+        assert_eq!(overruns.load(Ordering::Relaxed), 2);
+
+        // Nothing fits anymore
+        push_interleaved(&mut producer, &[0i16; 4], 2, &overruns);
+        assert_eq!(overruns.load(Ordering::Relaxed), 4);
+
+        consumer.read_chunk(4).unwrap().commit_all();
+        push_interleaved(&mut producer, &[0.2f32; 4], 2, &overruns);
+        assert_eq!(consumer.slots(), 4);
+        assert_eq!(overruns.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn test_capture_worker_mixes_down_and_routes() {
+        let targets = capture_targets();
+        targets.pre_roll.lock().unwrap().reset(3);
+        let stop = Arc::new(AtomicBool::new(false));
+
+        // Idle: frames land in the pre-roll, which keeps only the newest ones
+        let (mut producer, consumer) = RingBuffer::new(64);
+        for frame in [[0.2f32, 0.4], [0.0, 1.0], [0.5, 0.5], [-0.5, 0.1]] {
+            push_interleaved(&mut producer, &frame, 2, &AtomicU64::new(0));
+        }
+        drop(producer);
+        let mixer = ChannelMixer::new(&ChannelMode::Average, 2, 48000);
+        run_capture_worker(consumer, mixer, targets.clone(), Arc::clone(&stop), None);
+
+        let mut pre_roll = vec![];
+        targets.pre_roll.lock().unwrap().drain_into(&mut pre_roll);
+        let expected = [0.5f32, 0.5, -0.2];
+        assert!(pre_roll
+            .iter()
+            .zip(expected)
+            .all(|(a, b)| (a - b).abs() < 1e-6));
+        assert!(targets.samples.lock().unwrap().is_empty());
+        assert_eq!(targets.current_level.load(Ordering::Relaxed), 0);
+
+        // Recording: frames go to the take and drive the level meter
+        targets.is_recording.store(true, Ordering::Release);
+        let (mut producer, consumer) = RingBuffer::new(64);
+        push_interleaved(&mut producer, &[0.3f32; 20], 2, &AtomicU64::new(0));
+        drop(producer);
+        let mixer = ChannelMixer::new(&ChannelMode::Average, 2, 48000);
+        run_capture_worker(consumer, mixer, targets.clone(), stop, None);
+
+        assert_eq!(*targets.samples.lock().unwrap(), vec![0.3f32; 10]);
+        assert!(targets.current_level.load(Ordering::Relaxed) > 0);
+    }
+
+    fn config_range(
+        channels: u16,
+        min_rate: u32,
+        max_rate: u32,
+        format: SampleFormat,
+    ) -> SupportedStreamConfigRange {
+        SupportedStreamConfigRange::new(
+            channels,
+            SampleRate(min_rate),
+            SampleRate(max_rate),
+            cpal::SupportedBufferSize::Unknown,
+            format,
+        )
+    }
+
+    #[test]
+    fn test_negotiation_prefers_native_16k_mono() {
+        let ranges = [
+            config_range(2, 8000, 96000, SampleFormat::F32),
+            config_range(1, 8000, 48000, SampleFormat::I16),
+            config_range(1, 8000, 48000, SampleFormat::I32),
+        ];
+        let default = ranges[0].with_sample_rate(SampleRate(48000));
+
+        let candidates = negotiate_input_configs(&ranges, Some(&default), 1);
+        assert_eq!(candidates[0].sample_rate().0, 16000);
+        assert_eq!(candidates[0].channels(), 1);
+        assert_eq!(candidates[0].sample_format(), SampleFormat::I32);
+        assert_eq!(candidates[1], default);
+
+        let reported = CaptureConfig::from(&candidates[0]);
+        assert_eq!(reported.sample_format, "i32");
+    }
+
+    #[test]
+    fn test_negotiation_falls_back_without_16k() {
+        // Fixed 44.1/48kHz interface with exotic formats and no usable default
+        let ranges = [
+            config_range(2, 48000, 48000, SampleFormat::U8),
+            config_range(2, 44100, 44100, SampleFormat::F64),
+        ];
+
+        let candidates = negotiate_input_configs(&ranges, None, 1);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].sample_rate().0, 44100);
+        assert_eq!(candidates[0].sample_format(), SampleFormat::F64);
+
+        assert!(negotiate_input_configs(&[], None, 1).is_empty());
+    }
+
+    #[test]
+    fn test_negotiation_keeps_enough_channels_for_the_channel_mode() {
+        let ranges = [
+            config_range(1, 8000, 48000, SampleFormat::I16),
+            config_range(4, 8000, 48000, SampleFormat::I16),
+        ];
+        let default = ranges[0].with_sample_rate(SampleRate(48000));
+
+        // Channel 3 of a 4-channel interface must not be opened as mono
+        let candidates = negotiate_input_configs(&ranges, Some(&default), 3);
+        assert!(candidates.iter().all(|config| config.channels() == 4));
+
+        // A device without enough channels still opens
+        let candidates = negotiate_input_configs(&ranges[..1], Some(&default), 3);
+        assert_eq!(candidates[0].channels(), 1);
+        assert_eq!(ChannelMode::Loudest.required_channels(4), 4);
+        assert_eq!(
+            ChannelMode::Mix(vec![1.0, 0.5, 0.0]).required_channels(4),
+            2
+        );
+    }
+
+    #[test]
+    fn test_channel_mixer_select_and_mix() {
+        // Live mic on channel 0, silent channel 1
+        let interleaved = [0.4, 0.0, -0.2, 0.0];
+        let mut mono = Vec::new();
+
+        ChannelMixer::new(&ChannelMode::Average, 2, 16000).mix(&interleaved, &mut mono);
+        assert_eq!(mono, vec![0.2, -0.1]);
+
+        mono.clear();
+        ChannelMixer::new(&ChannelMode::Channel(0), 2, 16000).mix(&interleaved, &mut mono);
+        assert_eq!(mono, vec![0.4, -0.2]);
+
+        mono.clear();
+        ChannelMixer::new(&ChannelMode::Mix(vec![0.5, 1.0, 1.0]), 2, 16000)
+            .mix(&interleaved, &mut mono);
+        assert_eq!(mono, vec![0.2, -0.1]);
+
+        // Out of range falls back to averaging
+        mono.clear();
+        ChannelMixer::new(&ChannelMode::Channel(5), 2, 16000).mix(&interleaved, &mut mono);
+        assert_eq!(mono, vec![0.2, -0.1]);
+    }
+
+    #[test]
+    fn test_channel_mixer_follows_the_loudest_channel() {
+        let mut mixer = ChannelMixer::new(&ChannelMode::Loudest, 2, 16000);
+        let frames =
+            |left: f32, right: f32| -> Vec<f32> { (0..1600).flat_map(|_| [left, right]).collect() };
+        let mut mono = Vec::new();
+
+        mixer.mix(&frames(0.5, 0.01), &mut mono);
+        assert_eq!(*mono.last().unwrap(), 0.5);
+
+        // The other speaker takes over after a moment, crossfading once
+        for _ in 0..10 {
+            mono.clear();
+            mixer.mix(&frames(0.01, 0.3), &mut mono);
+        }
+        assert_eq!(*mono.last().unwrap(), 0.3);
+        assert!(mono.iter().all(|s| *s == 0.3));
+    }
+
+    #[test]
+    fn test_pre_roll_frames_follow_warm_mic_setting() {
+        let mut settings = AudioSettings::default();
+        assert!(!settings.warm_mic, "warm microphone must be opt-in");
+        assert_eq!(settings.pre_roll_frames(48000), 0);
+
+        settings.warm_mic = true;
+        assert_eq!(settings.pre_roll_frames(48000), 72000);
+
+        settings.pre_roll_ms = 10_000;
+        assert_eq!(settings.pre_roll_frames(16000), 32000);
+    }
+
+    #[test]
+    fn test_system_audio_aligner_pads_and_drops_to_follow_the_microphone() {
+        let mut aligner = SystemAudioAligner::new(1000);
+
+        // Pre-roll silence first, then the system audio that arrived
+        aligner.pad(3);
+        aligner.push(&[0.5, 0.5]);
+        assert_eq!(aligner.take(4), vec![0.0, 0.0, 0.0, 0.5]);
+
+        // Nothing delivered while the system was silent
+        assert_eq!(aligner.take(2), vec![0.5, 0.0]);
+
+        // A backlog beyond the 200 ms lag loses its oldest samples
+        aligner.push(&vec![0.1; 300]);
+        aligner.push(&[0.9; 10]);
+        let taken = aligner.take(10);
+        assert_eq!(taken, vec![0.1; 10]);
+        assert_eq!(aligner.pending.len(), 200);
+        assert_eq!(aligner.pending.back(), Some(&0.9));
+
+        assert!(is_loopback_name("Monitor of Built-in Audio Analog Stereo"));
+        assert!(is_loopback_name("Stereo Mix (Realtek(R) Audio)"));
+        assert!(!is_loopback_name("USB Microphone"));
+    }
+
+    #[test]
+    fn test_two_tracks_are_trimmed_on_a_shared_timeline() {
+        let mic = [SpeechSegment {
+            start_ms: 1000,
+            end_ms: 2000,
+        }];
+        let system = [
+            SpeechSegment {
+                start_ms: 1500,
+                end_ms: 3000,
+            },
+            SpeechSegment {
+                start_ms: 5000,
+                end_ms: 6000,
+            },
+        ];
+        let merged = merge_segments(&mic, &system);
+        assert_eq!(
+            merged,
+            vec![
+                SpeechSegment {
+                    start_ms: 1000,
+                    end_ms: 3000
+                },
+                SpeechSegment {
+                    start_ms: 5000,
+                    end_ms: 6000
+                },
+            ]
+        );
+
+        // Cut at 750 ms for 5500 ms: both tracks move by the same offset
+        let offset_ms = trim_start_ms(&merged[0]);
+        assert_eq!(
+            shift_segments(&system, offset_ms, 5500)[1],
+            SpeechSegment {
+                start_ms: 4250,
+                end_ms: 5250
+            }
+        );
+        assert_eq!(shift_segments(&mic, offset_ms, 5500)[0].start_ms, 250);
+    }
+
     #[test]
     fn test_default_settings() {
         let settings = AudioSettings::default();
         assert_eq!(settings.max_duration_minutes, 6);
         assert!(settings.privacy_mode);
         assert!(settings.device_id.is_none());
+        assert_eq!(settings.system_audio, SystemAudioMode::Off);
     }
-}
 
+    #[test]
+    fn test_preprocessing_is_opt_in() {
+        let settings = AudioSettings::default();
+        assert!(!settings.high_pass_filter);
+        assert!(!settings.noise_suppression);
+        assert!(!settings.loudness_normalization);
+
+        // A config saved before the preprocessing existed keeps recording unchanged
+        let json = r#"{"device_id": null, "max_duration_minutes": 6, "privacy_mode": true}"#;
+        let settings: AudioSettings = serde_json::from_str(json).unwrap();
+        assert!(!settings.high_pass_filter && !settings.loudness_normalization);
+    }
+}
@@ -0,0 +1,334 @@
+//! Compressed copies of recordings for the archive
+//!
+//! The 16kHz WAVs in the recordings directory are a cache and get purged. When
+//! the user wants to keep the audio of a take, it is encoded here and stored
+//! next to the Markdown note:
+//!
+//! - Opus in an Ogg container: small (24 kbit/s), lossy, plays everywhere
+//! - FLAC: lossless, roughly half the size of the WAV
+//!
+//! Both encoders work on the whole take in memory, so the result can be sealed
+//! with the at-rest key before it touches the disk.
+
+use crate::encryption;
+use audiopus::coder::Encoder as OpusEncoder;
+use audiopus::{Application, Bitrate, Channels, SampleRate as OpusSampleRate};
+use flacenc::component::BitRepr;
+use flacenc::error::Verify;
+use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+use std::io::Cursor;
+use std::path::Path;
+
+/// Granule positions and pre-skip in Ogg Opus are always counted at 48kHz
+const OGG_OPUS_RATE: u32 = 48_000;
+
+/// Bitrate for archived speech, transparent for voice at 16kHz
+const OPUS_BITRATE: i32 = 24_000;
+
+/// Opus frame length
+const OPUS_FRAME_MS: u32 = 20;
+
+/// Largest Opus packet we ask libopus for
+const OPUS_MAX_PACKET: usize = 4000;
+
+/// Format of the audio kept next to an archived transcription
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum AudioFormat {
+    /// Ogg Opus, lossy and small
+    Opus,
+    /// FLAC, lossless
+    Flac,
+}
+
+impl AudioFormat {
+    /// File extension of the encoded audio
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AudioFormat::Opus => "opus",
+            AudioFormat::Flac => "flac",
+        }
+    }
+}
+
+/// Errors that can occur while encoding a recording
+#[derive(Debug, thiserror::Error)]
+pub enum EncodeError {
+    #[error("Failed to read recording: {0}")]
+    Read(String),
+
+    #[error("Unsupported recording: {0}")]
+    Unsupported(String),
+
+    #[error("Opus encoder error: {0}")]
+    Opus(String),
+
+    #[error("Ogg container error: {0}")]
+    Ogg(String),
+
+    #[error("FLAC encoder error: {0}")]
+    Flac(String),
+}
+
+impl serde::Serialize for EncodeError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl From<audiopus::Error> for EncodeError {
+    fn from(e: audiopus::Error) -> Self {
+        EncodeError::Opus(e.to_string())
+    }
+}
+
+/// Encode a mono 16-bit recording (sealed or not) into `format`
+pub fn encode_recording(path: &Path, format: AudioFormat) -> Result<Vec<u8>, EncodeError> {
+    let bytes = encryption::read(path).map_err(|e| EncodeError::Read(e.to_string()))?;
+    let reader =
+        hound::WavReader::new(Cursor::new(bytes)).map_err(|e| EncodeError::Read(e.to_string()))?;
+    let spec = reader.spec();
+    if spec.channels != 1 || spec.bits_per_sample != 16 {
+        return Err(EncodeError::Unsupported(format!(
+            "{} channels, {} bit",
+            spec.channels, spec.bits_per_sample
+        )));
+    }
+    let samples = reader
+        .into_samples::<i16>()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| EncodeError::Read(e.to_string()))?;
+
+    match format {
+        AudioFormat::Opus => encode_opus(&samples, spec.sample_rate),
+        AudioFormat::Flac => encode_flac(&samples, spec.sample_rate),
+    }
+}
+
+/// Map a sample rate onto one libopus accepts as input
+fn opus_input_rate(sample_rate: u32) -> Result<OpusSampleRate, EncodeError> {
+    match sample_rate {
+        8_000 => Ok(OpusSampleRate::Hz8000),
+        12_000 => Ok(OpusSampleRate::Hz12000),
+        16_000 => Ok(OpusSampleRate::Hz16000),
+        24_000 => Ok(OpusSampleRate::Hz24000),
+        48_000 => Ok(OpusSampleRate::Hz48000),
+        other => Err(EncodeError::Unsupported(format!(
+            "{} Hz is not an Opus input rate",
+            other
+        ))),
+    }
+}
+
+/// Encode mono samples as an Ogg Opus file (RFC 7845)
+pub fn encode_opus(samples: &[i16], sample_rate: u32) -> Result<Vec<u8>, EncodeError> {
+    let mut encoder = OpusEncoder::new(
+        opus_input_rate(sample_rate)?,
+        Channels::Mono,
+        Application::Voip,
+    )?;
+    encoder.set_bitrate(Bitrate::BitsPerSecond(OPUS_BITRATE))?;
+
+    // Everything at 48kHz: one input sample is `scale` output samples
+    let scale = (OGG_OPUS_RATE / sample_rate) as u64;
+    let lookahead = encoder.lookahead()? as usize;
+    let pre_skip = lookahead as u64 * scale;
+    let frame_len = (sample_rate * OPUS_FRAME_MS / 1000) as usize;
+    // Flush the encoder's look-ahead with trailing silence
+    let frame_count = (samples.len() + lookahead).div_ceil(frame_len);
+    let end_granule = pre_skip + samples.len() as u64 * scale;
+
+    let ogg_err = |e: std::io::Error| EncodeError::Ogg(e.to_string());
+    let mut writer = PacketWriter::new(Vec::new());
+    writer
+        .write_packet(
+            opus_head(pre_skip as u16, sample_rate).into_boxed_slice(),
+            1,
+            PacketWriteEndInfo::EndPage,
+            0,
+        )
+        .map_err(ogg_err)?;
+    writer
+        .write_packet(
+            opus_tags().into_boxed_slice(),
+            1,
+            PacketWriteEndInfo::EndPage,
+            0,
+        )
+        .map_err(ogg_err)?;
+
+    let mut frame = vec![0i16; frame_len];
+    let mut packet = [0u8; OPUS_MAX_PACKET];
+    for index in 0..frame_count {
+        let start = (index * frame_len).min(samples.len());
+        let end = (start + frame_len).min(samples.len());
+        frame.fill(0);
+        frame[..end - start].copy_from_slice(&samples[start..end]);
+
+        let len = encoder.encode(&frame, &mut packet)?;
+        let last = index + 1 == frame_count;
+        // The last page's granule trims the padding off the end
+        let granule = if last {
+            end_granule
+        } else {
+            ((index + 1) * frame_len) as u64 * scale
+        };
+        let end_info = if last {
+            PacketWriteEndInfo::EndStream
+        } else {
+            PacketWriteEndInfo::NormalPacket
+        };
+        writer
+            .write_packet(packet[..len].into(), 1, end_info, granule)
+            .map_err(ogg_err)?;
+    }
+
+    Ok(writer.into_inner())
+}
+
+/// Identification header of a mono Ogg Opus stream
+fn opus_head(pre_skip: u16, input_rate: u32) -> Vec<u8> {
+    let mut head = b"OpusHead".to_vec();
+    head.push(1); // version
+    head.push(1); // channels
+    head.extend_from_slice(&pre_skip.to_le_bytes());
+    head.extend_from_slice(&input_rate.to_le_bytes());
+    head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+    head.push(0); // mapping family: mono/stereo
+    head
+}
+
+/// Comment header with our vendor string and no comments
+fn opus_tags() -> Vec<u8> {
+    let vendor = b"EverVoice";
+    let mut tags = b"OpusTags".to_vec();
+    tags.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    tags.extend_from_slice(vendor);
+    tags.extend_from_slice(&0u32.to_le_bytes());
+    tags
+}
+
+/// Encode mono 16-bit samples as a FLAC file
+pub fn encode_flac(samples: &[i16], sample_rate: u32) -> Result<Vec<u8>, EncodeError> {
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|(_, e)| EncodeError::Flac(e.to_string()))?;
+
+    let samples: Vec<i32> = samples.iter().map(|&sample| sample as i32).collect();
+    let source = flacenc::source::MemSource::from_samples(&samples, 1, 16, sample_rate as usize);
+    let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+        .map_err(|e| EncodeError::Flac(e.to_string()))?;
+
+    let mut sink = flacenc::bitsink::ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|e| EncodeError::Flac(e.to_string()))?;
+    Ok(sink.as_slice().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio_import::import_audio_file;
+    use crate::test_util::{sine, write_wav, TempPath};
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    /// Two seconds of a voice-like tone with a quiet and a silent stretch
+    fn test_samples() -> Vec<i16> {
+        let base = sine(220.0, 1.0, 16_000, 32_000);
+        let overtone = sine(1_330.0, 0.3, 16_000, 32_000);
+        base.iter()
+            .zip(&overtone)
+            .enumerate()
+            .map(|(i, (base, overtone))| {
+                let amplitude = match i {
+                    0..=11_999 => 9_000.0,
+                    12_000..=19_999 => 300.0,
+                    _ => 0.0,
+                };
+                ((base + overtone) * amplitude) as i16
+            })
+            .collect()
+    }
+
+    /// Decode a FLAC file with symphonia's reference decoder
+    fn decode_flac(bytes: Vec<u8>) -> (u32, Vec<i16>) {
+        let stream = MediaSourceStream::new(Box::new(Cursor::new(bytes)), Default::default());
+        let mut hint = Hint::new();
+        hint.with_extension("flac");
+        let mut format = symphonia::default::get_probe()
+            .format(
+                &hint,
+                stream,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .unwrap()
+            .format;
+        let params = format.default_track().unwrap().codec_params.clone();
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&params, &Default::default())
+            .unwrap();
+
+        let mut samples = Vec::new();
+        while let Ok(packet) = format.next_packet() {
+            let decoded = decoder.decode(&packet).unwrap();
+            let mut buffer = SampleBuffer::<i16>::new(decoded.capacity() as u64, *decoded.spec());
+            buffer.copy_interleaved_ref(decoded);
+            samples.extend_from_slice(buffer.samples());
+        }
+        (params.sample_rate.unwrap(), samples)
+    }
+
+    #[test]
+    fn test_flac_is_lossless() {
+        let samples = test_samples();
+        let flac = encode_flac(&samples, 16_000).unwrap();
+        // Less than half the size of the 16-bit WAV
+        assert!(flac.len() < samples.len(), "{} bytes", flac.len());
+
+        let (rate, decoded) = decode_flac(flac);
+        assert_eq!(rate, 16_000);
+        assert_eq!(decoded, samples);
+
+        // A take shorter than one block
+        let short = &samples[..1000];
+        assert_eq!(decode_flac(encode_flac(short, 16_000).unwrap()).1, short);
+    }
+
+    #[test]
+    fn test_opus_keeps_duration() {
+        let samples = test_samples();
+        let opus = encode_opus(&samples, 16_000).unwrap();
+        // 24 kbit/s for two seconds, plus container overhead
+        assert!(opus.len() < 8_000, "{} bytes", opus.len());
+
+        let path = TempPath::file("encode.opus");
+        let recordings = TempPath::dir("recordings");
+        std::fs::write(&path, &opus).unwrap();
+        let result = import_audio_file(&path, &recordings).unwrap();
+        // Pre-skip and end trimming give back the original length
+        assert!(
+            (1_980..=2_020).contains(&result.duration_ms),
+            "{}ms",
+            result.duration_ms
+        );
+    }
+
+    #[test]
+    fn test_encode_recording_rejects_stereo() {
+        let path = TempPath::file("encode.wav");
+        write_wav(&path, 16_000, 2, &[0.0, 0.0]);
+
+        assert!(matches!(
+            encode_recording(&path, AudioFormat::Flac),
+            Err(EncodeError::Unsupported(_))
+        ));
+    }
+}
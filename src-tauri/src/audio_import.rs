@@ -0,0 +1,472 @@
+//! Audio file import
+//!
+//! Decodes audio files from other tools (voice memos, meeting recordings) so
+//! they can go through the same transcription, edit and archive pipeline as
+//! our own recordings. Supported: MP3, FLAC, OGG (Vorbis and Opus), M4A/AAC
+//! and WAV.
+//!
+//! The file is decoded packet by packet, downmixed to mono and streamed through
+//! the recorder's resampling WAV writer into the recordings directory, so even
+//! hour-long files never sit in memory at the source rate.
+
+use crate::audio::{
+    AudioError, RecordingResult, SpeechSegment, StreamingWavWriter, VoiceActivityDetector,
+    WHISPER_SAMPLE_RATE,
+};
+use crate::audio_quality::{QualityAnalyzer, QualityReport};
+use crate::encryption;
+use audiopus::coder::Decoder as OpusDecoder;
+use audiopus::{Channels, SampleRate as OpusSampleRate};
+use std::fs;
+use std::path::{Path, PathBuf};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CodecParameters, DecoderOptions, CODEC_TYPE_NULL, CODEC_TYPE_OPUS};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use uuid::Uuid;
+
+/// File extensions offered in the import dialog
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "mp3", "flac", "ogg", "oga", "opus", "m4a", "mp4", "aac", "wav",
+];
+
+/// Opus always decodes at 48kHz
+const OPUS_SAMPLE_RATE: u32 = 48_000;
+
+/// Largest Opus frame (120 ms at 48kHz)
+const OPUS_MAX_FRAME: usize = 5760;
+
+/// Errors that can occur while importing an audio file
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    #[error("File not found: {0}")]
+    FileNotFound(String),
+
+    #[error("Unsupported audio format: {0}")]
+    UnsupportedFormat(String),
+
+    #[error("Failed to decode audio: {0}")]
+    DecodeError(String),
+
+    #[error("The file contains no audio")]
+    NoAudio,
+
+    #[error(transparent)]
+    Audio(#[from] AudioError),
+}
+
+impl serde::Serialize for ImportError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl From<SymphoniaError> for ImportError {
+    fn from(e: SymphoniaError) -> Self {
+        match e {
+            SymphoniaError::Unsupported(what) => ImportError::UnsupportedFormat(what.to_string()),
+            other => ImportError::DecodeError(other.to_string()),
+        }
+    }
+}
+
+/// Receives decoded mono audio at the source rate
+///
+/// Created lazily, because some containers only reveal the sample rate with
+/// the first decoded packet.
+struct ImportSink {
+    file_path: PathBuf,
+    writer: Option<StreamingWavWriter>,
+    vad: Option<VoiceActivityDetector>,
+    quality: Option<QualityAnalyzer>,
+    sample_rate: u32,
+}
+
+impl ImportSink {
+    fn new(file_path: PathBuf) -> Self {
+        Self {
+            file_path,
+            writer: None,
+            vad: None,
+            quality: None,
+            sample_rate: 0,
+        }
+    }
+
+    fn push(&mut self, samples: &[f32], sample_rate: u32) -> Result<(), ImportError> {
+        if samples.is_empty() {
+            return Ok(());
+        }
+        if self.writer.is_none() {
+            self.writer = Some(StreamingWavWriter::create(&self.file_path, sample_rate)?);
+            self.vad = Some(VoiceActivityDetector::new(sample_rate));
+            self.quality = Some(QualityAnalyzer::new(sample_rate));
+            self.sample_rate = sample_rate;
+        } else if sample_rate != self.sample_rate {
+            return Err(ImportError::DecodeError(format!(
+                "Sample rate changed mid-stream ({} -> {} Hz)",
+                self.sample_rate, sample_rate
+            )));
+        }
+
+        if let Some(vad) = self.vad.as_mut() {
+            vad.push(samples);
+        }
+        if let Some(quality) = self.quality.as_mut() {
+            quality.push(samples);
+        }
+        if let Some(writer) = self.writer.as_mut() {
+            writer.write(samples)?;
+        }
+        Ok(())
+    }
+
+    /// Close the WAV file; returns the 16kHz sample count, speech segments
+    /// and the quality check of the decoded audio
+    fn finish(self) -> Result<(u64, Vec<SpeechSegment>, QualityReport), ImportError> {
+        let (Some(writer), Some(vad), Some(quality)) = (self.writer, self.vad, self.quality) else {
+            return Err(ImportError::NoAudio);
+        };
+        let samples_written = writer.finalize()?;
+        let speech_segments = vad.finish();
+        let report = quality.report(&speech_segments, None);
+        Ok((samples_written, speech_segments, report))
+    }
+}
+
+/// Average interleaved frames down to mono
+fn downmix(interleaved: &[f32], channels: usize) -> Vec<f32> {
+    if channels <= 1 {
+        return interleaved.to_vec();
+    }
+    interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+/// Open a file and pick its first audio track
+fn open_format(path: &Path) -> Result<(Box<dyn FormatReader>, u32, CodecParameters), ImportError> {
+    let file = fs::File::open(path).map_err(|e| ImportError::FileNotFound(e.to_string()))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe().format(
+        &hint,
+        stream,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+    let format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or(ImportError::NoAudio)?;
+    let track_id = track.id;
+    let codec_params = track.codec_params.clone();
+
+    Ok((format, track_id, codec_params))
+}
+
+/// Read the next packet of `track_id`; None at the end of the stream
+fn next_packet(
+    format: &mut dyn FormatReader,
+    track_id: u32,
+) -> Result<Option<symphonia::core::formats::Packet>, ImportError> {
+    loop {
+        match format.next_packet() {
+            Ok(packet) if packet.track_id() == track_id => return Ok(Some(packet)),
+            Ok(_) => continue,
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                return Ok(None)
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Decode with one of symphonia's built-in codecs
+fn decode_with_symphonia(
+    format: &mut dyn FormatReader,
+    track_id: u32,
+    codec_params: &CodecParameters,
+    sink: &mut ImportSink,
+) -> Result<(), ImportError> {
+    let mut decoder =
+        symphonia::default::get_codecs().make(codec_params, &DecoderOptions::default())?;
+    let mut buffer: Option<SampleBuffer<f32>> = None;
+
+    while let Some(packet) = next_packet(format, track_id)? {
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A damaged frame shouldn't lose the rest of the file
+            Err(SymphoniaError::DecodeError(e)) => {
+                log::warn!("Skipping undecodable packet: {}", e);
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let spec = *decoded.spec();
+        let needs_new_buffer = buffer
+            .as_ref()
+            .map(|b| b.capacity() < decoded.capacity() * spec.channels.count())
+            .unwrap_or(true);
+        if needs_new_buffer {
+            buffer = Some(SampleBuffer::new(decoded.capacity() as u64, spec));
+        }
+
+        if let Some(buffer) = buffer.as_mut() {
+            buffer.copy_interleaved_ref(decoded);
+            let mono = downmix(buffer.samples(), spec.channels.count());
+            sink.push(&mono, spec.rate)?;
+        }
+    }
+    Ok(())
+}
+
+/// Decode Ogg Opus packets with libopus (symphonia only demuxes Opus)
+fn decode_opus(
+    format: &mut dyn FormatReader,
+    track_id: u32,
+    codec_params: &CodecParameters,
+    sink: &mut ImportSink,
+) -> Result<(), ImportError> {
+    // libopus downmixes to the requested channel count itself
+    let mut decoder = OpusDecoder::new(OpusSampleRate::Hz48000, Channels::Mono)
+        .map_err(|e| ImportError::DecodeError(e.to_string()))?;
+    let mut pre_skip = codec_params.delay.unwrap_or(0) as usize;
+    let mut frame = vec![0.0f32; OPUS_MAX_FRAME];
+
+    while let Some(packet) = next_packet(format, track_id)? {
+        let input = packet
+            .buf()
+            .try_into()
+            .map_err(|e: audiopus::Error| ImportError::DecodeError(e.to_string()))?;
+        let output = (&mut frame[..])
+            .try_into()
+            .map_err(|e: audiopus::Error| ImportError::DecodeError(e.to_string()))?;
+
+        let decoded = match decoder.decode_float(Some(input), output, false) {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                log::warn!("Skipping undecodable Opus packet: {}", e);
+                continue;
+            }
+        };
+
+        // The encoder's look-ahead at the start is not part of the audio
+        let skip = pre_skip.min(decoded);
+        pre_skip -= skip;
+        sink.push(&frame[skip..decoded], OPUS_SAMPLE_RATE)?;
+    }
+    Ok(())
+}
+
+/// Decode `path` into a new 16kHz mono WAV in `recordings_dir`
+///
+/// The result looks like a finished recording: the caller transcribes it with
+/// `transcribe_audio` and deletes it afterwards. The source file is untouched.
+pub fn import_audio_file(
+    path: &Path,
+    recordings_dir: &Path,
+) -> Result<RecordingResult, ImportError> {
+    if !path.is_file() {
+        return Err(ImportError::FileNotFound(path.display().to_string()));
+    }
+
+    let (mut format, track_id, codec_params) = open_format(path)?;
+    log::info!(
+        "Importing {:?}: codec {:?}, {} Hz, {} channels",
+        path,
+        codec_params.codec,
+        codec_params.sample_rate.unwrap_or(0),
+        codec_params.channels.map(|c| c.count()).unwrap_or(0)
+    );
+
+    let file_path = recordings_dir.join(format!("{}.wav", Uuid::new_v4()));
+    let mut sink = ImportSink::new(file_path.clone());
+
+    let decoded = if codec_params.codec == CODEC_TYPE_OPUS {
+        decode_opus(format.as_mut(), track_id, &codec_params, &mut sink)
+    } else {
+        decode_with_symphonia(format.as_mut(), track_id, &codec_params, &mut sink)
+    };
+
+    let (samples_written, speech_segments, quality) = match decoded.and_then(|_| sink.finish()) {
+        Ok(result) => result,
+        Err(e) => {
+            let _ = fs::remove_file(&file_path);
+            return Err(e);
+        }
+    };
+    if samples_written == 0 {
+        let _ = fs::remove_file(&file_path);
+        return Err(ImportError::NoAudio);
+    }
+    if let Err(e) = encryption::seal_file(&file_path) {
+        let _ = fs::remove_file(&file_path);
+        return Err(AudioError::Encryption(e.to_string()).into());
+    }
+
+    let duration_ms = samples_written * 1000 / WHISPER_SAMPLE_RATE as u64;
+    log::info!(
+        "Import complete: {}ms, {} speech segments, saved to {:?}",
+        duration_ms,
+        speech_segments.len(),
+        file_path
+    );
+
+    Ok(RecordingResult {
+        file_path: file_path.to_string_lossy().to_string(),
+        duration_ms,
+        // The converted copy is only a working file, always clean it up
+        privacy_mode: true,
+        speech_segments,
+        dropped_frames: 0,
+        capture_config: None,
+        device_gaps: Vec::new(),
+        pause_markers: Vec::new(),
+        quality: Some(quality),
+        system_track: None,
+        trim_offset_ms: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{sine, write_wav, TempPath};
+
+    fn write_stereo_wav(path: &Path, sample_rate: u32, seconds: u32) {
+        let tone = sine(440.0, 0.5, sample_rate, (sample_rate * seconds) as usize);
+        // Left carries the tone, right is silent
+        let interleaved: Vec<f32> = tone.iter().flat_map(|&s| [s, 0.0]).collect();
+        write_wav(path, sample_rate, 2, &interleaved);
+    }
+
+    #[test]
+    fn test_downmix_averages_channels() {
+        assert_eq!(downmix(&[1.0, 0.0, 0.5, 0.5], 2), vec![0.5, 0.5]);
+        assert_eq!(downmix(&[0.25, 0.75], 1), vec![0.25, 0.75]);
+    }
+
+    #[test]
+    fn test_import_resamples_and_downmixes() {
+        let source = TempPath::file("import.wav");
+        let recordings = TempPath::dir("recordings");
+        write_stereo_wav(&source, 44_100, 2);
+
+        let result = import_audio_file(&source, &recordings).unwrap();
+        assert_eq!(result.duration_ms, 2000);
+        assert!(result.privacy_mode);
+
+        let reader = hound::WavReader::open(&result.file_path).unwrap();
+        assert_eq!(reader.spec().sample_rate, WHISPER_SAMPLE_RATE);
+        assert_eq!(reader.spec().channels, 1);
+        let peak = reader
+            .into_samples::<i16>()
+            .map(|s| s.unwrap().unsigned_abs())
+            .max()
+            .unwrap();
+        // Half of the left channel's amplitude after averaging
+        assert!((7_000..9_000).contains(&peak), "peak {}", peak);
+    }
+
+    /// Encode one second of a tone as Ogg Opus (mono, 20 ms frames)
+    fn write_ogg_opus(path: &Path) {
+        use audiopus::coder::Encoder;
+        use audiopus::Application;
+        use ogg::writing::{PacketWriteEndInfo, PacketWriter};
+
+        const PRE_SKIP: u16 = 312;
+        let encoder =
+            Encoder::new(OpusSampleRate::Hz48000, Channels::Mono, Application::Voip).unwrap();
+        let mut writer = PacketWriter::new(fs::File::create(path).unwrap());
+
+        let mut head = b"OpusHead".to_vec();
+        head.push(1); // version
+        head.push(1); // channels
+        head.extend_from_slice(&PRE_SKIP.to_le_bytes());
+        head.extend_from_slice(&OPUS_SAMPLE_RATE.to_le_bytes());
+        head.extend_from_slice(&0i16.to_le_bytes()); // output gain
+        head.push(0); // mapping family
+        writer
+            .write_packet(head.into(), 1, PacketWriteEndInfo::EndPage, 0)
+            .unwrap();
+
+        let mut tags = b"OpusTags".to_vec();
+        tags.extend_from_slice(&4u32.to_le_bytes());
+        tags.extend_from_slice(b"test");
+        tags.extend_from_slice(&0u32.to_le_bytes());
+        writer
+            .write_packet(tags.into(), 1, PacketWriteEndInfo::EndPage, 0)
+            .unwrap();
+
+        let tone = sine(440.0, 0.5, OPUS_SAMPLE_RATE, OPUS_SAMPLE_RATE as usize);
+        let frames: Vec<&[f32]> = tone.chunks(960).collect();
+        let mut packet = [0u8; 4000];
+        for (i, frame) in frames.iter().enumerate() {
+            let len = encoder.encode_float(frame, &mut packet).unwrap();
+            let end = if i + 1 == frames.len() {
+                PacketWriteEndInfo::EndStream
+            } else {
+                PacketWriteEndInfo::NormalPacket
+            };
+            let granule = ((i + 1) * 960) as u64;
+            writer
+                .write_packet(packet[..len].to_vec().into(), 1, end, granule)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_import_decodes_ogg_opus() {
+        let source = TempPath::file("import.opus");
+        let recordings = TempPath::dir("recordings");
+        write_ogg_opus(&source);
+
+        let result = import_audio_file(&source, &recordings).unwrap();
+        // One second minus the 312 pre-skip samples
+        assert!(
+            (980..=1000).contains(&result.duration_ms),
+            "{}ms",
+            result.duration_ms
+        );
+        let reader = hound::WavReader::open(&result.file_path).unwrap();
+        assert_eq!(reader.spec().sample_rate, WHISPER_SAMPLE_RATE);
+        let peak = reader
+            .into_samples::<i16>()
+            .map(|s| s.unwrap().unsigned_abs())
+            .max()
+            .unwrap();
+        assert!(peak > 10_000, "peak {}", peak);
+    }
+
+    #[test]
+    fn test_import_rejects_non_audio() {
+        let source = TempPath::file("import.mp3");
+        let recordings = TempPath::dir("recordings");
+        fs::write(&source, b"definitely not an mp3 file").unwrap();
+
+        assert!(import_audio_file(&source, &recordings).is_err());
+        assert!(matches!(
+            import_audio_file(Path::new("/nonexistent/memo.m4a"), &recordings),
+            Err(ImportError::FileNotFound(_))
+        ));
+        // Nothing is left behind for a file that did not decode
+        assert_eq!(fs::read_dir(&recordings).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,419 @@
+//! Audio quality check before transcription
+//!
+//! Looks at the raw input signal of a take (before any preprocessing, so the
+//! numbers describe the microphone and not the DSP chain) and rates it:
+//! - Speech level and noise floor, giving an SNR estimate
+//! - Clipping ratio and peak level
+//! - Share of the take the VAD marked as speech
+//! - DC offset
+//!
+//! The verdict comes with suggested fixes that can be shown to the user, and
+//! a take without any speech can skip Whisper altogether.
+
+use crate::audio::{SpeechSegment, CLIP_THRESHOLD, METER_FLOOR_DBFS};
+
+/// Length of one analysis frame in milliseconds
+const FRAME_MS: u64 = 20;
+
+/// Peak level below which the input is considered dead (muted or wrong device)
+const NO_SIGNAL_DBFS: f32 = -70.0;
+
+/// Speech level below which the input is flagged as quiet
+const QUIET_DBFS: f32 = -40.0;
+
+/// Speech level below which Whisper is likely to miss words
+const VERY_QUIET_DBFS: f32 = -55.0;
+
+/// SNR below which background noise is worth mentioning
+const NOISY_SNR_DB: f32 = 15.0;
+
+/// SNR below which the take is rated poor
+const VERY_NOISY_SNR_DB: f32 = 6.0;
+
+/// Clipped sample share above which clipping is worth mentioning
+const CLIPPING_RATIO: f32 = 0.0005;
+
+/// Clipped sample share above which the take is rated poor
+const HEAVY_CLIPPING_RATIO: f32 = 0.005;
+
+/// Mean sample value above which the input has a DC offset
+const DC_OFFSET_LIMIT: f32 = 0.02;
+
+/// Overall verdict for a take
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum RecordingQuality {
+    Good,
+    Fair,
+    Poor,
+    /// Nothing that looks like speech, transcription can be skipped
+    NoSpeech,
+}
+
+/// A single problem found in a take
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum QualityIssue {
+    NoSignal,
+    TooQuiet,
+    Clipping,
+    Noisy,
+    DcOffset,
+    NoSpeech,
+}
+
+/// Measurements and verdict for a take
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct QualityReport {
+    pub quality: RecordingQuality,
+    /// Average level while speaking (loudest frames if no speech was found)
+    pub speech_level_dbfs: f32,
+    /// Level of the quietest non-speech frames
+    pub noise_floor_dbfs: f32,
+    pub peak_dbfs: f32,
+    /// Estimated signal-to-noise ratio
+    pub snr_db: f32,
+    /// Share of samples at full scale (0-1)
+    pub clipping_ratio: f32,
+    /// Share of the take the VAD marked as speech (0-1)
+    pub speech_ratio: f32,
+    /// Mean sample value, magnitude only
+    pub dc_offset: f32,
+    pub issues: Vec<QualityIssue>,
+    /// Suggested fixes in display order, one per issue
+    pub suggestions: Vec<String>,
+}
+
+/// Collects signal statistics while a take is being recorded
+///
+/// Keeps one power value per 20 ms frame, so the verdict can be matched
+/// against the speech segments the VAD reports at the end of the take.
+pub struct QualityAnalyzer {
+    frame_len: usize,
+    frame_power: f64,
+    frame_fill: usize,
+    frame_powers: Vec<f32>,
+    sum: f64,
+    sample_count: u64,
+    clipped: u64,
+    peak: f32,
+}
+
+impl QualityAnalyzer {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            frame_len: ((sample_rate as u64 * FRAME_MS / 1000) as usize).max(1),
+            frame_power: 0.0,
+            frame_fill: 0,
+            frame_powers: Vec::new(),
+            sum: 0.0,
+            sample_count: 0,
+            clipped: 0,
+            peak: 0.0,
+        }
+    }
+
+    /// Feed mono samples at the analyzer's sample rate
+    pub fn push(&mut self, samples: &[f32]) {
+        for &sample in samples {
+            let magnitude = sample.abs();
+            self.peak = self.peak.max(magnitude);
+            if magnitude >= CLIP_THRESHOLD {
+                self.clipped += 1;
+            }
+            self.sum += sample as f64;
+            self.sample_count += 1;
+
+            self.frame_power += (sample as f64) * (sample as f64);
+            self.frame_fill += 1;
+            if self.frame_fill == self.frame_len {
+                self.frame_powers
+                    .push((self.frame_power / self.frame_len as f64) as f32);
+                self.frame_power = 0.0;
+                self.frame_fill = 0;
+            }
+        }
+    }
+
+    /// Rate the take against the speech segments found in it
+    ///
+    /// `device_name` is used to point the suggestions at the right microphone.
+    pub fn report(
+        &self,
+        speech_segments: &[SpeechSegment],
+        device_name: Option<&str>,
+    ) -> QualityReport {
+        let dc = if self.sample_count > 0 {
+            (self.sum / self.sample_count as f64) as f32
+        } else {
+            0.0
+        };
+        // Frame powers without the DC component, which would otherwise
+        // raise the noise floor and hide a quiet voice
+        let dc_power = dc * dc;
+        let frame_powers: Vec<f32> = self
+            .frame_powers
+            .iter()
+            .map(|power| (power - dc_power).max(0.0))
+            .collect();
+
+        let is_speech = |index: usize| {
+            let center_ms = index as u64 * FRAME_MS + FRAME_MS / 2;
+            speech_segments
+                .iter()
+                .any(|segment| center_ms >= segment.start_ms && center_ms < segment.end_ms)
+        };
+        let mut speech = Vec::new();
+        let mut background = Vec::new();
+        for (index, &power) in frame_powers.iter().enumerate() {
+            if is_speech(index) {
+                speech.push(power);
+            } else {
+                background.push(power);
+            }
+        }
+
+        let speech_power = if speech.is_empty() {
+            percentile(&frame_powers, 0.95)
+        } else {
+            speech.iter().sum::<f32>() / speech.len() as f32
+        };
+        let noise_power = if background.is_empty() {
+            percentile(&frame_powers, 0.1)
+        } else {
+            percentile(&background, 0.1)
+        };
+
+        let speech_level_dbfs = power_to_dbfs(speech_power);
+        let noise_floor_dbfs = power_to_dbfs(noise_power);
+        let peak_dbfs = if self.peak > 0.0 {
+            (20.0 * self.peak.log10()).max(METER_FLOOR_DBFS)
+        } else {
+            METER_FLOOR_DBFS
+        };
+        let clipping_ratio = if self.sample_count > 0 {
+            self.clipped as f32 / self.sample_count as f32
+        } else {
+            0.0
+        };
+        let speech_ratio = if frame_powers.is_empty() {
+            0.0
+        } else {
+            speech.len() as f32 / frame_powers.len() as f32
+        };
+
+        let mut report = QualityReport {
+            quality: RecordingQuality::Good,
+            speech_level_dbfs,
+            noise_floor_dbfs,
+            peak_dbfs,
+            snr_db: (speech_level_dbfs - noise_floor_dbfs).max(0.0),
+            clipping_ratio,
+            speech_ratio,
+            dc_offset: dc.abs(),
+            issues: Vec::new(),
+            suggestions: Vec::new(),
+        };
+        report.rate(!speech_segments.is_empty(), device_name);
+        report
+    }
+}
+
+impl QualityReport {
+    /// Whether the take can skip transcription
+    pub fn is_silent(&self) -> bool {
+        self.quality == RecordingQuality::NoSpeech
+    }
+
+    /// Fill in issues, suggestions and the verdict from the measurements
+    fn rate(&mut self, has_speech: bool, device_name: Option<&str>) {
+        let microphone = device_name
+            .filter(|name| !name.is_empty())
+            .map(|name| format!("Mikrofon „{}“", name))
+            .unwrap_or_else(|| "das Mikrofon".to_string());
+        let mut poor = false;
+        let mut fair = false;
+
+        if self.peak_dbfs < NO_SIGNAL_DBFS {
+            poor = true;
+            self.add(
+                QualityIssue::NoSignal,
+                format!(
+                    "Kein Eingangssignal – {} prüfen: richtiges Gerät gewählt und nicht stummgeschaltet?",
+                    microphone
+                ),
+            );
+        } else if self.speech_level_dbfs < QUIET_DBFS {
+            let very_quiet = self.speech_level_dbfs < VERY_QUIET_DBFS;
+            poor |= very_quiet;
+            fair = true;
+            self.add(
+                QualityIssue::TooQuiet,
+                format!(
+                    "Eingang {} – {} prüfen oder den Eingangspegel erhöhen",
+                    if very_quiet { "sehr leise" } else { "leise" },
+                    microphone
+                ),
+            );
+        }
+
+        if self.clipping_ratio > CLIPPING_RATIO {
+            poor |= self.clipping_ratio > HEAVY_CLIPPING_RATIO;
+            fair = true;
+            self.add(
+                QualityIssue::Clipping,
+                format!(
+                    "Eingang übersteuert – Eingangspegel von {} senken oder mehr Abstand halten",
+                    microphone
+                ),
+            );
+        }
+
+        // Without speech the SNR compares noise with noise and means nothing
+        if has_speech && self.snr_db < NOISY_SNR_DB {
+            poor |= self.snr_db < VERY_NOISY_SNR_DB;
+            fair = true;
+            self.add(
+                QualityIssue::Noisy,
+                "Viel Hintergrundgeräusch – näher ans Mikrofon gehen oder die Rauschunterdrückung aktivieren"
+                    .to_string(),
+            );
+        }
+
+        if self.dc_offset > DC_OFFSET_LIMIT {
+            fair = true;
+            self.add(
+                QualityIssue::DcOffset,
+                format!(
+                    "Gleichspannungsversatz im Signal – {} oder Audio-Interface prüfen, der Hochpassfilter gleicht ihn aus",
+                    microphone
+                ),
+            );
+        }
+
+        if !has_speech {
+            self.add(
+                QualityIssue::NoSpeech,
+                "Keine Sprache erkannt – die Aufnahme wird nicht transkribiert".to_string(),
+            );
+        }
+
+        self.quality = if !has_speech {
+            RecordingQuality::NoSpeech
+        } else if poor {
+            RecordingQuality::Poor
+        } else if fair {
+            RecordingQuality::Fair
+        } else {
+            RecordingQuality::Good
+        };
+    }
+
+    fn add(&mut self, issue: QualityIssue, suggestion: String) {
+        self.issues.push(issue);
+        self.suggestions.push(suggestion);
+    }
+}
+
+fn power_to_dbfs(power: f32) -> f32 {
+    if power > 0.0 {
+        (10.0 * power.log10()).max(METER_FLOOR_DBFS)
+    } else {
+        METER_FLOOR_DBFS
+    }
+}
+
+/// Value at the given fraction of the sorted values (0 if empty)
+fn percentile(values: &[f32], fraction: f32) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let index = ((sorted.len() - 1) as f32 * fraction).round() as usize;
+    sorted[index]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::sine;
+
+    const RATE: u32 = 16000;
+
+    /// One second of quiet noise, one second of tone, one second of quiet noise
+    fn take(tone_amplitude: f32, noise_amplitude: f32) -> Vec<f32> {
+        let mut seed: u32 = 12345;
+        let mut noise = move || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            ((seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0) * noise_amplitude
+        };
+        let second = RATE as usize;
+        let tone = sine(440.0, tone_amplitude, RATE, second * 3);
+        (0..second * 3)
+            .map(|i| {
+                let speaking = (second..second * 2).contains(&i);
+                if speaking {
+                    tone[i] + noise()
+                } else {
+                    noise()
+                }
+            })
+            .collect()
+    }
+
+    fn speech() -> Vec<SpeechSegment> {
+        vec![SpeechSegment {
+            start_ms: 1000,
+            end_ms: 2000,
+        }]
+    }
+
+    fn analyze(samples: &[f32], segments: &[SpeechSegment]) -> QualityReport {
+        let mut analyzer = QualityAnalyzer::new(RATE);
+        // Uneven chunks, like the writer hands them over
+        for chunk in samples.chunks(1234) {
+            analyzer.push(chunk);
+        }
+        analyzer.report(segments, Some("USB Mic"))
+    }
+
+    #[test]
+    fn test_clean_take_is_good() {
+        let report = analyze(&take(0.3, 0.001), &speech());
+        assert_eq!(report.quality, RecordingQuality::Good);
+        assert!(report.issues.is_empty());
+        assert!(report.snr_db > 30.0, "snr {}", report.snr_db);
+        assert!((report.speech_ratio - 1.0 / 3.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_quiet_take_names_the_microphone() {
+        let report = analyze(&take(0.002, 0.0001), &speech());
+        assert_eq!(report.quality, RecordingQuality::Poor);
+        assert_eq!(report.issues, vec![QualityIssue::TooQuiet]);
+        assert!(report.suggestions[0].contains("sehr leise"));
+        assert!(report.suggestions[0].contains("„USB Mic“"));
+    }
+
+    #[test]
+    fn test_clipping_and_dc_offset_are_flagged() {
+        let samples: Vec<f32> = take(1.5, 0.001)
+            .into_iter()
+            .map(|sample| (sample + 0.05).clamp(-1.0, 1.0))
+            .collect();
+        let report = analyze(&samples, &speech());
+        assert_eq!(report.quality, RecordingQuality::Poor);
+        assert!(report.issues.contains(&QualityIssue::Clipping));
+        assert!(report.issues.contains(&QualityIssue::DcOffset));
+        assert!(report.clipping_ratio > HEAVY_CLIPPING_RATIO);
+    }
+
+    #[test]
+    fn test_take_without_speech_can_be_skipped() {
+        let report = analyze(&take(0.0, 0.0), &[]);
+        assert!(report.is_silent());
+        assert_eq!(
+            report.issues,
+            vec![QualityIssue::NoSignal, QualityIssue::NoSpeech]
+        );
+    }
+}
@@ -0,0 +1,745 @@
+//! Audio sources for the recorder
+//!
+//! `AudioRecorder` captures through an `AudioSource`: `CpalSource` opens a real
+//! input device, `FileSource` replays a WAV file and `SyntheticSource`
+//! generates test signals. Every source feeds the same capture ring, so
+//! recording, metering, voice activity detection and the auto-stop logic run
+//! unchanged without audio hardware, e.g. on CI or to replay the recording
+//! from a bug report.
+//!
+//! Sources are started and stopped on a single audio thread: cpal streams are
+//! not `Send` (CoreAudio and WASAPI expect them to be dropped on the thread
+//! that built them), so the recorder only ever holds a `StreamHandle`.
+
+use crate::audio::{
+    max_input_channels, negotiate_input_configs, push_interleaved, AudioError, CaptureConfig,
+    CAPTURE_BUFFER_MS,
+};
+use cpal::traits::{DeviceTrait, StreamTrait};
+use cpal::{Device, FromSample, SampleFormat, Stream, StreamConfig, SupportedStreamConfig};
+use rtrb::{Consumer, Producer, RingBuffer};
+use std::any::Any;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Environment variable naming a WAV file to record from instead of the microphone
+pub const REPLAY_ENV_VAR: &str = "EVERVOICE_REPLAY_WAV";
+
+/// Frames a replaying source hands over at once (10 ms at 48 kHz)
+const FEED_CHUNK_MS: u64 = 10;
+
+/// How long a fast replay waits for the capture worker to make room
+const FEED_WAIT: Duration = Duration::from_millis(1);
+
+/// Receives errors a running source reports (e.g. the device was unplugged)
+pub type SourceErrorHandler = Arc<dyn Fn(String) + Send + Sync>;
+
+/// Something the recorder can capture interleaved frames from
+pub trait AudioSource: Send {
+    /// Name recorded as the device of a take
+    fn name(&self) -> String;
+
+    /// Most channels the source can deliver (0 if unknown)
+    fn max_channels(&self) -> u16;
+
+    /// Start delivering frames, with at least `min_channels` channels if the
+    /// source offers them
+    ///
+    /// Frames the capture worker cannot take in time are counted in
+    /// `overruns`. The source keeps running until the returned stream is dropped.
+    fn start(
+        &mut self,
+        min_channels: u16,
+        overruns: Arc<AtomicU64>,
+        on_error: SourceErrorHandler,
+    ) -> Result<SourceStream, AudioError>;
+}
+
+/// A started source
+pub struct SourceStream {
+    /// Format of the delivered frames
+    pub config: CaptureConfig,
+    /// Capture ring the frames arrive in
+    pub consumer: Consumer<f32>,
+    /// Keeps the source running until dropped
+    pub guard: Box<dyn Any>,
+}
+
+/// Real-time side of the capture ring
+pub struct SourceSink {
+    producer: Producer<f32>,
+    channels: usize,
+    overruns: Arc<AtomicU64>,
+}
+
+impl SourceSink {
+    /// Ring sized for `CAPTURE_BUFFER_MS` of audio in `config`
+    pub fn ring(config: &CaptureConfig, overruns: Arc<AtomicU64>) -> (Self, Consumer<f32>) {
+        let channels = config.channels.max(1) as usize;
+        let capacity = (config.sample_rate as u64 * CAPTURE_BUFFER_MS / 1000) as usize * channels;
+        let (producer, consumer) = RingBuffer::new(capacity);
+        let sink = Self {
+            producer,
+            channels,
+            overruns,
+        };
+        (sink, consumer)
+    }
+
+    /// Copy whole frames in without blocking; what does not fit is an overrun
+    pub fn push<T>(&mut self, data: &[T])
+    where
+        T: cpal::Sample,
+        f32: FromSample<T>,
+    {
+        push_interleaved(&mut self.producer, data, self.channels, &self.overruns);
+    }
+
+    /// Room left in the ring, in frames
+    fn free_frames(&self) -> usize {
+        self.producer.slots() / self.channels
+    }
+}
+
+// ============================================================================
+// Audio thread
+// ============================================================================
+
+/// A source started on the audio thread
+pub struct RunningSource {
+    /// Format of the delivered frames
+    pub config: CaptureConfig,
+    /// Capture ring the frames arrive in
+    pub consumer: Consumer<f32>,
+    /// Keeps the source running until dropped
+    pub handle: StreamHandle,
+}
+
+/// Stops its source on the audio thread when dropped
+///
+/// The drop waits until the source is stopped, so the device is free again
+/// when it returns.
+pub struct StreamHandle(u64);
+
+impl Drop for StreamHandle {
+    fn drop(&mut self) {
+        let (done, stopped) = mpsc::channel();
+        audio_thread()
+            .send(AudioCommand::Stop { id: self.0, done })
+            .expect("audio thread stopped");
+        let _ = stopped.recv();
+    }
+}
+
+type StartReply = (
+    Box<dyn AudioSource>,
+    Result<(u64, CaptureConfig, Consumer<f32>), AudioError>,
+);
+
+enum AudioCommand {
+    Start {
+        source: Box<dyn AudioSource>,
+        min_channels: u16,
+        overruns: Arc<AtomicU64>,
+        on_error: SourceErrorHandler,
+        reply: Sender<StartReply>,
+    },
+    Stop {
+        id: u64,
+        done: Sender<()>,
+    },
+}
+
+/// Start `source` on the audio thread
+///
+/// The source is handed back whether it started or not, so it can be
+/// started again later.
+pub fn start_on_audio_thread(
+    source: Box<dyn AudioSource>,
+    min_channels: u16,
+    overruns: Arc<AtomicU64>,
+    on_error: SourceErrorHandler,
+) -> (Box<dyn AudioSource>, Result<RunningSource, AudioError>) {
+    let (reply, started) = mpsc::channel();
+    audio_thread()
+        .send(AudioCommand::Start {
+            source,
+            min_channels,
+            overruns,
+            on_error,
+            reply,
+        })
+        .expect("audio thread stopped");
+    let (source, started) = started.recv().expect("audio thread stopped");
+    let running = started.map(|(id, config, consumer)| RunningSource {
+        config,
+        consumer,
+        handle: StreamHandle(id),
+    });
+    (source, running)
+}
+
+/// Commands for the audio thread, which is spawned on first use
+fn audio_thread() -> &'static Sender<AudioCommand> {
+    static AUDIO_THREAD: OnceLock<Sender<AudioCommand>> = OnceLock::new();
+    AUDIO_THREAD.get_or_init(|| {
+        let (commands, received) = mpsc::channel();
+        thread::spawn(move || run_audio_thread(received));
+        commands
+    })
+}
+
+/// Start and stop sources, keeping the guards of the running ones
+///
+/// A panicking source fails its own start or stop, not the thread every
+/// other source depends on.
+fn run_audio_thread(commands: Receiver<AudioCommand>) {
+    let mut running: HashMap<u64, Box<dyn Any>> = HashMap::new();
+    let mut next_id: u64 = 0;
+    for command in commands {
+        match command {
+            AudioCommand::Start {
+                mut source,
+                min_channels,
+                overruns,
+                on_error,
+                reply,
+            } => {
+                let started = panic::catch_unwind(AssertUnwindSafe(|| {
+                    source.start(min_channels, overruns, on_error)
+                }))
+                .unwrap_or_else(|_| Err(AudioError::StreamError("Audio source panicked".into())))
+                .map(|stream| {
+                    next_id += 1;
+                    running.insert(next_id, stream.guard);
+                    (next_id, stream.config, stream.consumer)
+                });
+                // Nobody would ever stop a source the caller did not receive
+                if let Err(mpsc::SendError((_, Ok((id, _, _))))) = reply.send((source, started)) {
+                    running.remove(&id);
+                }
+            }
+            AudioCommand::Stop { id, done } => {
+                if let Some(guard) = running.remove(&id) {
+                    if panic::catch_unwind(AssertUnwindSafe(|| drop(guard))).is_err() {
+                        log::error!("Audio source panicked while stopping");
+                    }
+                }
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+// ============================================================================
+// cpal devices
+// ============================================================================
+
+/// A cpal input device, or an output device recorded in loopback mode (WASAPI)
+pub struct CpalSource {
+    device: Device,
+    loopback: bool,
+}
+
+impl CpalSource {
+    /// Record from an input device
+    pub fn new(device: Device) -> Self {
+        Self {
+            device,
+            loopback: false,
+        }
+    }
+
+    /// Record what an output device plays
+    pub fn loopback(device: Device) -> Self {
+        Self {
+            device,
+            loopback: true,
+        }
+    }
+
+    /// Configs to try, best first
+    fn configs(&self, min_channels: u16) -> Vec<SupportedStreamConfig> {
+        if self.loopback {
+            return self.device.default_output_config().into_iter().collect();
+        }
+        let ranges: Vec<_> = self
+            .device
+            .supported_input_configs()
+            .map(|configs| configs.collect())
+            .unwrap_or_default();
+        let default_config = self.device.default_input_config().ok();
+        negotiate_input_configs(&ranges, default_config.as_ref(), min_channels)
+    }
+}
+
+impl AudioSource for CpalSource {
+    fn name(&self) -> String {
+        self.device.name().unwrap_or_default()
+    }
+
+    fn max_channels(&self) -> u16 {
+        if self.loopback {
+            return self
+                .device
+                .default_output_config()
+                .map_or(0, |config| config.channels());
+        }
+        max_input_channels(&self.device)
+    }
+
+    /// Tries the negotiated configs in order until one of them runs
+    fn start(
+        &mut self,
+        min_channels: u16,
+        overruns: Arc<AtomicU64>,
+        on_error: SourceErrorHandler,
+    ) -> Result<SourceStream, AudioError> {
+        let mut last_error =
+            AudioError::ConfigError("Device reports no input configuration".into());
+        for config in self.configs(min_channels) {
+            let capture_config = CaptureConfig::from(&config);
+            let (sink, consumer) = SourceSink::ring(&capture_config, Arc::clone(&overruns));
+            let on_error = Arc::clone(&on_error);
+            let started = build_input_stream(&self.device, &config, sink, move |err| {
+                on_error(err.to_string())
+            })
+            .and_then(|stream| {
+                stream
+                    .play()
+                    .map_err(|e| AudioError::StreamError(e.to_string()))?;
+                Ok(stream)
+            });
+            match started {
+                Ok(stream) => {
+                    return Ok(SourceStream {
+                        config: capture_config,
+                        consumer,
+                        guard: Box::new(stream),
+                    })
+                }
+                Err(e) => {
+                    log::warn!("Input config {:?} failed: {}", config, e);
+                    last_error = e;
+                }
+            }
+        }
+        Err(last_error)
+    }
+}
+
+/// Build an input stream in the device's sample format
+fn build_input_stream<E>(
+    device: &Device,
+    config: &SupportedStreamConfig,
+    sink: SourceSink,
+    err_fn: E,
+) -> Result<Stream, AudioError>
+where
+    E: FnMut(cpal::StreamError) + Send + 'static,
+{
+    let stream_config: StreamConfig = config.config();
+    match config.sample_format() {
+        SampleFormat::I8 => build_stream::<i8, _>(device, &stream_config, sink, err_fn),
+        SampleFormat::I16 => build_stream::<i16, _>(device, &stream_config, sink, err_fn),
+        SampleFormat::I32 => build_stream::<i32, _>(device, &stream_config, sink, err_fn),
+        SampleFormat::I64 => build_stream::<i64, _>(device, &stream_config, sink, err_fn),
+        SampleFormat::U8 => build_stream::<u8, _>(device, &stream_config, sink, err_fn),
+        SampleFormat::U16 => build_stream::<u16, _>(device, &stream_config, sink, err_fn),
+        SampleFormat::U32 => build_stream::<u32, _>(device, &stream_config, sink, err_fn),
+        SampleFormat::U64 => build_stream::<u64, _>(device, &stream_config, sink, err_fn),
+        SampleFormat::F32 => build_stream::<f32, _>(device, &stream_config, sink, err_fn),
+        SampleFormat::F64 => build_stream::<f64, _>(device, &stream_config, sink, err_fn),
+        format => Err(AudioError::ConfigError(format!(
+            "Unsupported sample format: {}",
+            format
+        ))),
+    }
+}
+
+/// Build the audio input stream
+///
+/// The data callback runs on the real-time audio thread: it never locks or
+/// allocates, it only copies into the ring and counts overruns.
+fn build_stream<T, E>(
+    device: &Device,
+    config: &StreamConfig,
+    mut sink: SourceSink,
+    err_fn: E,
+) -> Result<Stream, AudioError>
+where
+    T: cpal::Sample + cpal::SizedSample + Send + 'static,
+    f32: FromSample<T>,
+    E: FnMut(cpal::StreamError) + Send + 'static,
+{
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| sink.push(data),
+            err_fn,
+            None, // No timeout
+        )
+        .map_err(|e| AudioError::StreamError(e.to_string()))
+}
+
+// ============================================================================
+// Replayed and generated audio
+// ============================================================================
+
+/// How fast a file or synthetic source delivers its frames
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pace {
+    /// Like a microphone: one second of audio per second
+    RealTime,
+    /// As fast as the capture worker takes them, without dropping any
+    Fast,
+}
+
+/// Stops a feeder thread when dropped
+struct FeederGuard {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for FeederGuard {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Hand interleaved `samples` to a new capture ring from a thread
+///
+/// The ring is abandoned once everything was delivered, which ends the
+/// capture worker like an unplugged device would, minus the error.
+fn start_feeder(
+    samples: Vec<f32>,
+    config: CaptureConfig,
+    pace: Pace,
+    overruns: Arc<AtomicU64>,
+) -> SourceStream {
+    let (mut sink, consumer) = SourceSink::ring(&config, overruns);
+    let stop = Arc::new(AtomicBool::new(false));
+    let feeder_stop = Arc::clone(&stop);
+
+    let channels = config.channels.max(1) as usize;
+    let rate = config.sample_rate.max(1) as u64;
+    let chunk_frames = (rate * FEED_CHUNK_MS / 1000).max(1) as usize;
+    let handle = thread::spawn(move || {
+        let started = Instant::now();
+        let mut fed_frames: u64 = 0;
+        for chunk in samples.chunks(chunk_frames * channels) {
+            let frames = chunk.len() / channels;
+            if pace == Pace::Fast {
+                while sink.free_frames() < frames {
+                    if feeder_stop.load(Ordering::Acquire) {
+                        return;
+                    }
+                    thread::sleep(FEED_WAIT);
+                }
+            }
+            if feeder_stop.load(Ordering::Acquire) {
+                return;
+            }
+            sink.push(chunk);
+            fed_frames += frames as u64;
+
+            if pace == Pace::RealTime {
+                let due = Duration::from_micros(fed_frames * 1_000_000 / rate);
+                if let Some(wait) = due.checked_sub(started.elapsed()) {
+                    thread::sleep(wait);
+                }
+            }
+        }
+    });
+
+    SourceStream {
+        config,
+        consumer,
+        guard: Box::new(FeederGuard {
+            stop,
+            handle: Some(handle),
+        }),
+    }
+}
+
+/// Replays a WAV file as if it were captured live
+pub struct FileSource {
+    path: PathBuf,
+    pace: Pace,
+}
+
+impl FileSource {
+    pub fn new(path: impl Into<PathBuf>, pace: Pace) -> Self {
+        Self {
+            path: path.into(),
+            pace,
+        }
+    }
+}
+
+/// Read a whole WAV file as interleaved f32 samples
+fn read_wav(path: &Path) -> Result<(Vec<f32>, hound::WavSpec), AudioError> {
+    let reader =
+        hound::WavReader::open(path).map_err(|e| AudioError::ConfigError(e.to_string()))?;
+    let spec = reader.spec();
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Int => {
+            let max_val = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .into_samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / max_val))
+                .collect::<Result<_, _>>()
+        }
+        hound::SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<_, _>>(),
+    }
+    .map_err(|e| AudioError::ConfigError(e.to_string()))?;
+    Ok((samples, spec))
+}
+
+impl AudioSource for FileSource {
+    fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.to_string_lossy().to_string())
+    }
+
+    fn max_channels(&self) -> u16 {
+        hound::WavReader::open(&self.path).map_or(0, |reader| reader.spec().channels)
+    }
+
+    /// The file is replayed with the channels it has, whatever `min_channels` asks for
+    fn start(
+        &mut self,
+        _min_channels: u16,
+        overruns: Arc<AtomicU64>,
+        _on_error: SourceErrorHandler,
+    ) -> Result<SourceStream, AudioError> {
+        let (samples, spec) = read_wav(&self.path)?;
+        let config = CaptureConfig {
+            sample_rate: spec.sample_rate,
+            channels: spec.channels,
+            sample_format: match spec.sample_format {
+                hound::SampleFormat::Int => format!("i{}", spec.bits_per_sample),
+                hound::SampleFormat::Float => "f32".to_string(),
+            },
+        };
+        log::info!(
+            "Replaying {} ({} Hz, {} channel(s))",
+            self.path.display(),
+            spec.sample_rate,
+            spec.channels
+        );
+        Ok(start_feeder(samples, config, self.pace, overruns))
+    }
+}
+
+/// One part of a `SyntheticSource` script
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Signal {
+    Silence,
+    Sine {
+        frequency: f32,
+        amplitude: f32,
+    },
+    /// Uniform white noise (deterministic)
+    Noise {
+        amplitude: f32,
+    },
+}
+
+/// Generates a scripted test signal, the same on every channel
+pub struct SyntheticSource {
+    sample_rate: u32,
+    channels: u16,
+    script: Vec<(Signal, Duration)>,
+    pace: Pace,
+}
+
+impl SyntheticSource {
+    /// Empty script, add signals with `then`
+    pub fn new(sample_rate: u32, channels: u16, pace: Pace) -> Self {
+        Self {
+            sample_rate,
+            channels: channels.max(1),
+            script: Vec::new(),
+            pace,
+        }
+    }
+
+    /// Append `signal` for `duration`
+    pub fn then(mut self, signal: Signal, duration: Duration) -> Self {
+        self.script.push((signal, duration));
+        self
+    }
+
+    /// The whole script as mono samples
+    fn render(&self) -> Vec<f32> {
+        let rate = self.sample_rate as f32;
+        // xorshift32, so the noise is the same on every run
+        let mut state: u32 = 0x9e37_79b9;
+        let mut samples = Vec::new();
+        for (signal, duration) in &self.script {
+            let frames = (duration.as_micros() * self.sample_rate as u128 / 1_000_000) as usize;
+            samples.extend((0..frames).map(|i| match *signal {
+                Signal::Silence => 0.0,
+                Signal::Sine {
+                    frequency,
+                    amplitude,
+                } => amplitude * (2.0 * PI * frequency * i as f32 / rate).sin(),
+                Signal::Noise { amplitude } => {
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    amplitude * (state as f32 / u32::MAX as f32 * 2.0 - 1.0)
+                }
+            }));
+        }
+        samples
+    }
+}
+
+impl AudioSource for SyntheticSource {
+    fn name(&self) -> String {
+        "Synthetic signal".to_string()
+    }
+
+    fn max_channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn start(
+        &mut self,
+        _min_channels: u16,
+        overruns: Arc<AtomicU64>,
+        _on_error: SourceErrorHandler,
+    ) -> Result<SourceStream, AudioError> {
+        let channels = self.channels as usize;
+        let samples: Vec<f32> = self
+            .render()
+            .into_iter()
+            .flat_map(|sample| std::iter::repeat(sample).take(channels))
+            .collect();
+        let config = CaptureConfig {
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            sample_format: "f32".to_string(),
+        };
+        Ok(start_feeder(samples, config, self.pace, overruns))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{write_wav, TempPath};
+
+    /// Drain a stream until its source has delivered everything
+    fn collect(mut stream: SourceStream) -> Vec<f32> {
+        let mut samples = Vec::new();
+        loop {
+            let finished = stream.consumer.is_abandoned();
+            let available = stream.consumer.slots();
+            if let Ok(chunk) = stream.consumer.read_chunk(available) {
+                samples.extend(chunk);
+            }
+            if finished && stream.consumer.is_empty() {
+                return samples;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    fn no_errors() -> SourceErrorHandler {
+        Arc::new(|error| panic!("unexpected source error: {}", error))
+    }
+
+    #[test]
+    fn test_file_source_replays_every_frame() {
+        let path = TempPath::file("replay.wav");
+        // Longer than the capture ring, so a fast replay has to wait for room
+        let ramp: Vec<f32> = (0..20_000)
+            .map(|i| (i % 1000 * 16) as f32 / i16::MAX as f32)
+            .collect();
+        write_wav(&path, 8000, 2, &ramp);
+
+        let mut source = FileSource::new(path.to_path_buf(), Pace::Fast);
+        assert_eq!(source.max_channels(), 2);
+        let overruns = Arc::new(AtomicU64::new(0));
+        let stream = source.start(1, Arc::clone(&overruns), no_errors()).unwrap();
+        assert_eq!(stream.config.sample_rate, 8000);
+        assert_eq!(stream.config.sample_format, "i16");
+
+        let samples = collect(stream);
+        assert_eq!(samples.len(), 20_000);
+        assert!((samples[999] - 999.0 * 16.0 / 32768.0).abs() < 1e-6);
+        assert_eq!(overruns.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_synthetic_source_follows_its_script() {
+        let mut source = SyntheticSource::new(16000, 2, Pace::Fast)
+            .then(Signal::Silence, Duration::from_millis(100))
+            .then(
+                Signal::Sine {
+                    frequency: 440.0,
+                    amplitude: 0.5,
+                },
+                Duration::from_millis(200),
+            )
+            .then(Signal::Noise { amplitude: 0.1 }, Duration::from_millis(50));
+        let stream = source
+            .start(1, Arc::new(AtomicU64::new(0)), no_errors())
+            .unwrap();
+        let samples = collect(stream);
+
+        // 350 ms of stereo frames, identical on both channels
+        assert_eq!(samples.len(), 350 * 16 * 2);
+        assert!(samples.chunks(2).all(|frame| frame[0] == frame[1]));
+        assert!(samples[..1600 * 2].iter().all(|s| *s == 0.0));
+        let peak = samples[1600 * 2..4800 * 2]
+            .iter()
+            .fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((peak - 0.5).abs() < 0.01);
+        assert!(samples[4800 * 2..].iter().all(|s| s.abs() <= 0.1));
+    }
+
+    #[test]
+    fn test_audio_thread_starts_and_stops_sources() {
+        let source = SyntheticSource::new(16000, 1, Pace::RealTime)
+            .then(Signal::Silence, Duration::from_secs(10));
+        let (source, started) = start_on_audio_thread(
+            Box::new(source),
+            1,
+            Arc::new(AtomicU64::new(0)),
+            no_errors(),
+        );
+        assert_eq!(source.name(), "Synthetic signal");
+        let running = started.unwrap();
+        assert_eq!(running.config.sample_rate, 16000);
+        assert!(!running.consumer.is_abandoned());
+
+        // Dropping the handle stops the feeder before it returns
+        drop(running.handle);
+        assert!(running.consumer.is_abandoned());
+
+        let missing = FileSource::new("missing.wav", Pace::Fast);
+        let (_, started) = start_on_audio_thread(
+            Box::new(missing),
+            1,
+            Arc::new(AtomicU64::new(0)),
+            no_errors(),
+        );
+        assert!(started.is_err());
+    }
+}
@@ -4,6 +4,7 @@
 //! text processing. This module provides the foundation for features like
 //! email-optimized or chat-optimized text formatting.
 
+use crate::encryption;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -943,7 +944,7 @@ pub fn get_config_path() -> PathBuf {
 pub fn load_config() -> ContextConfig {
     let config_path = get_config_path();
     if config_path.exists() {
-        if let Ok(content) = fs::read_to_string(&config_path) {
+        if let Ok(content) = encryption::read_to_string(&config_path) {
             if let Ok(mut config) = serde_json::from_str::<ContextConfig>(&content) {
                 // Merge with built-in mappings (user mappings override built-ins)
                 let builtin_mappings = get_builtin_mappings();
@@ -971,7 +972,7 @@ pub fn save_config(config: &ContextConfig) -> Result<(), String> {
         user_mappings: config.user_mappings.clone(),
     };
     let json = serde_json::to_string_pretty(&save_config).map_err(|e| e.to_string())?;
-    fs::write(&config_path, json).map_err(|e| e.to_string())
+    encryption::write(&config_path, json).map_err(|e| e.to_string())
 }
 
 // ============================================================================
@@ -0,0 +1,516 @@
+//! Audio preprocessing for Whisper
+//!
+//! A small chain of streaming stages that cleans up the 16kHz signal before
+//! it is written to disk:
+//! - High-pass filter: removes DC offset, rumble and most mains hum
+//! - Noise suppression: spectral gating against a tracked noise floor
+//! - Loudness normalization: slow AGC towards a target level with a peak guard
+//!
+//! Every stage keeps the sample count intact, so timestamps from the VAD and
+//! the duration limit stay valid after processing.
+
+use realfft::num_complex::Complex;
+use realfft::{ComplexToReal, RealFftPlanner, RealToComplex};
+use std::sync::Arc;
+
+/// High-pass corner frequency in Hz
+const HIGH_PASS_CUTOFF_HZ: f32 = 80.0;
+
+/// Noise suppression FFT size (32 ms at 16kHz)
+const NS_FRAME_LEN: usize = 512;
+
+/// Noise suppression hop size (50% overlap)
+const NS_HOP_LEN: usize = NS_FRAME_LEN / 2;
+
+/// Lowest gain applied to a noise-only bin (-20 dB)
+const NS_GAIN_FLOOR: f32 = 0.1;
+
+/// Over-subtraction factor for the noise estimate
+const NS_OVER_SUBTRACTION: f32 = 2.0;
+
+/// Bins below this multiple of the noise estimate count as noise and update it
+const NS_NOISE_UPDATE_RATIO: f32 = 3.0;
+
+/// How fast the noise estimate follows noise-only bins
+const NS_NOISE_ADAPT: f32 = 0.1;
+
+/// Frames averaged into the initial noise estimate (~250 ms)
+const NS_LEARN_FRAMES: u32 = 16;
+
+/// Weight of the previous frame in the per-bin power average
+const NS_POWER_SMOOTHING: f32 = 0.7;
+
+/// Per-frame growth of the noise estimate while the signal is louder (~15%/s)
+const NS_NOISE_RISE: f32 = 1.002;
+
+/// Target RMS level for loudness normalization (-20 dBFS)
+const AGC_TARGET_RMS: f32 = 0.1;
+
+/// Maximum AGC boost (+20 dB)
+const AGC_MAX_GAIN: f32 = 10.0;
+
+/// Minimum AGC gain (-12 dB)
+const AGC_MIN_GAIN: f32 = 0.25;
+
+/// Below this level the AGC holds its gain instead of amplifying noise (-50 dBFS)
+const AGC_GATE_RMS: f32 = 0.003;
+
+/// Output peaks are kept under this level
+const AGC_PEAK_LIMIT: f32 = 0.95;
+
+/// A streaming processing stage
+///
+/// `process` may hold samples back (e.g. for an FFT frame); `finish` returns
+/// whatever is still buffered so the total output length equals the input.
+pub trait DspStage: Send {
+    fn process(&mut self, input: &[f32]) -> Vec<f32>;
+
+    fn finish(&mut self) -> Vec<f32> {
+        Vec::new()
+    }
+}
+
+/// Ordered set of stages applied to every chunk
+#[derive(Default)]
+pub struct DspChain {
+    stages: Vec<Box<dyn DspStage>>,
+}
+
+impl DspChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a stage to the end of the chain
+    pub fn with_stage(mut self, stage: impl DspStage + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        let mut buffer = input.to_vec();
+        for stage in &mut self.stages {
+            buffer = stage.process(&buffer);
+        }
+        buffer
+    }
+
+    /// Drain every stage in order, pushing each tail through the stages after it
+    pub fn finish(&mut self) -> Vec<f32> {
+        let mut tail = Vec::new();
+        for stage in &mut self.stages {
+            let mut output = stage.process(&tail);
+            output.extend(stage.finish());
+            tail = output;
+        }
+        tail
+    }
+}
+
+// ============================================================================
+// High-pass filter
+// ============================================================================
+
+/// Second-order Butterworth high-pass (RBJ biquad)
+pub struct HighPassFilter {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl HighPassFilter {
+    pub fn new(sample_rate: u32) -> Self {
+        Self::with_cutoff(sample_rate, HIGH_PASS_CUTOFF_HZ)
+    }
+
+    pub fn with_cutoff(sample_rate: u32, cutoff_hz: f32) -> Self {
+        let omega = std::f32::consts::TAU * cutoff_hz / sample_rate as f32;
+        let alpha = omega.sin() / std::f32::consts::SQRT_2;
+        let cos = omega.cos();
+        let a0 = 1.0 + alpha;
+
+        Self {
+            b0: (1.0 + cos) / 2.0 / a0,
+            b1: -(1.0 + cos) / a0,
+            b2: (1.0 + cos) / 2.0 / a0,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+            x1: 0.0,
+            x2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        }
+    }
+}
+
+impl DspStage for HighPassFilter {
+    fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        input
+            .iter()
+            .map(|&x| {
+                let y = self.b0 * x + self.b1 * self.x1 + self.b2 * self.x2
+                    - self.a1 * self.y1
+                    - self.a2 * self.y2;
+                self.x2 = self.x1;
+                self.x1 = x;
+                self.y2 = self.y1;
+                self.y1 = y;
+                y
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// Noise suppression
+// ============================================================================
+
+/// Spectral noise suppressor
+///
+/// Short-time FFT with a sqrt-Hann window at 50% overlap. The noise spectrum
+/// is averaged over bins that look like noise and creeps up slowly otherwise,
+/// so it recovers when the background gets louder. Bins are
+/// attenuated by a smoothed spectral subtraction gain. Adds `NS_HOP_LEN`
+/// samples of internal latency, which is compensated in the output.
+pub struct NoiseSuppressor {
+    forward: Arc<dyn RealToComplex<f32>>,
+    inverse: Arc<dyn ComplexToReal<f32>>,
+    window: Vec<f32>,
+    input: Vec<f32>,
+    overlap: Vec<f32>,
+    spectrum: Vec<Complex<f32>>,
+    power: Vec<f32>,
+    noise: Vec<f32>,
+    gains: Vec<f32>,
+    /// Frames analysed so far
+    frames: u32,
+    /// Output samples still to drop to undo the frame latency
+    delay_remaining: usize,
+    samples_in: u64,
+    samples_out: u64,
+}
+
+impl NoiseSuppressor {
+    pub fn new() -> Self {
+        let mut planner = RealFftPlanner::<f32>::new();
+        let forward = planner.plan_fft_forward(NS_FRAME_LEN);
+        let inverse = planner.plan_fft_inverse(NS_FRAME_LEN);
+        let spectrum = forward.make_output_vec();
+        let bins = spectrum.len();
+
+        // Periodic sqrt-Hann: analysis * synthesis sums to 1 at 50% overlap
+        let window = (0..NS_FRAME_LEN)
+            .map(|i| {
+                let phase = std::f32::consts::TAU * i as f32 / NS_FRAME_LEN as f32;
+                (0.5 - 0.5 * phase.cos()).sqrt()
+            })
+            .collect();
+
+        Self {
+            forward,
+            inverse,
+            window,
+            // Pre-roll so the first real sample lands in two overlapping frames
+            input: vec![0.0; NS_FRAME_LEN - NS_HOP_LEN],
+            overlap: vec![0.0; NS_FRAME_LEN],
+            spectrum,
+            power: vec![0.0; bins],
+            noise: vec![0.0; bins],
+            gains: vec![1.0; bins],
+            frames: 0,
+            delay_remaining: NS_FRAME_LEN - NS_HOP_LEN,
+            samples_in: 0,
+            samples_out: 0,
+        }
+    }
+
+    fn process_frame(&mut self, output: &mut Vec<f32>) {
+        let mut frame: Vec<f32> = self.input[..NS_FRAME_LEN]
+            .iter()
+            .zip(&self.window)
+            .map(|(s, w)| s * w)
+            .collect();
+        self.input.drain(..NS_HOP_LEN);
+
+        if self
+            .forward
+            .process(&mut frame, &mut self.spectrum)
+            .is_err()
+        {
+            return;
+        }
+
+        for (k, bin) in self.spectrum.iter_mut().enumerate() {
+            // Smoothed power: the raw periodogram of noise fluctuates too much
+            // for a minimum to say anything about its mean
+            let power = &mut self.power[k];
+            *power = if self.frames > 0 {
+                NS_POWER_SMOOTHING * *power + (1.0 - NS_POWER_SMOOTHING) * bin.norm_sqr()
+            } else {
+                bin.norm_sqr()
+            };
+            let power = *power;
+
+            let noise = &mut self.noise[k];
+            if self.frames < NS_LEARN_FRAMES {
+                // Learning phase: plain average, the take usually starts before the speech
+                *noise += (power - *noise) / (self.frames + 1) as f32;
+            } else if power < NS_NOISE_UPDATE_RATIO * *noise {
+                *noise = (*noise + (power - *noise) * NS_NOISE_ADAPT).max(f32::MIN_POSITIVE);
+            } else {
+                *noise *= NS_NOISE_RISE;
+            }
+
+            let target = if power > 0.0 {
+                (1.0 - NS_OVER_SUBTRACTION * *noise / power).max(NS_GAIN_FLOOR)
+            } else {
+                NS_GAIN_FLOOR
+            };
+            // Open fast, close slowly: avoids "musical noise" on decaying speech
+            let gain = &mut self.gains[k];
+            *gain = if target > *gain {
+                target
+            } else {
+                0.7 * *gain + 0.3 * target
+            };
+            *bin *= *gain;
+        }
+        self.frames = self.frames.saturating_add(1);
+
+        let mut time = vec![0.0f32; NS_FRAME_LEN];
+        if self.inverse.process(&mut self.spectrum, &mut time).is_err() {
+            return;
+        }
+
+        let scale = 1.0 / NS_FRAME_LEN as f32;
+        for (i, sample) in time.iter().enumerate() {
+            self.overlap[i] += sample * scale * self.window[i];
+        }
+
+        let ready: Vec<f32> = self.overlap.drain(..NS_HOP_LEN).collect();
+        self.overlap.resize(NS_FRAME_LEN, 0.0);
+
+        let skip = self.delay_remaining.min(ready.len());
+        self.delay_remaining -= skip;
+        self.samples_out += (ready.len() - skip) as u64;
+        output.extend_from_slice(&ready[skip..]);
+    }
+}
+
+impl Default for NoiseSuppressor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DspStage for NoiseSuppressor {
+    fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        self.samples_in += input.len() as u64;
+        self.input.extend_from_slice(input);
+
+        let mut output = Vec::with_capacity(input.len() + NS_HOP_LEN);
+        while self.input.len() >= NS_FRAME_LEN {
+            self.process_frame(&mut output);
+        }
+        output
+    }
+
+    fn finish(&mut self) -> Vec<f32> {
+        let mut output = Vec::new();
+        while self.samples_out < self.samples_in {
+            self.input.resize(NS_FRAME_LEN, 0.0);
+            self.process_frame(&mut output);
+        }
+
+        // Drop what the zero padding produced past the real end
+        let excess = (self.samples_out - self.samples_in) as usize;
+        output.truncate(output.len().saturating_sub(excess));
+        self.samples_out = self.samples_in;
+        output
+    }
+}
+
+// ============================================================================
+// Loudness normalization
+// ============================================================================
+
+/// Automatic gain control towards `AGC_TARGET_RMS`
+///
+/// Tracks the signal level with a ~300 ms envelope, raises the gain slowly
+/// (~1 s) and lowers it quickly (~50 ms). Quiet passages below the gate keep
+/// the current gain so background noise isn't pulled up between sentences.
+pub struct LoudnessNormalizer {
+    envelope: f32,
+    gain: f32,
+    envelope_coef: f32,
+    attack_coef: f32,
+    release_coef: f32,
+}
+
+impl LoudnessNormalizer {
+    pub fn new(sample_rate: u32) -> Self {
+        let coef = |seconds: f32| 1.0 - (-1.0 / (seconds * sample_rate as f32)).exp();
+        Self {
+            envelope: 0.0,
+            gain: 1.0,
+            envelope_coef: coef(0.3),
+            attack_coef: coef(0.05),
+            release_coef: coef(1.0),
+        }
+    }
+}
+
+impl DspStage for LoudnessNormalizer {
+    fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        input
+            .iter()
+            .map(|&x| {
+                self.envelope += (x * x - self.envelope) * self.envelope_coef;
+                let level = self.envelope.sqrt();
+
+                if level > AGC_GATE_RMS {
+                    let target = (AGC_TARGET_RMS / level).clamp(AGC_MIN_GAIN, AGC_MAX_GAIN);
+                    let coef = if target < self.gain {
+                        self.attack_coef
+                    } else {
+                        self.release_coef
+                    };
+                    self.gain += (target - self.gain) * coef;
+                }
+
+                // Peak guard: pull the gain down at once instead of clipping
+                if (x * self.gain).abs() > AGC_PEAK_LIMIT {
+                    self.gain = AGC_PEAK_LIMIT / x.abs();
+                }
+                x * self.gain
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{read_wav, sine, write_wav, TempPath};
+    use std::path::Path;
+
+    const RATE: u32 = 16_000;
+
+    fn tone(freq: f32, amplitude: f32, seconds: f32) -> Vec<f32> {
+        sine(freq, amplitude, RATE, (seconds * RATE as f32) as usize)
+    }
+
+    /// Deterministic white noise in [-amplitude, amplitude]
+    fn noise(amplitude: f32, seconds: f32) -> Vec<f32> {
+        let mut state: u32 = 0x1234_5678;
+        (0..(seconds * RATE as f32) as usize)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state as f32 / u32::MAX as f32 * 2.0 - 1.0) * amplitude
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    /// Run a chain over a mono 16-bit WAV fixture, like the recorder does
+    fn process_fixture(input: &Path, output: &Path, chain: &mut DspChain) {
+        let samples = read_wav(input);
+        let mut processed = chain.process(&samples);
+        processed.extend(chain.finish());
+        write_wav(output, RATE, 1, &processed);
+    }
+
+    #[test]
+    fn test_high_pass_removes_dc_and_rumble() {
+        let input: Vec<f32> = tone(20.0, 0.3, 1.0).iter().map(|s| s + 0.2).collect();
+        let output = HighPassFilter::new(RATE).process(&input);
+
+        let settled = &output[RATE as usize / 2..];
+        let mean = settled.iter().sum::<f32>() / settled.len() as f32;
+        assert!(mean.abs() < 0.001);
+        assert!(rms(settled) < rms(&tone(20.0, 0.3, 0.5)) * 0.1);
+
+        let speech = tone(440.0, 0.3, 1.0);
+        let passed = HighPassFilter::new(RATE).process(&speech);
+        assert!((rms(&passed[8000..]) / rms(&speech[8000..]) - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_noise_suppressor_preserves_length_and_alignment() {
+        // Quiet room first so the noise floor is known before the tone starts
+        let mut input = noise(0.001, 0.5);
+        input.extend(tone(440.0, 0.3, 1.0));
+        let mut suppressor = NoiseSuppressor::new();
+        let mut output = Vec::new();
+        for chunk in input.chunks(333) {
+            output.extend(suppressor.process(chunk));
+        }
+        output.extend(suppressor.finish());
+
+        assert_eq!(output.len(), input.len());
+        // A tone well above the noise floor passes unchanged and in place
+        let error = rms(&output[12000..20000]
+            .iter()
+            .zip(&input[12000..20000])
+            .map(|(a, b)| a - b)
+            .collect::<Vec<_>>());
+        assert!(error < 0.01);
+    }
+
+    #[test]
+    fn test_chain_cleans_noisy_fixture() {
+        // 2 s of background noise, then a tone on top of the same noise
+        let background = noise(0.02, 3.0);
+        let mut fixture = background.clone();
+        for (sample, tone) in fixture[2 * RATE as usize..]
+            .iter_mut()
+            .zip(tone(300.0, 0.1, 1.0))
+        {
+            *sample += tone;
+        }
+        let input_path = TempPath::file("dsp.wav");
+        let output_path = TempPath::file("dsp.out.wav");
+        write_wav(&input_path, RATE, 1, &fixture);
+
+        let mut chain = DspChain::new()
+            .with_stage(HighPassFilter::new(RATE))
+            .with_stage(NoiseSuppressor::new());
+        process_fixture(&input_path, &output_path, &mut chain);
+        let output = read_wav(&output_path);
+
+        assert_eq!(output.len(), fixture.len());
+        let noise_only = 8000..24000;
+        let with_tone = 36000..44000;
+        // Background drops by at least 10 dB, the tone stays within 2 dB
+        assert!(rms(&output[noise_only.clone()]) < rms(&fixture[noise_only]) * 0.32);
+        assert!(rms(&output[with_tone.clone()]) > rms(&tone(300.0, 0.1, 0.5)) * 0.8);
+    }
+
+    #[test]
+    fn test_loudness_normalizer_reaches_target() {
+        // -34 dBFS tone should be lifted to about -20 dBFS
+        let input = tone(300.0, 0.02 * std::f32::consts::SQRT_2, 5.0);
+        let output = LoudnessNormalizer::new(RATE).process(&input);
+        let level = rms(&output[4 * RATE as usize..]);
+        assert!(
+            (level / AGC_TARGET_RMS - 1.0).abs() < 0.15,
+            "level {}",
+            level
+        );
+
+        // Loud input never clips
+        let loud = LoudnessNormalizer::new(RATE).process(&tone(300.0, 0.99, 1.0));
+        assert!(loud.iter().all(|s| s.abs() <= AGC_PEAK_LIMIT + 1e-6));
+    }
+}
@@ -0,0 +1,811 @@
+//! Optional encryption at rest
+//!
+//! When enabled, recordings, archived transcriptions and the `*_config.json`
+//! files are stored sealed with XChaCha20-Poly1305. They all use one random
+//! data key, which is itself sealed in `encryption_config.json` with a key
+//! from the OS keyring or one derived from a passphrase (Argon2id). In
+//! passphrase mode nothing can be read or written until `unlock` was called.
+//!
+//! Sealed files start with a short header naming the data key, so files
+//! written before encryption was enabled stay readable and a key rotation that
+//! was interrupted can be finished later. Recordings are written in plain form
+//! while they are captured (crash recovery needs a readable WAV) and sealed as
+//! soon as the take is finalized.
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use zeroize::Zeroizing;
+
+/// Start of every sealed file
+const MAGIC: &[u8; 6] = b"EVENC1";
+
+/// Bytes of the key id in the header (start of the SHA-256 of the key)
+const KEY_ID_LEN: usize = 8;
+
+/// XChaCha20 nonce length
+const NONCE_LEN: usize = 24;
+
+const HEADER_LEN: usize = MAGIC.len() + KEY_ID_LEN + NONCE_LEN;
+
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+
+/// Where the key that seals the data key is kept in keyring mode
+const KEYRING_SERVICE: &str = "com.evervoice.app";
+const KEYRING_ACCOUNT: &str = "at-rest-key";
+
+/// Block size used to overwrite files before they are deleted
+const SCRUB_CHUNK: usize = 64 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum EncryptionError {
+    #[error("Encrypted data is locked, enter the passphrase first")]
+    Locked,
+    #[error("Encryption is not enabled")]
+    NotEnabled,
+    #[error("Encryption is already enabled")]
+    AlreadyEnabled,
+    #[error("A passphrase is required")]
+    PassphraseRequired,
+    #[error("Wrong passphrase")]
+    WrongPassphrase,
+    #[error("File was encrypted with an unknown key")]
+    UnknownKey,
+    #[error("Encrypted data is corrupt")]
+    Corrupt,
+    #[error("Keyring error: {0}")]
+    Keyring(String),
+    #[error("Key derivation failed: {0}")]
+    KeyDerivation(String),
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+}
+
+impl serde::Serialize for EncryptionError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl From<EncryptionError> for io::Error {
+    fn from(error: EncryptionError) -> Self {
+        match error {
+            EncryptionError::Io(e) => e,
+            EncryptionError::Locked => io::Error::new(io::ErrorKind::PermissionDenied, error),
+            other => io::Error::new(io::ErrorKind::InvalidData, other),
+        }
+    }
+}
+
+/// Where the key that seals the data key comes from
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum KeySource {
+    /// Random key in the OS keyring, nothing to enter
+    #[default]
+    Keyring,
+    /// Derived from a passphrase that is asked for on every start
+    Passphrase,
+}
+
+/// What the settings page shows
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub key_source: KeySource,
+    /// Keys are loaded, sealed files can be read and written
+    pub unlocked: bool,
+}
+
+/// Contents of `encryption_config.json`, the only file that is never sealed
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+struct EncryptionConfig {
+    enabled: bool,
+    #[serde(default)]
+    key_source: KeySource,
+    /// Argon2 salt of the passphrase (hex)
+    #[serde(default)]
+    salt: Option<String>,
+    /// The data key, sealed with the keyring or passphrase key (hex)
+    #[serde(default)]
+    data_key: Option<String>,
+    /// Data key before the last rotation, kept until every file was resealed
+    #[serde(default)]
+    previous_data_key: Option<String>,
+}
+
+/// A 256-bit key and its id
+struct SealingKey {
+    id: [u8; KEY_ID_LEN],
+    bytes: Zeroizing<[u8; KEY_LEN]>,
+}
+
+impl SealingKey {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, EncryptionError> {
+        if bytes.len() != KEY_LEN {
+            return Err(EncryptionError::Corrupt);
+        }
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        key.copy_from_slice(bytes);
+        let mut id = [0u8; KEY_ID_LEN];
+        id.copy_from_slice(&Sha256::digest(key.as_slice())[..KEY_ID_LEN]);
+        Ok(Self { id, bytes: key })
+    }
+
+    fn generate() -> Self {
+        let mut bytes = Zeroizing::new([0u8; KEY_LEN]);
+        OsRng.fill_bytes(bytes.as_mut_slice());
+        Self::from_bytes(bytes.as_slice()).expect("key has the right length")
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(self.bytes.as_slice().into())
+    }
+
+    /// Encrypt `plaintext` into a sealed file body
+    fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let mut sealed = Vec::with_capacity(HEADER_LEN + plaintext.len() + 16);
+        sealed.extend_from_slice(MAGIC);
+        sealed.extend_from_slice(&self.id);
+        sealed.extend_from_slice(&nonce);
+        // The header is authenticated too, so the key id cannot be swapped
+        let ciphertext = self
+            .cipher()
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: &sealed,
+                },
+            )
+            .map_err(|_| EncryptionError::Corrupt)?;
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+}
+
+/// Keys of an unlocked vault
+struct Keys {
+    /// Seals the data keys in the config
+    master: SealingKey,
+    /// Seals everything written from now on
+    current: SealingKey,
+    /// Data key of an interrupted rotation, still needed to read some files
+    previous: Option<SealingKey>,
+}
+
+impl Keys {
+    fn find(&self, id: &[u8]) -> Option<&SealingKey> {
+        std::iter::once(&self.current)
+            .chain(&self.previous)
+            .find(|key| key.id == id)
+    }
+}
+
+enum Vault {
+    Disabled,
+    /// Enabled, but the keys are not loaded yet (passphrase or keyring missing)
+    Locked,
+    Unlocked(Keys),
+}
+
+static VAULT: Mutex<Vault> = Mutex::new(Vault::Disabled);
+
+fn vault() -> MutexGuard<'static, Vault> {
+    VAULT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Whether `data` is a sealed file body
+fn is_sealed_data(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Decrypt a sealed file body with whichever of `keys` it was sealed with
+fn open(keys: &Keys, sealed: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    if sealed.len() < HEADER_LEN || !is_sealed_data(sealed) {
+        return Err(EncryptionError::Corrupt);
+    }
+    let (header, ciphertext) = sealed.split_at(HEADER_LEN);
+    let key = keys
+        .find(&header[MAGIC.len()..MAGIC.len() + KEY_ID_LEN])
+        .ok_or(EncryptionError::UnknownKey)?;
+    open_with(key, header, ciphertext)
+}
+
+fn open_with(
+    key: &SealingKey,
+    header: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, EncryptionError> {
+    let nonce = XNonce::from_slice(&header[MAGIC.len() + KEY_ID_LEN..]);
+    key.cipher()
+        .decrypt(
+            nonce,
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| EncryptionError::Corrupt)
+}
+
+/// Open a data key sealed with the master key
+fn open_data_key(master: &SealingKey, sealed_hex: &str) -> Result<SealingKey, EncryptionError> {
+    let sealed = from_hex(sealed_hex).ok_or(EncryptionError::Corrupt)?;
+    if sealed.len() < HEADER_LEN || !is_sealed_data(&sealed) {
+        return Err(EncryptionError::Corrupt);
+    }
+    let (header, ciphertext) = sealed.split_at(HEADER_LEN);
+    let bytes = Zeroizing::new(open_with(master, header, ciphertext)?);
+    SealingKey::from_bytes(&bytes)
+}
+
+fn seal_data_key(master: &SealingKey, key: &SealingKey) -> Result<String, EncryptionError> {
+    Ok(to_hex(&master.seal(key.bytes.as_slice())?))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+// ============================================================================
+// Master key and config
+// ============================================================================
+
+fn app_dir() -> PathBuf {
+    let app_dir = dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.evervoice.app");
+    let _ = fs::create_dir_all(&app_dir);
+    app_dir
+}
+
+fn get_config_path() -> PathBuf {
+    app_dir().join("encryption_config.json")
+}
+
+fn load_config() -> EncryptionConfig {
+    fs::read_to_string(get_config_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_config(config: &EncryptionConfig) -> Result<(), EncryptionError> {
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| EncryptionError::Io(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+    replace_file(&get_config_path(), json.as_bytes())?;
+    Ok(())
+}
+
+fn keyring_entry() -> Result<keyring::Entry, EncryptionError> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_ACCOUNT)
+        .map_err(|e| EncryptionError::Keyring(e.to_string()))
+}
+
+fn load_keyring_key() -> Result<SealingKey, EncryptionError> {
+    let hex = keyring_entry()?
+        .get_password()
+        .map_err(|e| EncryptionError::Keyring(e.to_string()))?;
+    let bytes = Zeroizing::new(from_hex(&hex).ok_or(EncryptionError::Corrupt)?);
+    SealingKey::from_bytes(&bytes)
+}
+
+fn store_keyring_key(key: &SealingKey) -> Result<(), EncryptionError> {
+    keyring_entry()?
+        .set_password(&to_hex(key.bytes.as_slice()))
+        .map_err(|e| EncryptionError::Keyring(e.to_string()))
+}
+
+/// Derive the master key from a passphrase and the hex salt
+fn derive_key(passphrase: &str, salt_hex: &str) -> Result<SealingKey, EncryptionError> {
+    if passphrase.is_empty() {
+        return Err(EncryptionError::PassphraseRequired);
+    }
+    let salt = from_hex(salt_hex).ok_or(EncryptionError::Corrupt)?;
+    let mut bytes = Zeroizing::new([0u8; KEY_LEN]);
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), &salt, bytes.as_mut_slice())
+        .map_err(|e| EncryptionError::KeyDerivation(e.to_string()))?;
+    SealingKey::from_bytes(bytes.as_slice())
+}
+
+fn new_salt() -> String {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    to_hex(&salt)
+}
+
+/// Open the data keys of `config` with the master key
+fn open_keys(config: &EncryptionConfig, master: SealingKey) -> Result<Keys, EncryptionError> {
+    let sealed = config.data_key.as_deref().ok_or(EncryptionError::Corrupt)?;
+    let current = open_data_key(&master, sealed)?;
+    let previous = config
+        .previous_data_key
+        .as_deref()
+        .map(|sealed| open_data_key(&master, sealed))
+        .transpose()?;
+    Ok(Keys {
+        master,
+        current,
+        previous,
+    })
+}
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Load the encryption state on startup, before any config file is read
+///
+/// In keyring mode the keys are loaded right away; in passphrase mode, or if
+/// the keyring entry is missing, everything stays locked until `unlock`.
+pub fn init() {
+    let config = load_config();
+    let state = if !config.enabled {
+        Vault::Disabled
+    } else if config.key_source == KeySource::Keyring {
+        match load_keyring_key().and_then(|master| open_keys(&config, master)) {
+            Ok(keys) => Vault::Unlocked(keys),
+            Err(e) => {
+                log::error!("Could not load the encryption key from the keyring: {}", e);
+                Vault::Locked
+            }
+        }
+    } else {
+        Vault::Locked
+    };
+    if config.previous_data_key.is_some() {
+        log::warn!("A key rotation was interrupted, the next rotation finishes it");
+    }
+    *vault() = state;
+}
+
+pub fn status() -> EncryptionStatus {
+    let config = load_config();
+    EncryptionStatus {
+        enabled: config.enabled,
+        key_source: config.key_source,
+        unlocked: matches!(*vault(), Vault::Unlocked(_)),
+    }
+}
+
+/// Whether encryption is enabled but the keys are not loaded
+pub fn is_locked() -> bool {
+    matches!(*vault(), Vault::Locked)
+}
+
+/// Load the keys with the passphrase (or retry the keyring in keyring mode)
+pub fn unlock(passphrase: &str) -> Result<(), EncryptionError> {
+    let config = load_config();
+    if !config.enabled {
+        return Err(EncryptionError::NotEnabled);
+    }
+    let keys = match config.key_source {
+        KeySource::Keyring => open_keys(&config, load_keyring_key()?)?,
+        KeySource::Passphrase => {
+            let salt = config.salt.as_deref().ok_or(EncryptionError::Corrupt)?;
+            let master = derive_key(passphrase, salt)?;
+            open_keys(&config, master).map_err(|e| match e {
+                EncryptionError::Corrupt => EncryptionError::WrongPassphrase,
+                other => other,
+            })?
+        }
+    };
+    *vault() = Vault::Unlocked(keys);
+    log::info!("Encryption unlocked");
+    Ok(())
+}
+
+/// Turn encryption on and seal `files` with a new data key
+///
+/// Returns how many files were sealed.
+pub fn enable(
+    key_source: KeySource,
+    passphrase: Option<&str>,
+    files: &[PathBuf],
+) -> Result<usize, EncryptionError> {
+    let mut vault = vault();
+    if !matches!(*vault, Vault::Disabled) {
+        return Err(EncryptionError::AlreadyEnabled);
+    }
+
+    let mut config = EncryptionConfig {
+        enabled: true,
+        key_source,
+        ..EncryptionConfig::default()
+    };
+    let master = match key_source {
+        KeySource::Keyring => {
+            let master = SealingKey::generate();
+            store_keyring_key(&master)?;
+            master
+        }
+        KeySource::Passphrase => {
+            let salt = new_salt();
+            let master = derive_key(passphrase.unwrap_or_default(), &salt)?;
+            config.salt = Some(salt);
+            master
+        }
+    };
+    let current = SealingKey::generate();
+    config.data_key = Some(seal_data_key(&master, &current)?);
+
+    // The config goes first: if sealing stops halfway, the rest stays readable
+    save_config(&config)?;
+    let keys = Keys {
+        master,
+        current,
+        previous: None,
+    };
+    let sealed = reseal_all(&keys, files, Some(&keys.current));
+    *vault = Vault::Unlocked(keys);
+    let sealed = sealed?;
+    log::info!("Encryption enabled, {} files sealed", sealed);
+    Ok(sealed)
+}
+
+/// Decrypt `files` and turn encryption off
+pub fn disable(files: &[PathBuf]) -> Result<usize, EncryptionError> {
+    let mut vault = vault();
+    let keys = match &*vault {
+        Vault::Unlocked(keys) => keys,
+        Vault::Locked => return Err(EncryptionError::Locked),
+        Vault::Disabled => return Err(EncryptionError::NotEnabled),
+    };
+    let opened = reseal_all(keys, files, None)?;
+
+    let config = load_config();
+    save_config(&EncryptionConfig::default())?;
+    if config.key_source == KeySource::Keyring {
+        if let Err(e) = keyring_entry().and_then(|entry| {
+            entry
+                .delete_password()
+                .map_err(|e| EncryptionError::Keyring(e.to_string()))
+        }) {
+            log::warn!(
+                "Could not remove the encryption key from the keyring: {}",
+                e
+            );
+        }
+    }
+    *vault = Vault::Disabled;
+    log::info!("Encryption disabled, {} files decrypted", opened);
+    Ok(opened)
+}
+
+/// Reseal `files` with a new data key
+///
+/// In passphrase mode `new_passphrase` also changes the passphrase. The old
+/// data key stays in the config until every file was resealed, so an
+/// interrupted rotation loses nothing and the next one finishes it.
+pub fn rotate(new_passphrase: Option<&str>, files: &[PathBuf]) -> Result<usize, EncryptionError> {
+    let mut vault = vault();
+    let keys = match &mut *vault {
+        Vault::Unlocked(keys) => keys,
+        Vault::Locked => return Err(EncryptionError::Locked),
+        Vault::Disabled => return Err(EncryptionError::NotEnabled),
+    };
+    let mut config = load_config();
+
+    // Finish an interrupted rotation first, only two data keys fit in the config
+    if keys.previous.is_some() {
+        reseal_all(keys, files, Some(&keys.current))?;
+        config.previous_data_key = None;
+        save_config(&config)?;
+        keys.previous = None;
+    }
+
+    if let (KeySource::Passphrase, Some(passphrase)) = (config.key_source, new_passphrase) {
+        let salt = new_salt();
+        keys.master = derive_key(passphrase, &salt)?;
+        config.salt = Some(salt);
+    }
+    let previous = std::mem::replace(&mut keys.current, SealingKey::generate());
+    config.data_key = Some(seal_data_key(&keys.master, &keys.current)?);
+    config.previous_data_key = Some(seal_data_key(&keys.master, &previous)?);
+    save_config(&config)?;
+    keys.previous = Some(previous);
+
+    let resealed = reseal_all(keys, files, Some(&keys.current))?;
+    config.previous_data_key = None;
+    save_config(&config)?;
+    keys.previous = None;
+    log::info!("Encryption key rotated, {} files resealed", resealed);
+    Ok(resealed)
+}
+
+/// Files the encryption manages: the configs, the recordings, and whatever in
+/// the archive is already sealed
+///
+/// Archive files in plain form are left alone, the archive folder may hold
+/// the user's own notes.
+pub fn protected_files(recordings_dir: &Path, archive_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = list_dir(&app_dir())
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.ends_with("_config.json") && name != "encryption_config.json"
+                })
+        })
+        .collect();
+    // A plain take with a journal may still be repaired by the crash recovery
+    files.extend(list_dir(recordings_dir).into_iter().filter(|path| {
+        path.extension().is_some_and(|ext| ext == "wav")
+            && (is_sealed(path) || !crate::recovery::has_journal(path))
+    }));
+    if let Some(dir) = archive_dir {
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            for path in list_dir(&dir) {
+                if path.is_dir() {
+                    pending.push(path);
+                } else if is_sealed(&path) {
+                    files.push(path);
+                }
+            }
+        }
+    }
+    files
+}
+
+fn list_dir(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default()
+}
+
+/// Bring every file to `target` (None decrypts), returns how many changed
+fn reseal_all(
+    keys: &Keys,
+    files: &[PathBuf],
+    target: Option<&SealingKey>,
+) -> Result<usize, EncryptionError> {
+    let mut changed = 0;
+    for path in files {
+        match reseal(keys, path, target) {
+            Ok(true) => changed += 1,
+            Ok(false) => {}
+            Err(EncryptionError::Io(e)) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                log::error!("Could not reseal {:?}: {}", path, e);
+                return Err(e);
+            }
+        }
+    }
+    Ok(changed)
+}
+
+fn reseal(keys: &Keys, path: &Path, target: Option<&SealingKey>) -> Result<bool, EncryptionError> {
+    let data = fs::read(path)?;
+    let plaintext = if is_sealed_data(&data) {
+        let id = &data[MAGIC.len()..(MAGIC.len() + KEY_ID_LEN).min(data.len())];
+        if target.is_some_and(|key| key.id == id) {
+            return Ok(false);
+        }
+        Zeroizing::new(open(keys, &data)?)
+    } else if target.is_none() {
+        return Ok(false);
+    } else {
+        Zeroizing::new(data)
+    };
+    let contents = match target {
+        Some(key) => key.seal(&plaintext)?,
+        None => plaintext.to_vec(),
+    };
+    replace_file(path, &contents)?;
+    Ok(true)
+}
+
+/// Replace a file's contents, overwriting the old ones on disk
+///
+/// The new contents go to a temporary file first; the old file is scrubbed
+/// right before the temporary one is renamed over it.
+fn replace_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = path.with_file_name(format!(".{}.tmp", name));
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let written = options.open(&temp).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    if path.exists() {
+        scrub(path)?;
+    }
+    fs::rename(&temp, path)
+}
+
+/// Overwrite a file with zeros in place
+fn scrub(path: &Path) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new().write(true).open(path)?;
+    let mut remaining = file.metadata()?.len();
+    let zeros = vec![0u8; SCRUB_CHUNK];
+    while remaining > 0 {
+        let len = remaining.min(SCRUB_CHUNK as u64) as usize;
+        file.write_all(&zeros[..len])?;
+        remaining -= len as u64;
+    }
+    file.sync_all()
+}
+
+/// Whether the file at `path` is sealed
+pub fn is_sealed(path: &Path) -> bool {
+    let mut magic = [0u8; MAGIC.len()];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok_and(|_| &magic == MAGIC)
+}
+
+/// Read a file, decrypting it if it is sealed
+pub fn read(path: &Path) -> io::Result<Vec<u8>> {
+    let data = fs::read(path)?;
+    if !is_sealed_data(&data) {
+        return Ok(data);
+    }
+    match &*vault() {
+        Vault::Unlocked(keys) => Ok(open(keys, &data)?),
+        Vault::Locked => Err(EncryptionError::Locked.into()),
+        Vault::Disabled => Err(EncryptionError::NotEnabled.into()),
+    }
+}
+
+pub fn read_to_string(path: &Path) -> io::Result<String> {
+    String::from_utf8(read(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Seal `contents` if encryption is enabled, otherwise return them as they are
+pub fn seal(contents: &[u8]) -> io::Result<Vec<u8>> {
+    match &*vault() {
+        Vault::Unlocked(keys) => Ok(keys.current.seal(contents)?),
+        Vault::Locked => Err(EncryptionError::Locked.into()),
+        Vault::Disabled => Ok(contents.to_vec()),
+    }
+}
+
+/// Write a file, sealed if encryption is enabled
+pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    fs::write(path, seal(contents.as_ref())?)
+}
+
+/// Seal a finished file in place if encryption is enabled
+pub fn seal_file(path: &Path) -> io::Result<()> {
+    match &*vault() {
+        Vault::Unlocked(keys) => reseal(keys, path, Some(&keys.current))
+            .map(|_| ())
+            .map_err(io::Error::from),
+        Vault::Locked => Err(EncryptionError::Locked.into()),
+        Vault::Disabled => Ok(()),
+    }
+}
+
+/// Overwrite a file with zeros before deleting it
+///
+/// On SSDs and copy-on-write file systems the old blocks may survive anyway;
+/// that is what sealing the files is for.
+pub fn secure_delete(path: &Path) -> io::Result<()> {
+    scrub(path)?;
+    fs::remove_file(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempPath;
+
+    fn test_keys() -> Keys {
+        Keys {
+            master: SealingKey::generate(),
+            current: SealingKey::generate(),
+            previous: None,
+        }
+    }
+
+    #[test]
+    fn test_sealed_data_round_trips_and_detects_tampering() {
+        let keys = test_keys();
+        let sealed = keys.current.seal(b"Sehr geehrte Damen und Herren").unwrap();
+        assert!(is_sealed_data(&sealed));
+        assert_eq!(
+            open(&keys, &sealed).unwrap(),
+            b"Sehr geehrte Damen und Herren"
+        );
+
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            open(&keys, &tampered),
+            Err(EncryptionError::Corrupt)
+        ));
+
+        let other = test_keys();
+        assert!(matches!(
+            open(&other, &sealed),
+            Err(EncryptionError::UnknownKey)
+        ));
+    }
+
+    #[test]
+    fn test_data_key_only_opens_with_the_right_passphrase() {
+        let salt = new_salt();
+        let master = derive_key("richtig", &salt).unwrap();
+        let data_key = SealingKey::generate();
+        let sealed = seal_data_key(&master, &data_key).unwrap();
+
+        let opened = open_data_key(&derive_key("richtig", &salt).unwrap(), &sealed).unwrap();
+        assert_eq!(opened.id, data_key.id);
+        assert!(open_data_key(&derive_key("falsch", &salt).unwrap(), &sealed).is_err());
+        assert!(matches!(
+            derive_key("", &salt),
+            Err(EncryptionError::PassphraseRequired)
+        ));
+    }
+
+    #[test]
+    fn test_reseal_moves_files_between_keys_and_back_to_plain() {
+        let dir = TempPath::dir("reseal");
+        let path = dir.join("take.wav");
+        fs::write(&path, b"RIFF....WAVE").unwrap();
+
+        let mut keys = test_keys();
+        assert!(reseal(&keys, &path, Some(&keys.current)).unwrap());
+        assert!(is_sealed(&path));
+        // Already sealed with the target key: left alone
+        assert!(!reseal(&keys, &path, Some(&keys.current)).unwrap());
+
+        // Rotation: the old key stays readable until the file was resealed
+        let previous = std::mem::replace(&mut keys.current, SealingKey::generate());
+        keys.previous = Some(previous);
+        assert!(reseal(&keys, &path, Some(&keys.current)).unwrap());
+        keys.previous = None;
+        assert_eq!(
+            open(&keys, &fs::read(&path).unwrap()).unwrap(),
+            b"RIFF....WAVE"
+        );
+
+        assert!(reseal(&keys, &path, None).unwrap());
+        assert_eq!(fs::read(&path).unwrap(), b"RIFF....WAVE");
+        assert!(!dir.join(".take.wav.tmp").exists());
+
+        secure_delete(&path).unwrap();
+        assert!(!path.exists());
+    }
+}
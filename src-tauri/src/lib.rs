@@ -1,11 +1,11 @@
 use std::fs;
 use std::io::Write;
 use std::panic;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem},
     path::BaseDirectory,
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     Emitter, Manager, Runtime, State,
@@ -15,19 +15,37 @@ use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
 mod archive;
 mod audio;
+mod audio_encode;
+mod audio_import;
+mod audio_quality;
+mod audio_source;
 mod context;
+mod dsp;
+mod encryption;
+mod model_import;
+mod model_registry;
 mod ollama;
+mod recovery;
+#[cfg(test)]
+mod test_util;
 mod text_insert;
 mod whisper;
 
 use archive::{ArchiveManager, ArchiveResult, ArchiveSettings, FolderStructure, TranscriptionData};
-use audio::{AudioDevice, AudioError, AudioRecorder, AudioSettings, RecordingResult};
+use audio::{
+    AudioDevice, AudioError, AudioRecorder, AudioSettings, LiveAudio, PauseMarker, RecorderEvent,
+    RecordingResult, SpeechSegment, SystemTrack,
+};
+use audio_source::{FileSource, Pace};
 use context::{AppCategory, AppContext, AppMapping, ContextConfig, ContextManager};
+use encryption::{EncryptionStatus, KeySource};
+use model_registry::ModelEntry;
 use ollama::{AutoEditResult, ChatContextSettings, EmailContextSettings, OllamaManager, OllamaSettings, OllamaStatus};
+use recovery::{OrphanedRecording, RecoveryAction};
 use text_insert::{InsertMethod, TextInsertResult, TextInsertSettings};
 use whisper::{
-    DownloadProgress, ModelStatus, TranscriptionResult, WhisperError, WhisperLanguage,
-    WhisperManager, WhisperModel, WhisperSettings,
+    DownloadProgress, LanguageInfo, ModelStatus, PartialTranscript, Speaker, StreamingSession,
+    TranscriptionResult, WhisperError, WhisperManager, WhisperSettings,
 };
 
 /// Hotkey mode: Push-to-Talk or Toggle
@@ -49,6 +67,9 @@ pub struct HotkeySettings {
     pub shortcut: String,
     pub mode: HotkeyMode,
     pub enabled: bool,
+    /// Optional second shortcut that pauses and resumes the running take
+    #[serde(default)]
+    pub pause_shortcut: Option<String>,
 }
 
 impl Default for HotkeySettings {
@@ -61,6 +82,7 @@ impl Default for HotkeySettings {
             shortcut: "Control+Shift+Space".to_string(),
             mode: HotkeyMode::Toggle,
             enabled: true,
+            pause_shortcut: None,
         }
     }
 }
@@ -70,6 +92,8 @@ pub struct AppState {
     current_status: Mutex<AppStatus>,
     had_previous_crash: Mutex<bool>,
     crash_info: Mutex<Option<CrashInfo>>,
+    /// Unprocessed recordings found on startup, waiting for the user's decision
+    orphaned_recordings: Mutex<Vec<OrphanedRecording>>,
     hotkey_settings: Mutex<HotkeySettings>,
     hotkey_press_time: Mutex<Option<std::time::Instant>>,
     is_recording: Mutex<bool>,
@@ -78,6 +102,8 @@ pub struct AppState {
     // Whisper state (PROJ-4)
     whisper_manager: Mutex<WhisperManager>,
     whisper_settings: Mutex<WhisperSettings>,
+    /// Live transcription of the current or last take
+    streaming_session: Mutex<Option<StreamingSession>>,
     // Text insert state (PROJ-6)
     text_insert_settings: Mutex<TextInsertSettings>,
     // Ollama state (PROJ-7)
@@ -100,6 +126,7 @@ impl Default for AppState {
             current_status: Mutex::new(AppStatus::Idle),
             had_previous_crash: Mutex::new(false),
             crash_info: Mutex::new(None),
+            orphaned_recordings: Mutex::new(Vec::new()),
             hotkey_settings: Mutex::new(HotkeySettings::default()),
             hotkey_press_time: Mutex::new(None),
             is_recording: Mutex::new(false),
@@ -107,6 +134,7 @@ impl Default for AppState {
             audio_settings: Mutex::new(AudioSettings::default()),
             whisper_manager: Mutex::new(WhisperManager::new()),
             whisper_settings: Mutex::new(WhisperSettings::default()),
+            streaming_session: Mutex::new(None),
             text_insert_settings: Mutex::new(TextInsertSettings::default()),
             ollama_manager: Mutex::new(OllamaManager::new()),
             ollama_settings: Mutex::new(OllamaSettings::default()),
@@ -158,6 +186,15 @@ fn get_status_tooltip(status: AppStatus) -> &'static str {
     }
 }
 
+/// Tooltip text for the tray, flags when the warm microphone holds the mic open
+fn get_tray_tooltip(status: AppStatus, warm_mic: bool) -> String {
+    if warm_mic {
+        format!("{} (Mikrofon vorgehalten)", get_status_tooltip(status))
+    } else {
+        get_status_tooltip(status).to_string()
+    }
+}
+
 // ============================================================================
 // Crash Recovery Functions
 // ============================================================================
@@ -225,6 +262,15 @@ fn check_crash_marker() -> Option<CrashInfo> {
     None
 }
 
+/// When the crash happened, from the timestamp written by the panic handler
+fn crash_time(info: &CrashInfo) -> Option<std::time::SystemTime> {
+    chrono::NaiveDateTime::parse_from_str(&info.timestamp, "%Y-%m-%d %H:%M:%S")
+        .ok()?
+        .and_local_timezone(chrono::Local)
+        .earliest()
+        .map(Into::into)
+}
+
 /// Set up the panic handler to capture crashes
 fn setup_panic_handler() {
     let default_hook = panic::take_hook();
@@ -302,6 +348,98 @@ async fn clear_crash_log() -> Result<(), String> {
     Ok(())
 }
 
+/// Recordings the previous session left unprocessed
+#[tauri::command]
+async fn get_orphaned_recordings(
+    state: State<'_, AppState>,
+) -> Result<Vec<OrphanedRecording>, String> {
+    let orphans = state
+        .orphaned_recordings
+        .lock()
+        .map_err(|e| e.to_string())?;
+    Ok(orphans.clone())
+}
+
+/// Transcribe, archive or discard a recording found on startup
+///
+/// `Transcribe` returns the recording like a finished take for the normal
+/// pipeline; it stays marked as unprocessed until `transcribe_audio` succeeds.
+#[tauri::command]
+async fn resolve_orphaned_recording(
+    state: State<'_, AppState>,
+    file_path: String,
+    action: RecoveryAction,
+) -> Result<Option<RecordingResult>, String> {
+    // Only recordings from the startup scan, never arbitrary paths
+    let orphan = {
+        let orphans = state
+            .orphaned_recordings
+            .lock()
+            .map_err(|e| e.to_string())?;
+        orphans
+            .iter()
+            .find(|orphan| orphan.file_path == file_path)
+            .cloned()
+            .ok_or_else(|| "Unknown recording".to_string())?
+    };
+
+    let result = match action {
+        RecoveryAction::Transcribe => {
+            // The speech segments did not survive the crash, the VAD finds them again
+            let detect = |path: &str| {
+                audio::detect_speech_in_file(Path::new(path)).map_err(|e| e.to_string())
+            };
+            let system_track = match &orphan.system_track {
+                Some(path) => Some(SystemTrack {
+                    file_path: path.clone(),
+                    speech_segments: detect(path)?,
+                }),
+                None => None,
+            };
+            Some(RecordingResult {
+                file_path: orphan.file_path.clone(),
+                duration_ms: orphan.duration_ms,
+                privacy_mode: orphan.privacy_mode,
+                speech_segments: detect(&orphan.file_path)?,
+                dropped_frames: 0,
+                capture_config: None,
+                device_gaps: Vec::new(),
+                pause_markers: Vec::new(),
+                quality: None,
+                system_track,
+                trim_offset_ms: 0,
+            })
+        }
+        RecoveryAction::Archive => {
+            let recorded_at = orphan
+                .started_at
+                .as_deref()
+                .and_then(|date| chrono::DateTime::parse_from_rfc3339(date).ok())
+                .map(|date| date.with_timezone(&chrono::Local))
+                .unwrap_or_else(chrono::Local::now);
+            let manager = state.archive_manager.lock().map_err(|e| e.to_string())?;
+            manager.archive_recording(Path::new(&file_path), &recorded_at)?;
+            if let Some(system_path) = &orphan.system_track {
+                manager.archive_recording(Path::new(system_path), &recorded_at)?;
+            }
+            recovery::clear_journal(Path::new(&file_path));
+            None
+        }
+        RecoveryAction::Discard => {
+            AudioRecorder::delete_recording(&file_path).map_err(|e| e.to_string())?;
+            None
+        }
+    };
+
+    log::info!("Recovered recording {}: {:?}", file_path, action);
+    let mut orphans = state
+        .orphaned_recordings
+        .lock()
+        .map_err(|e| e.to_string())?;
+    orphans.retain(|orphan| orphan.file_path != file_path);
+    Ok(result)
+}
+
 // ============================================================================
 // Hotkey Configuration Functions
 // ============================================================================
@@ -319,7 +457,7 @@ fn get_hotkey_config_path() -> PathBuf {
 fn load_hotkey_settings() -> HotkeySettings {
     let config_path = get_hotkey_config_path();
     if config_path.exists() {
-        if let Ok(content) = fs::read_to_string(&config_path) {
+        if let Ok(content) = encryption::read_to_string(&config_path) {
             if let Ok(settings) = serde_json::from_str(&content) {
                 return settings;
             }
@@ -332,7 +470,7 @@ fn load_hotkey_settings() -> HotkeySettings {
 fn save_hotkey_settings(settings: &HotkeySettings) -> Result<(), String> {
     let config_path = get_hotkey_config_path();
     let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
-    fs::write(&config_path, json).map_err(|e| e.to_string())
+    encryption::write(&config_path, json).map_err(|e| e.to_string())
 }
 
 /// Get current hotkey settings
@@ -349,15 +487,21 @@ async fn set_hotkey_settings<R: Runtime>(
     state: State<'_, AppState>,
     settings: HotkeySettings,
 ) -> Result<(), String> {
-    // Get old shortcut to unregister
-    let old_shortcut = {
+    if settings.pause_shortcut.as_deref() == Some(settings.shortcut.as_str()) {
+        return Err("Pause-Hotkey muss sich vom Aufnahme-Hotkey unterscheiden".to_string());
+    }
+
+    // Get old shortcuts to unregister
+    let (old_shortcut, old_pause_shortcut) = {
         let current = state.hotkey_settings.lock().map_err(|e| e.to_string())?;
-        current.shortcut.clone()
+        (current.shortcut.clone(), current.pause_shortcut.clone())
     };
 
-    // Unregister old shortcut
-    if let Ok(old_sc) = old_shortcut.parse::<Shortcut>() {
-        let _ = app.global_shortcut().unregister(old_sc);
+    // Unregister old shortcuts
+    for old in std::iter::once(old_shortcut).chain(old_pause_shortcut) {
+        if let Ok(old_sc) = old.parse::<Shortcut>() {
+            let _ = app.global_shortcut().unregister(old_sc);
+        }
     }
 
     // Save new settings
@@ -369,9 +513,12 @@ async fn set_hotkey_settings<R: Runtime>(
         *current = settings.clone();
     }
 
-    // Register new shortcut if enabled
+    // Register new shortcuts if enabled
     if settings.enabled {
         register_global_hotkey(&app, &settings.shortcut)?;
+        if let Some(pause_shortcut) = &settings.pause_shortcut {
+            register_pause_hotkey(&app, pause_shortcut)?;
+        }
     }
 
     log::info!("Hotkey settings updated: {:?}", settings);
@@ -442,6 +589,15 @@ async fn list_audio_devices(state: State<'_, AppState>) -> Result<Vec<AudioDevic
     recorder.list_devices().map_err(|e| e.to_string())
 }
 
+/// List monitor and loopback sources system audio can be recorded from
+#[tauri::command]
+async fn list_system_audio_sources(state: State<'_, AppState>) -> Result<Vec<AudioDevice>, String> {
+    let recorder = state.audio_recorder.lock().map_err(|e| e.to_string())?;
+    recorder
+        .list_system_audio_sources()
+        .map_err(|e| e.to_string())
+}
+
 /// Get current audio settings
 #[tauri::command]
 async fn get_audio_settings(state: State<'_, AppState>) -> Result<AudioSettings, String> {
@@ -451,9 +607,19 @@ async fn get_audio_settings(state: State<'_, AppState>) -> Result<AudioSettings,
 
 /// Update audio settings
 #[tauri::command]
-async fn set_audio_settings(
+async fn set_audio_settings<R: Runtime>(
+    app: tauri::AppHandle<R>,
     state: State<'_, AppState>,
     settings: AudioSettings,
+) -> Result<(), String> {
+    apply_audio_settings(&app, &state, settings)
+}
+
+/// Store, apply and persist audio settings, then refresh the warm microphone state
+fn apply_audio_settings<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    state: &AppState,
+    settings: AudioSettings,
 ) -> Result<(), String> {
     // Save to state
     {
@@ -465,15 +631,76 @@ async fn set_audio_settings(
     {
         let mut recorder = state.audio_recorder.lock().map_err(|e| e.to_string())?;
         recorder.update_settings(settings.clone());
+        if let Err(e) = recorder.sync_warm_mic() {
+            // Recording still works without it, the stream is opened on demand
+            log::warn!("Failed to open warm microphone: {}", e);
+        }
     }
 
     // Persist to config file
     save_audio_settings(&settings)?;
 
+    refresh_warm_mic_state(app);
+
     log::info!("Audio settings updated: {:?}", settings);
     Ok(())
 }
 
+/// Check if the warm microphone currently holds the input stream open
+fn is_warm_mic_active<R: Runtime>(app: &tauri::AppHandle<R>) -> bool {
+    let state: State<'_, AppState> = app.state();
+    let active = state
+        .audio_recorder
+        .lock()
+        .map(|recorder| recorder.is_warm())
+        .unwrap_or(false);
+    active
+}
+
+/// Show the warm microphone state in the tray and tell the frontend about it
+fn refresh_warm_mic_state<R: Runtime>(app: &tauri::AppHandle<R>) {
+    let warm_mic = is_warm_mic_active(app);
+    let status = {
+        let state: State<'_, AppState> = app.state();
+        let status = state.current_status.lock().map(|s| *s).unwrap_or_default();
+        status
+    };
+
+    if let Some(tray) = app.tray_by_id("main") {
+        let _ = tray.set_tooltip(Some(get_tray_tooltip(status, warm_mic)));
+        if let Ok(menu) = create_tray_menu(app, warm_mic) {
+            let _ = tray.set_menu(Some(menu));
+        }
+    }
+
+    let _ = app.emit("warm-mic-changed", warm_mic);
+}
+
+/// Check if the warm microphone is holding the input stream open
+#[tauri::command]
+async fn get_warm_mic_state<R: Runtime>(app: tauri::AppHandle<R>) -> Result<bool, String> {
+    Ok(is_warm_mic_active(&app))
+}
+
+/// Turn the warm microphone on or off (tray toggle)
+fn toggle_warm_mic<R: Runtime>(app: &tauri::AppHandle<R>) {
+    let state: State<'_, AppState> = app.state();
+    let settings = match state.audio_settings.lock() {
+        Ok(current) => AudioSettings {
+            warm_mic: !current.warm_mic,
+            ..current.clone()
+        },
+        Err(e) => {
+            log::error!("Failed to read audio settings: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = apply_audio_settings(app, &state, settings) {
+        log::error!("Failed to toggle warm microphone: {}", e);
+    }
+}
+
 /// Start audio recording
 #[tauri::command]
 async fn start_audio_recording<R: Runtime>(
@@ -488,9 +715,33 @@ async fn start_audio_recording<R: Runtime>(
         }
     }
 
+    // Silence auto-stop only makes sense when no key is held during the take
+    let toggle_mode = {
+        let hotkey_settings = state.hotkey_settings.lock().map_err(|e| e.to_string())?;
+        hotkey_settings.mode == HotkeyMode::Toggle
+    };
+
+    // Live transcription listens to the take from its first sample, if there
+    // is a model to transcribe with; a manager busy with the last take has one
+    let streaming = {
+        let whisper_settings = state.whisper_settings.lock().map_err(|e| e.to_string())?;
+        whisper_settings.streaming
+    };
+    let live_audio = (streaming
+        && state
+            .whisper_manager
+            .try_lock()
+            .map_or(true, |manager| manager.is_model_available()))
+    .then(LiveAudio::default);
+    if streaming && live_audio.is_none() {
+        log::info!("No model downloaded, recording without live transcription");
+    }
+
     // Start recording
     {
         let mut recorder = state.audio_recorder.lock().map_err(|e| e.to_string())?;
+        recorder.set_silence_auto_stop(toggle_mode);
+        recorder.set_live_tap(live_audio.clone());
         recorder.start_recording().map_err(|e| {
             let error_msg = e.to_string();
             // Emit specific error events for UI handling
@@ -512,8 +763,11 @@ async fn start_audio_recording<R: Runtime>(
         })?;
     }
 
-    // Note: Audio level is polled by frontend via get_audio_level command
-    // This avoids the need for complex thread management
+    // Levels are pushed as "audio-meter" events while recording
+
+    if let Some(audio) = live_audio {
+        start_streaming_session(&app, &state, audio)?;
+    }
 
     // Emit recording started event
     let _ = app.emit("recording-started", ());
@@ -522,6 +776,44 @@ async fn start_audio_recording<R: Runtime>(
     Ok(())
 }
 
+/// Transcribe the take that just started while it records
+///
+/// Partial text goes out as "transcription-partial" events; the model is
+/// loaded on the session thread so the take starts right away. If live
+/// transcription fails, "transcription-stream-error" tells the frontend and
+/// the take is transcribed from the file once it stops.
+fn start_streaming_session<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    state: &State<'_, AppState>,
+    audio: LiveAudio,
+) -> Result<(), String> {
+    let opener = app.clone();
+    let emitter = app.clone();
+    let error_emitter = app.clone();
+    let session = StreamingSession::spawn(
+        move || {
+            let state: State<'_, AppState> = opener.state();
+            let mut manager = state
+                .whisper_manager
+                .lock()
+                .map_err(|e| WhisperError::ModelLoadError(e.to_string()))?;
+            manager.start_streaming()
+        },
+        audio,
+        Arc::new(move |partial: PartialTranscript| {
+            let _ = emitter.emit("transcription-partial", &partial);
+        }),
+        Arc::new(move |e: &WhisperError| {
+            log::warn!("Live transcription stopped: {}", e);
+            let _ = error_emitter.emit("transcription-stream-error", e.to_string());
+        }),
+    );
+
+    // A session nobody collected ends when it is replaced
+    *state.streaming_session.lock().map_err(|e| e.to_string())? = Some(session);
+    Ok(())
+}
+
 /// Stop audio recording and get the result
 #[tauri::command]
 async fn stop_audio_recording<R: Runtime>(
@@ -529,9 +821,10 @@ async fn stop_audio_recording<R: Runtime>(
     state: State<'_, AppState>,
 ) -> Result<RecordingResult, String> {
     // First check if recording is actually in progress
+    // A take interrupted by a stream error is still open and gets finalized here
     let is_recording = {
         let recorder = state.audio_recorder.lock().map_err(|e| e.to_string())?;
-        recorder.is_recording()
+        recorder.is_recording() || recorder.has_active_take()
     };
 
     if !is_recording {
@@ -541,6 +834,14 @@ async fn stop_audio_recording<R: Runtime>(
             file_path: String::new(),
             duration_ms: 0,
             privacy_mode: false,
+            speech_segments: Vec::new(),
+            dropped_frames: 0,
+            capture_config: None,
+            device_gaps: Vec::new(),
+            pause_markers: Vec::new(),
+            quality: None,
+            system_track: None,
+            trim_offset_ms: 0,
         });
     }
 
@@ -553,6 +854,16 @@ async fn stop_audio_recording<R: Runtime>(
         })?
     };
 
+    // The live transcription decodes the rest while the frontend moves on
+    if let Some(session) = state
+        .streaming_session
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_mut()
+    {
+        session.stop(&result.file_path);
+    }
+
     // Emit recording complete event
     let _ = app.emit("recording-complete", &result);
 
@@ -560,11 +871,54 @@ async fn stop_audio_recording<R: Runtime>(
     Ok(result)
 }
 
-/// Get current audio level (0-100)
+/// Pause or resume the running take and tell the frontend
+fn set_recording_paused<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    state: &State<'_, AppState>,
+    pause: bool,
+) -> Result<(), String> {
+    {
+        let mut recorder = state.audio_recorder.lock().map_err(|e| e.to_string())?;
+        let result = if pause {
+            recorder.pause_recording()
+        } else {
+            recorder.resume_recording()
+        };
+        result.map_err(|e| e.to_string())?;
+    }
+
+    let event = if pause {
+        "recording-paused"
+    } else {
+        "recording-resumed"
+    };
+    let _ = app.emit(event, ());
+    Ok(())
+}
+
+/// Pause the running take; the paused span is left out of the recording
+#[tauri::command]
+async fn pause_audio_recording<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    set_recording_paused(&app, &state, true)
+}
+
+/// Resume a paused take
+#[tauri::command]
+async fn resume_audio_recording<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    set_recording_paused(&app, &state, false)
+}
+
+/// Check if the running take is paused
 #[tauri::command]
-async fn get_audio_level(state: State<'_, AppState>) -> Result<u8, String> {
+async fn is_audio_recording_paused(state: State<'_, AppState>) -> Result<bool, String> {
     let recorder = state.audio_recorder.lock().map_err(|e| e.to_string())?;
-    Ok(recorder.get_level())
+    Ok(recorder.is_paused())
 }
 
 /// Check if currently recording audio
@@ -588,6 +942,50 @@ async fn delete_recording(file_path: String) -> Result<(), String> {
     AudioRecorder::delete_recording(&file_path).map_err(|e| e.to_string())
 }
 
+/// Mark a take as processed without transcribing it (e.g. skipped for silence)
+#[tauri::command]
+async fn mark_recording_processed(file_path: String) -> Result<(), String> {
+    let path = recording_path(&file_path)?;
+    recovery::clear_journal(&path);
+    Ok(())
+}
+
+/// Decode an external audio file into a 16kHz WAV ready for transcription
+#[tauri::command]
+async fn import_audio_file<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    path: String,
+) -> Result<RecordingResult, String> {
+    log::info!("Importing audio file: {}", path);
+    let _ = app.emit("audio-import-started", &path);
+
+    // Decoding a long file takes a while, keep it off the async runtime
+    let source = PathBuf::from(&path);
+    let recordings_dir = AudioRecorder::get_recordings_dir();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        audio_import::import_audio_file(&source, &recordings_dir)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| {
+        let error_msg = e.to_string();
+        let _ = app.emit("audio-import-error", &error_msg);
+        error_msg
+    })?;
+
+    let _ = app.emit("audio-import-complete", &result);
+    Ok(result)
+}
+
+/// File extensions accepted by `import_audio_file`
+#[tauri::command]
+async fn get_import_extensions() -> Result<Vec<String>, String> {
+    Ok(audio_import::SUPPORTED_EXTENSIONS
+        .iter()
+        .map(|e| e.to_string())
+        .collect())
+}
+
 /// Request microphone permission (macOS only)
 #[tauri::command]
 async fn request_microphone_permission() -> Result<(), String> {
@@ -609,6 +1007,80 @@ async fn request_microphone_permission() -> Result<(), String> {
     Ok(())
 }
 
+/// Forward a recorder event from the audio threads to the frontend
+fn emit_recorder_event<R: Runtime>(app: &tauri::AppHandle<R>, event: RecorderEvent) {
+    match event {
+        RecorderEvent::LimitWarning {
+            elapsed_ms,
+            remaining_ms,
+        } => {
+            log::info!("Recording limit approaching: {}ms left", remaining_ms);
+            let _ = app.emit(
+                "recording-limit-warning",
+                serde_json::json!({ "elapsed_ms": elapsed_ms, "remaining_ms": remaining_ms }),
+            );
+        }
+        RecorderEvent::LimitReached { elapsed_ms } => {
+            log::info!("Recording limit reached after {}ms", elapsed_ms);
+            let _ = app.emit("recording-limit-reached", elapsed_ms);
+        }
+        RecorderEvent::SilenceAutoStop { elapsed_ms } => {
+            log::info!("Recording stopped after silence at {}ms", elapsed_ms);
+            let _ = app.emit("recording-silence-stop", elapsed_ms);
+        }
+        RecorderEvent::CaptureOverrun { dropped_frames } => {
+            let _ = app.emit("recording-overrun", dropped_frames);
+        }
+        RecorderEvent::DeviceLost { elapsed_ms } => {
+            let _ = app.emit("recording-device-lost", elapsed_ms);
+            // The writer thread must not block on the recorder, recover elsewhere
+            let app = app.clone();
+            std::thread::spawn(move || recover_audio_device(&app));
+        }
+        RecorderEvent::DeviceChanged { device } => {
+            log::info!("Recording switched to input device: {}", device);
+            let _ = app.emit(
+                "recording-device-changed",
+                serde_json::json!({ "device": device }),
+            );
+        }
+        RecorderEvent::Meter(frame) => {
+            let _ = app.emit("audio-meter", &frame);
+        }
+        RecorderEvent::SystemAudioUnavailable { reason } => {
+            let _ = app.emit(
+                "recording-system-audio-unavailable",
+                serde_json::json!({ "reason": reason }),
+            );
+        }
+    }
+}
+
+/// Keep looking for a replacement input device until the take can continue
+///
+/// Gives up after `FAILOVER_TIMEOUT`; the stream error then reaches the
+/// frontend health check, which finalizes the partial take.
+fn recover_audio_device<R: Runtime>(app: &tauri::AppHandle<R>) {
+    let state: State<'_, AppState> = app.state();
+    let deadline = std::time::Instant::now() + audio::FAILOVER_TIMEOUT;
+
+    loop {
+        let Ok(mut recorder) = state.audio_recorder.lock() else {
+            return;
+        };
+        match recorder.fail_over() {
+            Ok(_) => return,
+            Err(e) if std::time::Instant::now() >= deadline => {
+                recorder.abandon_failover(&e.to_string());
+                return;
+            }
+            Err(e) => log::debug!("No replacement input device yet: {}", e),
+        }
+        drop(recorder);
+        std::thread::sleep(audio::FAILOVER_RETRY_INTERVAL);
+    }
+}
+
 /// Get the path to the audio settings config file
 fn get_audio_config_path() -> PathBuf {
     let app_dir = dirs::data_local_dir()
@@ -622,7 +1094,7 @@ fn get_audio_config_path() -> PathBuf {
 fn load_audio_settings() -> AudioSettings {
     let config_path = get_audio_config_path();
     if config_path.exists() {
-        if let Ok(content) = fs::read_to_string(&config_path) {
+        if let Ok(content) = encryption::read_to_string(&config_path) {
             if let Ok(settings) = serde_json::from_str(&content) {
                 return settings;
             }
@@ -635,7 +1107,7 @@ fn load_audio_settings() -> AudioSettings {
 fn save_audio_settings(settings: &AudioSettings) -> Result<(), String> {
     let config_path = get_audio_config_path();
     let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
-    fs::write(&config_path, json).map_err(|e| e.to_string())
+    encryption::write(&config_path, json).map_err(|e| e.to_string())
 }
 
 // ============================================================================
@@ -655,7 +1127,7 @@ fn get_whisper_config_path() -> PathBuf {
 fn load_whisper_settings() -> WhisperSettings {
     let config_path = get_whisper_config_path();
     if config_path.exists() {
-        if let Ok(content) = fs::read_to_string(&config_path) {
+        if let Ok(content) = encryption::read_to_string(&config_path) {
             if let Ok(settings) = serde_json::from_str(&content) {
                 return settings;
             }
@@ -668,7 +1140,7 @@ fn load_whisper_settings() -> WhisperSettings {
 fn save_whisper_settings(settings: &WhisperSettings) -> Result<(), String> {
     let config_path = get_whisper_config_path();
     let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
-    fs::write(&config_path, json).map_err(|e| e.to_string())
+    encryption::write(&config_path, json).map_err(|e| e.to_string())
 }
 
 /// Get current whisper settings
@@ -684,6 +1156,13 @@ async fn set_whisper_settings(
     state: State<'_, AppState>,
     settings: WhisperSettings,
 ) -> Result<(), String> {
+    {
+        let manager = state.whisper_manager.lock().map_err(|e| e.to_string())?;
+        settings
+            .validate(manager.registry())
+            .map_err(|e| e.to_string())?;
+    }
+
     // Save to state
     {
         let mut current = state.whisper_settings.lock().map_err(|e| e.to_string())?;
@@ -703,6 +1182,12 @@ async fn set_whisper_settings(
     Ok(())
 }
 
+/// Languages Whisper can transcribe, from its own table
+#[tauri::command]
+async fn get_whisper_languages() -> Result<Vec<LanguageInfo>, String> {
+    Ok(whisper::supported_languages())
+}
+
 /// Get status of all Whisper models
 #[tauri::command]
 async fn get_whisper_model_status(state: State<'_, AppState>) -> Result<Vec<ModelStatus>, String> {
@@ -715,23 +1200,27 @@ async fn get_whisper_model_status(state: State<'_, AppState>) -> Result<Vec<Mode
 async fn download_whisper_model<R: Runtime>(
     app: tauri::AppHandle<R>,
     state: State<'_, AppState>,
-    model: WhisperModel,
+    model: String,
 ) -> Result<(), String> {
-    // Clone what we need for the async operation
-    let manager = {
-        let m = state.whisper_manager.lock().map_err(|e| e.to_string())?;
-        // We need to create a new manager for async download since we can't hold the lock
-        // Actually, we'll use a simpler approach - download in the current context
-        drop(m);
-        WhisperManager::new()
+    // The downloader shares progress and cancel flag without holding the lock
+    let (downloader, entry) = {
+        let manager = state.whisper_manager.lock().map_err(|e| e.to_string())?;
+        let entry = manager
+            .model_entry(&model)
+            .map_err(|e| e.to_string())?
+            .clone();
+        (manager.downloader(), entry)
     };
 
-    log::info!("Starting download of Whisper model: {:?}", model);
+    log::info!("Starting download of Whisper model: {}", model);
 
-    // Start download
-    match manager.download_model(model).await {
+    // Start download, pushing progress to the frontend
+    let on_progress = |progress: &DownloadProgress| {
+        let _ = app.emit("whisper-download-progress", progress);
+    };
+    match downloader.download(&entry, on_progress).await {
         Ok(()) => {
-            log::info!("Whisper model {} downloaded successfully", model.name());
+            log::info!("Whisper model {} downloaded successfully", model);
             let _ = app.emit("whisper-download-complete", model);
             Ok(())
         }
@@ -743,6 +1232,34 @@ async fn download_whisper_model<R: Runtime>(
     }
 }
 
+/// Import local ggml model files, for machines without access to Hugging Face
+///
+/// `path` is a `ggml-*.bin` file or a directory of them.
+#[tauri::command]
+async fn import_whisper_models(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<Vec<ModelEntry>, String> {
+    log::info!("Importing Whisper models from: {}", path);
+    let mut registry = {
+        let manager = state.whisper_manager.lock().map_err(|e| e.to_string())?;
+        manager.registry().clone()
+    };
+
+    // Hashing and copying gigabytes takes a while, keep it off the async runtime
+    let source = PathBuf::from(&path);
+    let imported = tauri::async_runtime::spawn_blocking(move || {
+        model_import::import_models(&source, &mut registry)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    let mut manager = state.whisper_manager.lock().map_err(|e| e.to_string())?;
+    manager.reload_registry();
+    Ok(imported)
+}
+
 /// Get current download progress
 #[tauri::command]
 async fn get_whisper_download_progress(
@@ -763,18 +1280,19 @@ async fn cancel_whisper_download(state: State<'_, AppState>) -> Result<(), Strin
 
 /// Delete a downloaded model
 #[tauri::command]
-async fn delete_whisper_model(
-    state: State<'_, AppState>,
-    model: WhisperModel,
-) -> Result<(), String> {
+async fn delete_whisper_model(state: State<'_, AppState>, model: String) -> Result<(), String> {
     // Unload if currently loaded
-    {
+    let entry = {
         let mut manager = state.whisper_manager.lock().map_err(|e| e.to_string())?;
         manager.unload_model();
-    }
+        manager
+            .model_entry(&model)
+            .map_err(|e| e.to_string())?
+            .clone()
+    };
 
-    WhisperManager::delete_model(model).map_err(|e| e.to_string())?;
-    log::info!("Whisper model {} deleted", model.name());
+    WhisperManager::delete_model(&entry).map_err(|e| e.to_string())?;
+    log::info!("Whisper model {} deleted", model);
     Ok(())
 }
 
@@ -800,18 +1318,12 @@ async fn is_whisper_model_loaded(state: State<'_, AppState>) -> Result<bool, Str
     Ok(manager.is_model_loaded())
 }
 
-/// Transcribe an audio file using Whisper
-/// SECURITY (BUG-4 fix): Only allows transcription of files within the recordings directory
-#[tauri::command]
-async fn transcribe_audio<R: Runtime>(
-    app: tauri::AppHandle<R>,
-    state: State<'_, AppState>,
-    wav_path: String,
-) -> Result<TranscriptionResult, String> {
+/// Resolve a path handed in by the frontend, which must lie in the recordings directory
+fn recording_path(wav_path: &str) -> Result<PathBuf, String> {
     // SECURITY (BUG-4 fix): Validate that the file is within the recordings directory
     // This prevents path traversal attacks where an attacker could try to read arbitrary files
     let recordings_dir = AudioRecorder::get_recordings_dir();
-    let path = PathBuf::from(&wav_path);
+    let path = PathBuf::from(wav_path);
 
     // Canonicalize paths to resolve any "../" or symlinks
     let canonical_path = path
@@ -824,22 +1336,128 @@ async fn transcribe_audio<R: Runtime>(
     // Validate path is within recordings directory
     if !canonical_path.starts_with(&canonical_recordings_dir) {
         log::warn!(
-            "Security: Blocked attempt to transcribe file outside recordings dir: {}",
+            "Security: Blocked access to file outside recordings dir: {}",
             wav_path
         );
         return Err("Access denied: File must be in recordings directory".to_string());
     }
+    Ok(canonical_path)
+}
+
+/// Transcribe an audio file using Whisper
+/// SECURITY (BUG-4 fix): Only allows transcription of files within the recordings directory
+#[tauri::command]
+async fn transcribe_audio<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    state: State<'_, AppState>,
+    wav_path: String,
+    speech_segments: Option<Vec<SpeechSegment>>,
+    pause_markers: Option<Vec<PauseMarker>>,
+    system_track: Option<SystemTrack>,
+    trim_offset_ms: Option<u64>,
+) -> Result<TranscriptionResult, String> {
+    let canonical_path = recording_path(&wav_path)?;
+    // A system track without speech adds nothing to the transcript
+    let system_track = system_track.filter(|track| !track.speech_segments.is_empty());
+    if let Some(track) = &system_track {
+        recording_path(&track.file_path)?;
+    }
+
+    // Takes without speech never reach Whisper, whoever asks for them
+    let skip_silent = state
+        .audio_settings
+        .lock()
+        .map_err(|e| e.to_string())?
+        .skip_silent_takes;
+    if skip_silent && system_track.is_none() {
+        let silent = match &speech_segments {
+            Some(segments) => segments.is_empty(),
+            None => audio::detect_speech_in_file(&canonical_path)
+                .map_err(|e| e.to_string())?
+                .is_empty(),
+        };
+        if silent {
+            log::info!("No speech in {}, skipping transcription", wav_path);
+            let mut session = state.streaming_session.lock().map_err(|e| e.to_string())?;
+            if session.as_ref().is_some_and(|live| live.is_for(&wav_path)) {
+                *session = None;
+            }
+            recovery::clear_journal(&canonical_path);
+            return Ok(TranscriptionResult::no_speech());
+        }
+    }
 
     log::info!("Starting transcription of: {}", wav_path);
 
     // Emit transcription started event
     let _ = app.emit("transcription-started", &wav_path);
 
-    let result = {
-        let mut manager = state.whisper_manager.lock().map_err(|e| e.to_string())?;
-        manager.transcribe(&wav_path).map_err(|e| e.to_string())?
+    // A take transcribed while it recorded only has its tail left to decode
+    let session = {
+        let mut session = state.streaming_session.lock().map_err(|e| e.to_string())?;
+        match session.take() {
+            Some(live) if system_track.is_none() && live.is_for(&wav_path) => Some(live),
+            other => {
+                *session = other;
+                None
+            }
+        }
+    };
+    let streamed = session.and_then(|session| match session.finish() {
+        Ok(result) => Some(result),
+        Err(e) => {
+            log::warn!("Live transcription failed, transcribing the file: {}", e);
+            None
+        }
+    });
+
+    let mut result = match streamed {
+        Some(mut result) => {
+            // The live tap ran before the lead-in was trimmed and heard every silence
+            whisper::align_streamed(
+                &mut result,
+                trim_offset_ms.unwrap_or(0),
+                speech_segments.as_deref(),
+            );
+            result
+        }
+        None => {
+            let mut manager = state.whisper_manager.lock().map_err(|e| e.to_string())?;
+            match &system_track {
+                // Each side is transcribed on its own, so the archive can tell
+                // who said what; a silent microphone is not sent to Whisper
+                Some(track) => {
+                    let mut tracks = Vec::new();
+                    if !matches!(&speech_segments, Some(segments) if segments.is_empty()) {
+                        let me = manager
+                            .transcribe(&wav_path, speech_segments.as_deref())
+                            .map_err(|e| e.to_string())?;
+                        tracks.push((Speaker::Me, me));
+                    }
+                    let them = manager
+                        .transcribe(&track.file_path, Some(track.speech_segments.as_slice()))
+                        .map_err(|e| e.to_string())?;
+                    tracks.push((Speaker::Them, them));
+                    whisper::merge_speaker_tracks(tracks)
+                }
+                None => manager
+                    .transcribe(&wav_path, speech_segments.as_deref())
+                    .map_err(|e| e.to_string())?,
+            }
+        }
     };
 
+    // The take is safe now, the cache cleanup may remove it later
+    recovery::clear_journal(&canonical_path);
+
+    // Pauses in the take become paragraph breaks (a dialog already has one per turn)
+    if let Some(markers) = pause_markers.filter(|markers| !markers.is_empty()) {
+        if system_track.is_none() {
+            let breaks: Vec<u64> = markers.iter().map(|marker| marker.at_ms).collect();
+            whisper::apply_paragraph_breaks(&mut result, &breaks);
+        }
+    }
+
     // Emit transcription complete event
     let _ = app.emit("transcription-complete", &result);
 
@@ -1393,8 +2011,13 @@ async fn set_archive_settings(
 async fn archive_transcription<R: Runtime>(
     app: tauri::AppHandle<R>,
     state: State<'_, AppState>,
-    data: TranscriptionData,
+    mut data: TranscriptionData,
 ) -> Result<ArchiveResult, String> {
+    // The audio is read and decrypted into the archive, so only takes may be kept
+    if let Some(audio_path) = data.audio_path.as_deref() {
+        data.audio_path = Some(recording_path(audio_path)?.to_string_lossy().to_string());
+    }
+
     let manager = state.archive_manager.lock().map_err(|e| e.to_string())?;
 
     let result = manager.archive_transcription(&data);
@@ -1405,6 +2028,8 @@ async fn archive_transcription<R: Runtime>(
             let _ = app.emit("archive-success", path);
             log::info!("Transcription archived successfully: {}", path);
         }
+        // Expire old notes together with their audio
+        manager.apply_retention(chrono::Local::now());
     } else if let Some(ref error) = result.error {
         let _ = app.emit("archive-error", error);
         log::warn!("Failed to archive transcription: {}", error);
@@ -1430,6 +2055,147 @@ async fn get_default_archive_path() -> Result<String, String> {
     Ok(default_path.to_string_lossy().to_string())
 }
 
+// ============================================================================
+// Encryption at rest commands
+// ============================================================================
+
+/// Files the encryption manages, see `encryption::protected_files`
+fn encryption_files(state: &AppState) -> Result<Vec<PathBuf>, String> {
+    let archive_path = state
+        .archive_settings
+        .lock()
+        .map_err(|e| e.to_string())?
+        .path
+        .clone();
+    let archive_dir = archive::validate_archive_path(&archive_path).ok();
+    Ok(encryption::protected_files(
+        &AudioRecorder::get_recordings_dir(),
+        archive_dir.as_deref(),
+    ))
+}
+
+/// Load every config file again and apply it, e.g. after unlocking
+fn reload_settings<R: Runtime>(app: &tauri::AppHandle<R>, state: &AppState) -> Result<(), String> {
+    let hotkey_settings = load_hotkey_settings();
+    let previous = {
+        let mut current = state.hotkey_settings.lock().map_err(|e| e.to_string())?;
+        std::mem::replace(&mut *current, hotkey_settings.clone())
+    };
+    for old in std::iter::once(previous.shortcut).chain(previous.pause_shortcut) {
+        if let Ok(old_sc) = old.parse::<Shortcut>() {
+            let _ = app.global_shortcut().unregister(old_sc);
+        }
+    }
+    if hotkey_settings.enabled {
+        register_global_hotkey(app, &hotkey_settings.shortcut)?;
+        if let Some(pause_shortcut) = &hotkey_settings.pause_shortcut {
+            register_pause_hotkey(app, pause_shortcut)?;
+        }
+    }
+
+    let audio_settings = load_audio_settings();
+    {
+        let mut recorder = state.audio_recorder.lock().map_err(|e| e.to_string())?;
+        recorder.update_settings(audio_settings.clone());
+        if let Err(e) = recorder.sync_warm_mic() {
+            log::warn!("Failed to open warm microphone: {}", e);
+        }
+    }
+    *state.audio_settings.lock().map_err(|e| e.to_string())? = audio_settings;
+
+    let whisper_settings = load_whisper_settings();
+    state
+        .whisper_manager
+        .lock()
+        .map_err(|e| e.to_string())?
+        .update_settings(whisper_settings.clone());
+    *state.whisper_settings.lock().map_err(|e| e.to_string())? = whisper_settings;
+
+    let text_insert_settings = text_insert::load_settings();
+    *state
+        .text_insert_settings
+        .lock()
+        .map_err(|e| e.to_string())? = text_insert_settings;
+
+    let ollama_settings = ollama::load_settings();
+    state
+        .ollama_manager
+        .lock()
+        .map_err(|e| e.to_string())?
+        .update_settings(ollama_settings.clone());
+    *state.ollama_settings.lock().map_err(|e| e.to_string())? = ollama_settings;
+
+    state
+        .context_manager
+        .lock()
+        .map_err(|e| e.to_string())?
+        .update_config(context::load_config());
+    *state.email_settings.lock().map_err(|e| e.to_string())? = ollama::load_email_settings();
+    *state.chat_settings.lock().map_err(|e| e.to_string())? = ollama::load_chat_settings();
+
+    let archive_settings = archive::load_settings();
+    state
+        .archive_manager
+        .lock()
+        .map_err(|e| e.to_string())?
+        .update_settings(archive_settings.clone());
+    *state.archive_settings.lock().map_err(|e| e.to_string())? = archive_settings;
+
+    log::info!("Settings reloaded");
+    Ok(())
+}
+
+/// Get whether encryption at rest is enabled and unlocked
+#[tauri::command]
+async fn get_encryption_status() -> Result<EncryptionStatus, String> {
+    Ok(encryption::status())
+}
+
+/// Turn on encryption at rest and seal the existing configs and recordings
+///
+/// Returns the number of files sealed.
+#[tauri::command]
+async fn enable_encryption(
+    state: State<'_, AppState>,
+    key_source: KeySource,
+    passphrase: Option<String>,
+) -> Result<usize, String> {
+    let files = encryption_files(&state)?;
+    encryption::enable(key_source, passphrase.as_deref(), &files).map_err(|e| e.to_string())
+}
+
+/// Decrypt everything and turn encryption at rest off
+#[tauri::command]
+async fn disable_encryption(state: State<'_, AppState>) -> Result<usize, String> {
+    let files = encryption_files(&state)?;
+    encryption::disable(&files).map_err(|e| e.to_string())
+}
+
+/// Unlock with the passphrase and load the settings that were sealed
+#[tauri::command]
+async fn unlock_encryption<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    state: State<'_, AppState>,
+    passphrase: String,
+) -> Result<(), String> {
+    encryption::unlock(&passphrase).map_err(|e| e.to_string())?;
+    reload_settings(&app, &state)?;
+    let _ = app.emit("encryption-unlocked", ());
+    Ok(())
+}
+
+/// Reseal every file with a new key, optionally with a new passphrase
+///
+/// Returns the number of files resealed.
+#[tauri::command]
+async fn rotate_encryption_key(
+    state: State<'_, AppState>,
+    new_passphrase: Option<String>,
+) -> Result<usize, String> {
+    let files = encryption_files(&state)?;
+    encryption::rotate(new_passphrase.as_deref(), &files).map_err(|e| e.to_string())
+}
+
 /// Register a global hotkey
 fn register_global_hotkey<R: Runtime>(
     app: &tauri::AppHandle<R>,
@@ -1586,19 +2352,66 @@ fn register_global_hotkey<R: Runtime>(
     Ok(())
 }
 
+/// Register the optional hotkey that pauses and resumes the running take
+fn register_pause_hotkey<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    shortcut_str: &str,
+) -> Result<(), String> {
+    let shortcut: Shortcut = shortcut_str.parse().map_err(|e| format!("{:?}", e))?;
+
+    app.global_shortcut()
+        .on_shortcut(shortcut, |app, _sc, event| {
+            if !matches!(event.state, ShortcutState::Pressed) {
+                return;
+            }
+            let state: State<'_, AppState> = app.state();
+            let paused = match state.audio_recorder.lock() {
+                Ok(recorder) if recorder.is_recording() => recorder.is_paused(),
+                _ => return,
+            };
+            if let Err(e) = set_recording_paused(app, &state, !paused) {
+                log::warn!("Failed to toggle pause: {}", e);
+            }
+        })
+        .map_err(|e| e.to_string())?;
+
+    log::info!("Pause hotkey registered: {}", shortcut_str);
+    Ok(())
+}
+
 // ============================================================================
 // Tray Menu Functions
 // ============================================================================
 
 /// Create the tray context menu
-fn create_tray_menu<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<Menu<R>> {
+fn create_tray_menu<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    warm_mic: bool,
+) -> tauri::Result<Menu<R>> {
     let status_item = MenuItem::with_id(app, "status", "Status: Bereit", false, None::<&str>)?;
     let separator = MenuItem::with_id(app, "sep", "─────────────", false, None::<&str>)?;
+    let warm_mic_item = CheckMenuItem::with_id(
+        app,
+        "warm_mic",
+        "Mikrofon vorhalten (Pre-Roll)",
+        true,
+        warm_mic,
+        None::<&str>,
+    )?;
     let settings_item =
         MenuItem::with_id(app, "settings", "Einstellungen öffnen", true, None::<&str>)?;
     let quit_item = MenuItem::with_id(app, "quit", "App beenden", true, None::<&str>)?;
 
-    Menu::with_items(app, &[&status_item, &separator, &settings_item, &quit_item])
+    Menu::with_items(
+        app,
+        &[
+            &status_item,
+            &separator,
+            &warm_mic_item,
+            &settings_item,
+            &quit_item,
+        ],
+    )
 }
 
 /// Get the icon bytes for a given status
@@ -1621,12 +2434,13 @@ fn build_tray_icon<R: Runtime>(app: &tauri::AppHandle<R>, status: AppStatus) ->
     }
 
     // Create tray menu
-    let menu = create_tray_menu(app).map_err(|e| e.to_string())?;
+    let warm_mic = is_warm_mic_active(app);
+    let menu = create_tray_menu(app, warm_mic).map_err(|e| e.to_string())?;
 
     // Load icon for current status
     let icon_bytes = get_status_icon_bytes(status);
     let icon = Image::from_bytes(icon_bytes).map_err(|e| e.to_string())?;
-    let tooltip = get_status_tooltip(status);
+    let tooltip = get_tray_tooltip(status, warm_mic);
 
     // Build new tray icon
     let _tray = TrayIconBuilder::with_id("main")
@@ -1642,6 +2456,7 @@ fn build_tray_icon<R: Runtime>(app: &tauri::AppHandle<R>, status: AppStatus) ->
                     let _ = window.center();
                 }
             }
+            "warm_mic" => toggle_warm_mic(app),
             "quit" => {
                 app.exit(0);
             }
@@ -1692,7 +2507,7 @@ async fn update_tray_status<R: Runtime>(
     // Update existing tray
     if let Some(tray) = app.tray_by_id("main") {
         // Update tooltip
-        let tooltip = get_status_tooltip(status);
+        let tooltip = get_tray_tooltip(status, is_warm_mic_active(&app));
         tray.set_tooltip(Some(tooltip)).map_err(|e| e.to_string())?;
 
         // Update icon
@@ -1795,7 +2610,7 @@ fn get_privacy_config_path() -> PathBuf {
 fn load_general_settings() -> GeneralSettings {
     let config_path = get_general_config_path();
     if config_path.exists() {
-        if let Ok(content) = fs::read_to_string(&config_path) {
+        if let Ok(content) = encryption::read_to_string(&config_path) {
             if let Ok(settings) = serde_json::from_str(&content) {
                 return settings;
             }
@@ -1810,14 +2625,14 @@ fn load_general_settings() -> GeneralSettings {
 fn save_general_settings(settings: &GeneralSettings) -> Result<(), String> {
     let config_path = get_general_config_path();
     let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
-    fs::write(&config_path, json).map_err(|e| e.to_string())
+    encryption::write(&config_path, json).map_err(|e| e.to_string())
 }
 
 /// Load privacy settings from config file
 fn load_privacy_settings() -> PrivacySettings {
     let config_path = get_privacy_config_path();
     if config_path.exists() {
-        if let Ok(content) = fs::read_to_string(&config_path) {
+        if let Ok(content) = encryption::read_to_string(&config_path) {
             if let Ok(settings) = serde_json::from_str(&content) {
                 return settings;
             }
@@ -1830,7 +2645,7 @@ fn load_privacy_settings() -> PrivacySettings {
 fn save_privacy_settings(settings: &PrivacySettings) -> Result<(), String> {
     let config_path = get_privacy_config_path();
     let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
-    fs::write(&config_path, json).map_err(|e| e.to_string())
+    encryption::write(&config_path, json).map_err(|e| e.to_string())
 }
 
 /// Get current general settings
@@ -2070,14 +2885,44 @@ pub fn run() {
     let previous_crash = check_crash_marker();
     let had_crash = previous_crash.is_some();
 
+    // Load the encryption keys first, the configs below may be sealed
+    encryption::init();
+
     // Load hotkey settings
     let hotkey_settings = load_hotkey_settings();
 
     // Load audio settings
-    let audio_settings = load_audio_settings();
+    let mut audio_settings = load_audio_settings();
     let mut audio_recorder = AudioRecorder::new();
     audio_recorder.update_settings(audio_settings.clone());
 
+    // Replay a WAV file instead of the microphone (debugging, bug reports)
+    if let Some(path) = std::env::var_os(audio_source::REPLAY_ENV_VAR) {
+        log::warn!("Replaying {:?} instead of the microphone", path);
+        audio_recorder.set_source(Some(Box::new(FileSource::new(path, Pace::RealTime))));
+    }
+
+    // Rewrite name-only microphone ids from older versions to stable identities
+    if audio_recorder.migrate_device_ids() {
+        audio_settings = audio_recorder.get_settings().clone();
+        if let Err(e) = save_audio_settings(&audio_settings) {
+            log::warn!("Failed to save migrated audio settings: {}", e);
+        }
+    }
+
+    // Recordings the previous session never processed are kept for recovery,
+    // the cleanup only removes the rest
+    let orphaned_recordings = recovery::find_orphans(
+        &AudioRecorder::get_recordings_dir(),
+        previous_crash.as_ref().and_then(crash_time),
+    );
+    if !orphaned_recordings.is_empty() {
+        log::warn!(
+            "{} unprocessed recordings from the previous session",
+            orphaned_recordings.len()
+        );
+    }
+
     // Clean up old recordings on startup
     let _ = AudioRecorder::cleanup_old_recordings();
 
@@ -2107,12 +2952,14 @@ pub fn run() {
     // Load archive settings (PROJ-18)
     let archive_settings = archive::load_settings();
     let archive_manager = ArchiveManager::with_settings(archive_settings.clone());
+    archive_manager.apply_retention(chrono::Local::now());
 
     // Create initial state with crash info, hotkey settings, audio, whisper, ollama, text insert, context, email, chat, and archive
     let initial_state = AppState {
         current_status: Mutex::new(AppStatus::Idle),
         had_previous_crash: Mutex::new(had_crash),
         crash_info: Mutex::new(previous_crash.clone()),
+        orphaned_recordings: Mutex::new(orphaned_recordings),
         hotkey_settings: Mutex::new(hotkey_settings.clone()),
         hotkey_press_time: Mutex::new(None),
         is_recording: Mutex::new(false),
@@ -2120,6 +2967,7 @@ pub fn run() {
         audio_settings: Mutex::new(audio_settings),
         whisper_manager: Mutex::new(whisper_manager),
         whisper_settings: Mutex::new(whisper_settings),
+        streaming_session: Mutex::new(None),
         text_insert_settings: Mutex::new(text_insert_settings),
         ollama_manager: Mutex::new(ollama_manager),
         ollama_settings: Mutex::new(ollama_settings),
@@ -2168,6 +3016,21 @@ pub fn run() {
                     .build(),
             )?;
 
+            // Let the audio recorder push events (limit warnings, auto-stop) to the frontend
+            {
+                let app_handle = app.handle().clone();
+                let state: State<'_, AppState> = app.state();
+                if let Ok(mut recorder) = state.audio_recorder.lock() {
+                    recorder.set_event_handler(Arc::new(move |event| {
+                        emit_recorder_event(&app_handle, event)
+                    }));
+                    // Open the warm microphone if the user opted in
+                    if let Err(e) = recorder.sync_warm_mic() {
+                        log::warn!("Failed to open warm microphone: {}", e);
+                    }
+                };
+            }
+
             // Build initial tray icon using the shared function
             build_tray_icon(app.handle(), AppStatus::Idle)
                 .map_err(|e| format!("Failed to create tray icon: {}", e))?;
@@ -2178,6 +3041,11 @@ pub fn run() {
                     log::warn!("Failed to register global hotkey: {}", e);
                     // Don't fail startup, just warn - user can re-configure later
                 }
+                if let Some(pause_shortcut) = &hotkey_settings.pause_shortcut {
+                    if let Err(e) = register_pause_hotkey(app.handle(), pause_shortcut) {
+                        log::warn!("Failed to register pause hotkey: {}", e);
+                    }
+                }
             }
 
             log::info!("EverVoice Desktop App started successfully");
@@ -2195,6 +3063,8 @@ pub fn run() {
             clear_crash_notification,
             get_crash_log,
             clear_crash_log,
+            get_orphaned_recordings,
+            resolve_orphaned_recording,
             get_hotkey_settings,
             set_hotkey_settings,
             check_shortcut_available,
@@ -2203,20 +3073,29 @@ pub fn run() {
             request_accessibility_permission,
             // Audio commands (PROJ-3)
             list_audio_devices,
+            list_system_audio_sources,
             get_audio_settings,
             set_audio_settings,
             start_audio_recording,
             stop_audio_recording,
-            get_audio_level,
             is_audio_recording,
+            pause_audio_recording,
+            resume_audio_recording,
+            is_audio_recording_paused,
             check_audio_health,
             delete_recording,
+            mark_recording_processed,
             request_microphone_permission,
+            get_warm_mic_state,
+            import_audio_file,
+            get_import_extensions,
             // Whisper commands (PROJ-4)
             get_whisper_settings,
             set_whisper_settings,
+            get_whisper_languages,
             get_whisper_model_status,
             download_whisper_model,
+            import_whisper_models,
             get_whisper_download_progress,
             cancel_whisper_download,
             delete_whisper_model,
@@ -2261,7 +3140,13 @@ pub fn run() {
             set_archive_settings,
             archive_transcription,
             check_archive_path,
-            get_default_archive_path
+            get_default_archive_path,
+            // Encryption at rest commands
+            get_encryption_status,
+            enable_encryption,
+            disable_encryption,
+            unlock_encryption,
+            rotate_encryption_key
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,513 @@
+//! Import of Whisper models from local files
+//!
+//! For machines that cannot reach Hugging Face: `ggml-*.bin` files copied
+//! from a share or a USB stick are checked, copied into the models directory
+//! and registered in the user manifest. A file whose checksum matches a
+//! manifest entry becomes that model. Anything else is described from its
+//! ggml header and added as a custom model.
+
+use crate::model_registry::{Checksum, FileHashes, ModelEntry, ModelRegistry};
+use crate::whisper::WhisperManager;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// "ggml" as whisper.cpp writes it, a little-endian u32 at the file start
+const GGML_MAGIC: u32 = 0x6767_6d6c;
+
+/// whisper.cpp keeps the quantization version in the thousands of `ftype`
+const GGML_QNT_VERSION_FACTOR: i32 = 1000;
+
+/// Vocabulary size of the English-only models
+const ENGLISH_ONLY_VOCAB: i32 = 51864;
+
+/// Errors that can occur while importing a model file
+#[derive(Debug, thiserror::Error)]
+pub enum ModelImportError {
+    #[error("File not found: {0}")]
+    FileNotFound(String),
+
+    #[error("Not a ggml Whisper model: {0}")]
+    NotAModel(String),
+
+    #[error("No ggml-*.bin files in {0}")]
+    NoModels(String),
+
+    #[error("The copy of {0} does not match the original")]
+    CopyMismatch(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+}
+
+impl serde::Serialize for ModelImportError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Hyperparameters at the start of a ggml Whisper file
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GgmlHeader {
+    pub n_vocab: i32,
+    pub n_audio_layer: i32,
+    pub n_text_layer: i32,
+    pub n_mels: i32,
+    pub ftype: i32,
+}
+
+impl GgmlHeader {
+    /// Read the header, `None` if the data is not a ggml Whisper model
+    pub fn read(reader: &mut impl Read) -> io::Result<Option<Self>> {
+        // magic, then n_vocab, n_audio_ctx, n_audio_state, n_audio_head,
+        // n_audio_layer, n_text_ctx, n_text_state, n_text_head,
+        // n_text_layer, n_mels and ftype
+        let mut bytes = [0u8; 48];
+        match reader.read_exact(&mut bytes) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let field = |index: usize| {
+            let start = index * 4;
+            i32::from_le_bytes([
+                bytes[start],
+                bytes[start + 1],
+                bytes[start + 2],
+                bytes[start + 3],
+            ])
+        };
+        if field(0) as u32 != GGML_MAGIC {
+            return Ok(None);
+        }
+
+        let header = Self {
+            n_vocab: field(1),
+            n_audio_layer: field(5),
+            n_text_layer: field(9),
+            n_mels: field(10),
+            ftype: field(11),
+        };
+        // Rules out other ggml files (LLMs) that happen to share the magic
+        let plausible = header.n_vocab > 50_000
+            && matches!(header.n_mels, 80 | 128)
+            && (1..=64).contains(&header.n_audio_layer)
+            && (1..=64).contains(&header.n_text_layer);
+        Ok(plausible.then_some(header))
+    }
+
+    /// Model size as whisper.cpp names it, from the layer counts
+    pub fn model_type(&self) -> Option<&'static str> {
+        match (self.n_audio_layer, self.n_text_layer, self.n_mels) {
+            (4, _, _) => Some("tiny"),
+            (6, _, _) => Some("base"),
+            (12, _, _) => Some("small"),
+            (24, _, _) => Some("medium"),
+            (32, 4, _) => Some("large-v3-turbo"),
+            (32, _, 128) => Some("large-v3"),
+            (32, _, _) => Some("large"),
+            _ => None,
+        }
+    }
+
+    /// English-only (`.en`) models have a smaller vocabulary
+    pub fn is_english_only(&self) -> bool {
+        self.n_vocab == ENGLISH_ONLY_VOCAB
+    }
+
+    /// Quantization of the weights, `None` for full precision (f32/f16)
+    pub fn quantization(&self) -> Option<String> {
+        let name = match self.ftype % GGML_QNT_VERSION_FACTOR {
+            0 | 1 => return None,
+            2 => "q4_0",
+            3 | 4 => "q4_1",
+            7 => "q8_0",
+            8 => "q5_0",
+            9 => "q5_1",
+            10 => "q2_k",
+            11 => "q3_k",
+            12 => "q4_k",
+            13 => "q5_k",
+            14 => "q6_k",
+            other => return Some(format!("ftype{}", other)),
+        };
+        Some(name.to_string())
+    }
+
+    /// RAM recommended for the model, in GB
+    fn min_ram_gb(&self) -> u8 {
+        let full = match self.model_type() {
+            Some("tiny") | Some("base") => 1,
+            Some("small") => 2,
+            Some("medium") | Some("large-v3-turbo") => 4,
+            _ => 8,
+        };
+        if self.quantization().is_some() {
+            (full / 2).max(1)
+        } else {
+            full
+        }
+    }
+}
+
+/// A model file that was copied into the models directory
+struct ImportedModel {
+    entry: ModelEntry,
+    /// Not in the manifest yet, has to be registered
+    is_new: bool,
+    /// The copy in the models directory, None if the file was already there
+    copy: Option<PathBuf>,
+}
+
+/// Import one model file or every `ggml-*.bin` in a directory
+///
+/// Files of a directory that are no Whisper model are skipped. New custom
+/// models are added to `registry` and the user manifest.
+pub fn import_models(
+    path: &Path,
+    registry: &mut ModelRegistry,
+) -> Result<Vec<ModelEntry>, ModelImportError> {
+    import_models_into(
+        path,
+        &WhisperManager::get_models_dir(),
+        &ModelRegistry::user_manifest_path(),
+        registry,
+    )
+}
+
+/// `import_models` with the models directory and manifest to use
+///
+/// All files are copied before any is registered. If the manifest cannot be
+/// written, the copies of the new models are removed again.
+fn import_models_into(
+    path: &Path,
+    models_dir: &Path,
+    manifest_path: &Path,
+    registry: &mut ModelRegistry,
+) -> Result<Vec<ModelEntry>, ModelImportError> {
+    if !path.exists() {
+        return Err(ModelImportError::FileNotFound(path.display().to_string()));
+    }
+
+    let imported = if path.is_dir() {
+        let mut files: Vec<PathBuf> = fs::read_dir(path)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|file| file.is_file() && is_model_filename(file))
+            .collect();
+        files.sort();
+
+        // Later files must see the earlier ones, e.g. two copies of one fine-tune
+        let mut staged = registry.clone();
+        let mut imported = Vec::new();
+        let mut first_error = None;
+        for file in &files {
+            match import_file(file, models_dir, &staged) {
+                Ok(model) => {
+                    staged.insert(model.entry.clone());
+                    imported.push(model);
+                }
+                Err(e) => {
+                    log::warn!("Skipping {:?}: {}", file, e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match (imported.is_empty(), first_error) {
+            (true, Some(e)) => return Err(e),
+            (true, None) => return Err(ModelImportError::NoModels(path.display().to_string())),
+            _ => imported,
+        }
+    } else {
+        vec![import_file(path, models_dir, registry)?]
+    };
+
+    let new: Vec<ModelEntry> = imported
+        .iter()
+        .filter(|model| model.is_new)
+        .map(|model| model.entry.clone())
+        .collect();
+    if !new.is_empty() {
+        if let Err(e) = registry.register_in(manifest_path, &new) {
+            // A model file the app does not know about would only waste space
+            for model in imported.iter().filter(|model| model.is_new) {
+                if let Some(copy) = &model.copy {
+                    let _ = fs::remove_file(copy);
+                }
+            }
+            return Err(e.into());
+        }
+    }
+
+    Ok(imported
+        .into_iter()
+        .map(|model| {
+            log::info!("Imported Whisper model {}", model.entry.id);
+            model.entry
+        })
+        .collect())
+}
+
+fn is_model_filename(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.starts_with("ggml-") && name.ends_with(".bin"))
+        .unwrap_or(false)
+}
+
+/// Check a file and copy it into `models_dir`
+fn import_file(
+    source: &Path,
+    models_dir: &Path,
+    registry: &ModelRegistry,
+) -> Result<ImportedModel, ModelImportError> {
+    let file_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let header = GgmlHeader::read(&mut fs::File::open(source)?)?
+        .ok_or_else(|| ModelImportError::NotAModel(file_name.clone()))?;
+    let size = fs::metadata(source)?.len();
+    let hashes = FileHashes::of_file(source)?;
+
+    // A known file keeps its manifest entry, e.g. a copied ggml-small.bin
+    let known = registry
+        .models()
+        .iter()
+        .find(|model| Checksum::of(model).is_some_and(|checksum| checksum.matches(&hashes)))
+        .cloned();
+    let is_new = known.is_none();
+    let entry = known.unwrap_or_else(|| custom_entry(&file_name, &header, size, &hashes, registry));
+
+    let target = models_dir.join(&entry.filename);
+    let same_file = match (source.canonicalize(), target.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    };
+    if same_file {
+        return Ok(ImportedModel {
+            entry,
+            is_new,
+            copy: None,
+        });
+    }
+
+    // Copy next to the target first, a half-written model must never load
+    let temp = target.with_extension("importing");
+    fs::copy(source, &temp)?;
+    if FileHashes::of_file(&temp)? != hashes {
+        let _ = fs::remove_file(&temp);
+        return Err(ModelImportError::CopyMismatch(file_name));
+    }
+    fs::rename(&temp, &target)?;
+
+    Ok(ImportedModel {
+        entry,
+        is_new,
+        copy: Some(target),
+    })
+}
+
+/// Manifest entry for a file the manifest does not know
+fn custom_entry(
+    file_name: &str,
+    header: &GgmlHeader,
+    size: u64,
+    hashes: &FileHashes,
+    registry: &ModelRegistry,
+) -> ModelEntry {
+    let stem = file_name
+        .trim_start_matches("ggml-")
+        .trim_end_matches(".bin")
+        .to_lowercase()
+        .replace(
+            |c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_')),
+            "-",
+        );
+    let stem = if stem.is_empty() {
+        "custom".to_string()
+    } else {
+        stem
+    };
+
+    // Never shadow a manifest model, e.g. a fine-tune saved as ggml-small.bin
+    let mut id = stem.clone();
+    let mut suffix = 1;
+    while registry.get(&id).is_some() {
+        id = match suffix {
+            1 => format!("{}-custom", stem),
+            n => format!("{}-custom-{}", stem, n),
+        };
+        suffix += 1;
+    }
+
+    let quantization = header.quantization();
+    let mut description = vec![format!(
+        "Importiert, Whisper {}",
+        header.model_type().unwrap_or("unbekannter Größe")
+    )];
+    if let Some(quantization) = &quantization {
+        description.push(quantization.clone());
+    }
+    if header.is_english_only() {
+        description.push("nur Englisch".to_string());
+    }
+
+    ModelEntry {
+        display_name: format!("{} (importiert)", stem),
+        description: description.join(", "),
+        filename: format!("ggml-{}.bin", id),
+        id,
+        url: None,
+        size_mb: size.div_ceil(1024 * 1024).max(1),
+        min_ram_gb: header.min_ram_gb(),
+        languages: if header.is_english_only() {
+            vec!["en".to_string()]
+        } else {
+            Vec::new()
+        },
+        quantization,
+        sha256: Some(hashes.sha256.clone()),
+        sha1: Some(hashes.sha1.clone()),
+        custom: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{ggml_model, TempPath};
+
+    #[test]
+    fn test_header_describes_the_model() {
+        let turbo = GgmlHeader::read(&mut &ggml_model(51866, 32, 4, 128, 1008)[..])
+            .unwrap()
+            .unwrap();
+        assert_eq!(turbo.model_type(), Some("large-v3-turbo"));
+        assert_eq!(turbo.quantization().as_deref(), Some("q5_0"));
+        assert!(!turbo.is_english_only());
+
+        let tiny_en = GgmlHeader::read(&mut &ggml_model(51864, 4, 4, 80, 1)[..])
+            .unwrap()
+            .unwrap();
+        assert_eq!(tiny_en.model_type(), Some("tiny"));
+        assert_eq!(tiny_en.quantization(), None);
+        assert!(tiny_en.is_english_only());
+
+        // Wrong magic, too short, or an LLM in ggml format
+        let mut other = ggml_model(51865, 12, 12, 80, 1);
+        other[0] = b'x';
+        assert_eq!(GgmlHeader::read(&mut &other[..]).unwrap(), None);
+        assert_eq!(GgmlHeader::read(&mut &b"lmgg"[..]).unwrap(), None);
+        let llm = ggml_model(32000, 32, 32, 4096, 1);
+        assert_eq!(GgmlHeader::read(&mut &llm[..]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_unknown_file_becomes_custom_model() {
+        let dir = TempPath::dir("import");
+        let models_dir = dir.join("models");
+        fs::create_dir_all(&models_dir).unwrap();
+
+        // Named like a bundled model, but with other contents
+        let source = dir.join("ggml-small.bin");
+        fs::write(&source, ggml_model(51865, 12, 12, 80, 1007)).unwrap();
+
+        let registry = ModelRegistry::bundled();
+        let imported = import_file(&source, &models_dir, &registry).unwrap();
+        assert!(imported.is_new);
+        let entry = imported.entry;
+        assert_eq!(entry.id, "small-custom");
+        assert_eq!(entry.filename, "ggml-small-custom.bin");
+        assert_eq!(entry.quantization.as_deref(), Some("q8_0"));
+        assert!(entry.custom && entry.languages.is_empty());
+        assert_eq!(
+            fs::read(models_dir.join(&entry.filename)).unwrap(),
+            fs::read(&source).unwrap()
+        );
+
+        // Importing it again finds the registered entry by its checksum
+        let mut registry = registry;
+        registry.insert(entry.clone());
+        let again = import_file(&source, &models_dir, &registry).unwrap();
+        assert!(!again.is_new);
+        assert_eq!(again.entry, entry);
+
+        let text = dir.join("ggml-notes.bin");
+        fs::write(&text, "not a model").unwrap();
+        assert!(matches!(
+            import_file(&text, &models_dir, &registry),
+            Err(ModelImportError::NotAModel(_))
+        ));
+    }
+
+    #[test]
+    fn test_bundled_model_is_recognized_by_its_sha256() {
+        let dir = TempPath::dir("import");
+        let models_dir = dir.join("models");
+        fs::create_dir_all(&models_dir).unwrap();
+
+        // Renamed on the way, only the checksum can tell it is the bundled model
+        let source = dir.join("ggml-from-usb-stick.bin");
+        fs::write(&source, ggml_model(51865, 12, 12, 80, 1)).unwrap();
+        let mut registry = ModelRegistry::bundled();
+        let mut small = registry.get("small").unwrap().clone();
+        small.sha256 = Some(FileHashes::of_file(&source).unwrap().sha256);
+        small.sha1 = None;
+        registry.insert(small.clone());
+        let bundled_count = registry.models().len();
+
+        let manifest = dir.join("models.json");
+        let imported = import_models_into(&source, &models_dir, &manifest, &mut registry).unwrap();
+        assert_eq!(imported, vec![small]);
+        assert!(!imported[0].custom);
+        assert_eq!(registry.models().len(), bundled_count);
+        assert!(models_dir.join("ggml-small.bin").exists());
+        // Nothing new to register
+        assert!(!manifest.exists());
+    }
+
+    #[test]
+    fn test_directory_import_registers_all_models_or_none() {
+        let dir = TempPath::dir("import");
+        let source = dir.join("share");
+        let models_dir = dir.join("models");
+        fs::create_dir_all(&source).unwrap();
+        fs::create_dir_all(&models_dir).unwrap();
+        fs::write(
+            source.join("ggml-de-finetune.bin"),
+            ggml_model(51865, 12, 12, 80, 1),
+        )
+        .unwrap();
+        fs::write(
+            source.join("ggml-en-finetune.bin"),
+            ggml_model(51864, 4, 4, 80, 1),
+        )
+        .unwrap();
+
+        // The manifest cannot be written: no copy may stay behind
+        let blocked = dir.join("blocked");
+        fs::create_dir_all(&blocked).unwrap();
+        let mut registry = ModelRegistry::bundled();
+        let bundled_count = registry.models().len();
+        assert!(import_models_into(&source, &models_dir, &blocked, &mut registry).is_err());
+        assert_eq!(registry.models().len(), bundled_count);
+        assert_eq!(fs::read_dir(&models_dir).unwrap().count(), 0);
+
+        let manifest = dir.join("models.json");
+        let imported = import_models_into(&source, &models_dir, &manifest, &mut registry).unwrap();
+        let ids: Vec<&str> = imported.iter().map(|entry| entry.id.as_str()).collect();
+        assert_eq!(ids, vec!["de-finetune", "en-finetune"]);
+        assert_eq!(registry.models().len(), bundled_count + 2);
+        let mut reloaded = ModelRegistry::bundled();
+        reloaded
+            .extend_from_json(&fs::read_to_string(&manifest).unwrap())
+            .unwrap();
+        assert!(reloaded
+            .get("en-finetune")
+            .is_some_and(|entry| entry.custom));
+        assert_eq!(fs::read_dir(&models_dir).unwrap().count(), 2);
+    }
+}
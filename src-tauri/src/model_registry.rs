@@ -0,0 +1,442 @@
+//! Registry of the Whisper models the app can download and load
+//!
+//! The models come from a manifest bundled with the app (`models.json`). A
+//! `models.json` in the models directory adds further models or replaces
+//! bundled ones with the same id, e.g. to point them at an internal server.
+//!
+//! Every bundled entry carries the SHA-256 Hugging Face publishes for its file
+//! (the LFS object id); `scripts/update-model-checksums.sh` fills them in. A
+//! user manifest may add `sha256` or `sha1` for its own models.
+
+use crate::whisper::WhisperManager;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Manifest shipped with the app
+const BUNDLED_MANIFEST: &str = include_str!("../models.json");
+
+/// Name of the user manifest in the models directory
+pub const MANIFEST_FILENAME: &str = "models.json";
+
+/// Model used until the user picks another one
+pub const DEFAULT_MODEL: &str = "small";
+
+/// A Whisper model from the manifest
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ModelEntry {
+    /// Stable identifier stored in the settings, e.g. "large-v3-turbo-q5_0"
+    pub id: String,
+    /// Name shown in the model list
+    pub display_name: String,
+    /// Short description shown below the model list
+    #[serde(default)]
+    pub description: String,
+    /// File name in the models directory
+    pub filename: String,
+    /// Download URL; without one the file is fetched from the manifest's base URL
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Approximate file size in MiB
+    pub size_mb: u64,
+    /// RAM recommended to run the model, in GB
+    pub min_ram_gb: u8,
+    /// ISO codes of the languages the model transcribes, empty for all of Whisper's
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// Quantization of the weights ("q5_0", "q8_0"), `None` for full precision
+    #[serde(default)]
+    pub quantization: Option<String>,
+    /// SHA-256 of the model file
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// SHA-1 of the model file, as whisper.cpp publishes it
+    #[serde(default)]
+    pub sha1: Option<String>,
+    /// Imported from a local file, there is nothing to download
+    #[serde(default)]
+    pub custom: bool,
+}
+
+impl ModelEntry {
+    /// Approximate file size in bytes
+    pub fn size_bytes(&self) -> u64 {
+        self.size_mb * 1024 * 1024
+    }
+
+    /// Whether the model transcribes the given language
+    pub fn covers(&self, language: &str) -> bool {
+        self.languages.is_empty() || self.languages.iter().any(|code| code == language)
+    }
+}
+
+/// Checksums of a file, computed in one pass
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileHashes {
+    pub sha256: String,
+    pub sha1: String,
+}
+
+impl FileHashes {
+    /// Hash a file, read in chunks so large models need little memory
+    pub fn of_file(path: &Path) -> io::Result<Self> {
+        let mut file = fs::File::open(path)?;
+        let mut sha256 = Sha256::new();
+        let mut sha1 = Sha1::new();
+        let mut buffer = vec![0u8; 1 << 20];
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            sha256.update(&buffer[..read]);
+            sha1.update(&buffer[..read]);
+        }
+        let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        Ok(Self {
+            sha256: hex(&sha256.finalize()),
+            sha1: hex(&sha1.finalize()),
+        })
+    }
+}
+
+/// Checksum from the manifest a model file has to match
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Checksum<'a> {
+    Sha256(&'a str),
+    Sha1(&'a str),
+}
+
+impl<'a> Checksum<'a> {
+    /// The strongest checksum the manifest has for a model
+    pub fn of(model: &'a ModelEntry) -> Option<Self> {
+        model
+            .sha256
+            .as_deref()
+            .map(Checksum::Sha256)
+            .or_else(|| model.sha1.as_deref().map(Checksum::Sha1))
+    }
+
+    /// Compare with the hashes of a file
+    pub fn matches(&self, hashes: &FileHashes) -> bool {
+        let (expected, actual) = match *self {
+            Checksum::Sha256(expected) => (expected, &hashes.sha256),
+            Checksum::Sha1(expected) => (expected, &hashes.sha1),
+        };
+        actual.eq_ignore_ascii_case(expected.trim())
+    }
+
+    /// Hash the file and compare
+    pub fn matches_file(&self, path: &Path) -> io::Result<bool> {
+        Ok(self.matches(&FileHashes::of_file(path)?))
+    }
+}
+
+/// Layout of a manifest file
+#[derive(serde::Deserialize)]
+struct Manifest {
+    /// Prefix for the download URL of models without their own `url`
+    #[serde(default)]
+    base_url: Option<String>,
+    #[serde(default)]
+    models: Vec<ModelEntry>,
+}
+
+/// All models known to the app
+#[derive(Clone, Debug)]
+pub struct ModelRegistry {
+    base_url: String,
+    models: Vec<ModelEntry>,
+}
+
+impl ModelRegistry {
+    /// Bundled manifest, extended by the user manifest if there is one
+    pub fn load() -> Self {
+        let mut registry = Self::bundled();
+        let path = Self::user_manifest_path();
+        if path.exists() {
+            let extended = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|json| registry.extend_from_json(&json).map_err(|e| e.to_string()));
+            if let Err(e) = extended {
+                log::warn!("Ignoring model manifest {:?}: {}", path, e);
+            }
+        }
+        registry
+    }
+
+    /// Models shipped with the app
+    pub fn bundled() -> Self {
+        let manifest: Manifest =
+            serde_json::from_str(BUNDLED_MANIFEST).expect("bundled model manifest is valid");
+        Self {
+            base_url: manifest.base_url.unwrap_or_default(),
+            models: manifest.models,
+        }
+    }
+
+    /// Path of the manifest users can put next to their models
+    pub fn user_manifest_path() -> PathBuf {
+        WhisperManager::get_models_dir().join(MANIFEST_FILENAME)
+    }
+
+    /// Add the models of another manifest, replacing those with the same id
+    pub fn extend_from_json(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        let manifest: Manifest = serde_json::from_str(json)?;
+        if let Some(base_url) = manifest.base_url {
+            self.base_url = base_url;
+        }
+        for entry in manifest.models {
+            self.insert(entry);
+        }
+        Ok(())
+    }
+
+    /// Add a model, replacing one with the same id
+    pub fn insert(&mut self, entry: ModelEntry) {
+        match self.models.iter_mut().find(|m| m.id == entry.id) {
+            Some(existing) => *existing = entry,
+            None => self.models.push(entry),
+        }
+    }
+
+    /// Add models and keep them in the manifest at `manifest_path`
+    ///
+    /// The manifest is written once for all of them, so either every entry
+    /// is registered or none.
+    pub fn register_in(&mut self, manifest_path: &Path, entries: &[ModelEntry]) -> io::Result<()> {
+        let invalid = |e: serde_json::Error| io::Error::new(io::ErrorKind::InvalidData, e);
+
+        // Edit the file as JSON, so settings the user wrote there survive
+        let mut manifest = match fs::read_to_string(manifest_path) {
+            Ok(json) => serde_json::from_str(&json).map_err(invalid)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => serde_json::json!({}),
+            Err(e) => return Err(e),
+        };
+        let models = manifest
+            .as_object_mut()
+            .map(|object| {
+                object
+                    .entry("models")
+                    .or_insert_with(|| serde_json::json!([]))
+            })
+            .and_then(|models| models.as_array_mut())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "model manifest has no model list",
+                )
+            })?;
+        for entry in entries {
+            let id = Some(entry.id.as_str());
+            models.retain(|model| model.get("id").and_then(|id| id.as_str()) != id);
+            models.push(serde_json::to_value(entry).map_err(invalid)?);
+        }
+
+        let json = serde_json::to_string_pretty(&manifest).map_err(invalid)?;
+        fs::write(manifest_path, json)?;
+        for entry in entries {
+            self.insert(entry.clone());
+        }
+        Ok(())
+    }
+
+    /// Look up a model by id
+    pub fn get(&self, id: &str) -> Option<&ModelEntry> {
+        self.models.iter().find(|m| m.id == id)
+    }
+
+    /// All models, bundled ones first
+    pub fn models(&self) -> &[ModelEntry] {
+        &self.models
+    }
+
+    /// Where to download a model from
+    pub fn download_url(&self, entry: &ModelEntry) -> String {
+        match &entry.url {
+            Some(url) => url.clone(),
+            None => format!("{}{}", self.base_url, entry.filename),
+        }
+    }
+
+    /// URLs to try in order: the mirrors, then the manifest's URL
+    pub fn download_urls(&self, entry: &ModelEntry, mirrors: &[String]) -> Vec<String> {
+        let mut urls = Vec::new();
+        let mirrored = mirrors
+            .iter()
+            .map(|mirror| format!("{}/{}", mirror.trim_end_matches('/'), entry.filename));
+        for url in mirrored.chain(std::iter::once(self.download_url(entry))) {
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+}
+
+/// Model id for a settings value, including the names stored by older versions
+pub fn normalize_model_id(value: &str) -> String {
+    match value.trim() {
+        "" => DEFAULT_MODEL.to_string(),
+        "Tiny" | "Small" | "Medium" => value.trim().to_lowercase(),
+        id => id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempPath;
+
+    #[test]
+    fn test_bundled_manifest_covers_model_families() {
+        let registry = ModelRegistry::bundled();
+        for id in [
+            "tiny",
+            "base",
+            "small",
+            "medium",
+            "large-v3",
+            "large-v3-turbo",
+        ] {
+            let entry = registry.get(id).expect(id);
+            assert!(entry.covers("de"));
+            assert!(entry.quantization.is_none());
+        }
+
+        let english = registry.get("small.en").unwrap();
+        assert!(english.covers("en"));
+        assert!(!english.covers("de"));
+        assert_eq!(
+            registry.get("medium-q5_0").unwrap().quantization.as_deref(),
+            Some("q5_0")
+        );
+
+        // Every bundled model is checked against its published SHA-256
+        for entry in registry.models() {
+            let sha256 = entry.sha256.as_deref().unwrap_or_default();
+            assert!(entry.sha256.is_some(), "{} has no sha256", entry.id);
+            assert!(
+                sha256.len() == 64 && sha256.bytes().all(|b| b.is_ascii_hexdigit()),
+                "{}",
+                entry.id
+            );
+            assert!(entry.size_mb > 0, "{}", entry.id);
+        }
+        assert_eq!(
+            registry.download_url(registry.get(DEFAULT_MODEL).unwrap()),
+            "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin"
+        );
+    }
+
+    #[test]
+    fn test_user_manifest_overrides_and_adds_models() {
+        let mut registry = ModelRegistry::bundled();
+        let bundled_count = registry.models().len();
+        let json = r#"{
+            "base_url": "http://models.intern/whisper/",
+            "models": [
+                {"id": "small", "display_name": "Small (intern)", "filename": "ggml-small.bin",
+                 "size_mb": 466, "min_ram_gb": 2},
+                {"id": "de-finetune", "display_name": "Deutsch", "filename": "ggml-de.bin",
+                 "url": "http://models.intern/de.bin", "size_mb": 1500, "min_ram_gb": 4,
+                 "languages": ["de"]}
+            ]
+        }"#;
+        registry.extend_from_json(json).unwrap();
+
+        assert_eq!(registry.models().len(), bundled_count + 1);
+        let small = registry.get("small").unwrap();
+        assert_eq!(small.display_name, "Small (intern)");
+        assert_eq!(
+            registry.download_url(small),
+            "http://models.intern/whisper/ggml-small.bin"
+        );
+        let custom = registry.get("de-finetune").unwrap();
+        assert_eq!(registry.download_url(custom), "http://models.intern/de.bin");
+        assert!(!custom.covers("en"));
+    }
+
+    #[test]
+    fn test_mirrors_come_before_the_manifest_url() {
+        let registry = ModelRegistry::bundled();
+        let tiny = registry.get("tiny").unwrap();
+        let mirrors = vec![
+            "http://localhost:8000/models/".to_string(),
+            "https://huggingface.co/ggerganov/whisper.cpp/resolve/main".to_string(),
+        ];
+        assert_eq!(
+            registry.download_urls(tiny, &mirrors),
+            vec![
+                "http://localhost:8000/models/ggml-tiny.bin".to_string(),
+                "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_models_are_kept_in_the_user_manifest() {
+        let path = TempPath::file("models.json");
+        fs::write(&path, r#"{"base_url": "http://models.intern/"}"#).unwrap();
+
+        let mut registry = ModelRegistry::bundled();
+        let mut entry = registry.get("small").unwrap().clone();
+        entry.id = "small-finetune".to_string();
+        entry.filename = "ggml-small-finetune.bin".to_string();
+        entry.custom = true;
+        registry
+            .register_in(&path, std::slice::from_ref(&entry))
+            .unwrap();
+        registry
+            .register_in(&path, std::slice::from_ref(&entry))
+            .unwrap();
+        assert_eq!(registry.get("small-finetune"), Some(&entry));
+
+        // A fresh registry reads it back, the user's base URL stays
+        let mut reloaded = ModelRegistry::bundled();
+        reloaded
+            .extend_from_json(&fs::read_to_string(&path).unwrap())
+            .unwrap();
+        assert_eq!(reloaded.get("small-finetune"), Some(&entry));
+        assert_eq!(reloaded.models().len(), registry.models().len());
+        assert!(reloaded
+            .download_url(reloaded.get("tiny").unwrap())
+            .starts_with("http://models.intern/"));
+    }
+
+    #[test]
+    fn test_checksum_prefers_sha256_and_checks_the_file() {
+        let path = TempPath::file("checksum.bin");
+        fs::write(&path, b"abc").unwrap();
+
+        let mut entry = ModelRegistry::bundled().get("tiny").unwrap().clone();
+        entry.sha1 = Some("A9993E364706816ABA3E25717850C26C9CD0D89D".to_string());
+        entry.sha256 = None;
+        let sha1 = Checksum::of(&entry).unwrap();
+        assert!(matches!(sha1, Checksum::Sha1(_)));
+        assert!(sha1.matches_file(&path).unwrap());
+
+        entry.sha256 =
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".to_string());
+        assert!(Checksum::of(&entry).unwrap().matches_file(&path).unwrap());
+
+        fs::write(&path, b"abd").unwrap();
+        let hashes = FileHashes::of_file(&path).unwrap();
+        assert!(!Checksum::of(&entry).unwrap().matches(&hashes));
+
+        entry.sha1 = None;
+        entry.sha256 = None;
+        assert!(Checksum::of(&entry).is_none());
+    }
+
+    #[test]
+    fn test_legacy_model_names_map_to_ids() {
+        assert_eq!(normalize_model_id("Small"), "small");
+        assert_eq!(normalize_model_id("Medium"), "medium");
+        assert_eq!(normalize_model_id("large-v3-turbo"), "large-v3-turbo");
+        assert_eq!(normalize_model_id(""), DEFAULT_MODEL);
+    }
+}
@@ -0,0 +1,449 @@
+//! Recovery of recordings left behind by a crash
+//!
+//! Every take gets a small journal file next to its WAV that stays until the
+//! take was transcribed or deleted. A journal that is still there on startup
+//! means the app went down while recording or before the transcription
+//! finished, and the WAV is the only copy of that dictation. Instead of letting
+//! the cache cleanup delete it, startup repairs the file where possible and
+//! lists it, so the user can transcribe, archive or discard it.
+
+use crate::audio::{system_track_path, AudioError, SYSTEM_TRACK_EXTENSION};
+use crate::encryption;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Suffix of the journal file that replaces `.wav`
+const JOURNAL_EXTENSION: &str = "take.json";
+
+/// Takes last written this close to a crash are attributed to it
+const CRASH_PAIR_WINDOW: Duration = Duration::from_secs(120);
+
+/// How much of a WAV file is searched for the data chunk
+const HEADER_SEARCH_LEN: u64 = 4096;
+
+/// What is known about a take while it has not been processed
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct TakeJournal {
+    /// Start of the take (RFC 3339, local time)
+    pub started_at: String,
+    #[serde(default)]
+    pub device_name: Option<String>,
+    #[serde(default)]
+    pub privacy_mode: bool,
+    /// A separate system audio track is written next to the take
+    #[serde(default)]
+    pub system_track: bool,
+}
+
+/// An unprocessed recording found on startup
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct OrphanedRecording {
+    pub file_path: String,
+    /// Start of the take (RFC 3339), None if the journal was unreadable
+    pub started_at: Option<String>,
+    pub device_name: Option<String>,
+    pub duration_ms: u64,
+    /// Whether privacy mode was on when the take was recorded
+    pub privacy_mode: bool,
+    /// The WAV header had to be rewritten to match the audio on disk
+    pub repaired: bool,
+    /// The take was last written around the time of the recorded crash
+    pub crashed: bool,
+    /// Separate system audio track of the take, if it survived
+    #[serde(default)]
+    pub system_track: Option<String>,
+}
+
+/// What to do with an orphaned recording
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum RecoveryAction {
+    /// Run it through the transcription pipeline
+    Transcribe,
+    /// Keep the audio in the archive folder without transcribing it
+    Archive,
+    /// Delete the recording
+    Discard,
+}
+
+/// Outcome of a WAV repair
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WavRepair {
+    /// Complete sample frames in the file
+    pub frames: u64,
+    pub sample_rate: u32,
+    /// Whether the header or the file length had to be fixed
+    pub repaired: bool,
+}
+
+/// Journal file belonging to a recording
+pub fn journal_path(wav_path: &Path) -> PathBuf {
+    wav_path.with_extension(JOURNAL_EXTENSION)
+}
+
+/// Whether a recording still waits to be processed
+pub fn has_journal(wav_path: &Path) -> bool {
+    journal_path(wav_path).is_file()
+}
+
+/// Stem of the recording a journal file belongs to
+fn journal_stem(path: &Path) -> Option<&str> {
+    path.file_name()?
+        .to_str()?
+        .strip_suffix(JOURNAL_EXTENSION)?
+        .strip_suffix('.')
+}
+
+/// Whether `path` is a journal file rather than a recording
+pub fn is_journal(path: &Path) -> bool {
+    journal_stem(path).is_some()
+}
+
+/// Whether `path` belongs to a take that still waits to be processed
+///
+/// Covers the take, its journal and its system audio track, which shares the
+/// take's journal.
+pub fn is_unprocessed(path: &Path) -> bool {
+    if is_journal(path) || has_journal(path) {
+        return true;
+    }
+    let take = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(SYSTEM_TRACK_EXTENSION))
+        .and_then(|stem| stem.strip_suffix('.'))
+        .map(|stem| path.with_file_name(format!("{}.wav", stem)));
+    take.is_some_and(|take| has_journal(&take))
+}
+
+/// Mark a recording as unprocessed
+pub fn write_journal(wav_path: &Path, journal: &TakeJournal) -> Result<(), AudioError> {
+    let json = serde_json::to_string(journal)
+        .map_err(|e| AudioError::WavWriteError(format!("Failed to write journal: {}", e)))?;
+    fs::write(journal_path(wav_path), json)
+        .map_err(|e| AudioError::WavWriteError(format!("Failed to write journal: {}", e)))
+}
+
+/// Mark a recording as processed, so a later startup leaves it to the cleanup
+pub fn clear_journal(wav_path: &Path) {
+    match fs::remove_file(journal_path(wav_path)) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => log::warn!("Failed to remove journal of {:?}: {}", wav_path, e),
+    }
+}
+
+fn read_journal(wav_path: &Path) -> Option<TakeJournal> {
+    let content = fs::read_to_string(journal_path(wav_path)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Find and repair the unprocessed recordings in `dir`
+///
+/// `crashed_at` is the time from the crash marker, if the last session
+/// crashed. Recordings without any audio are removed along with their journal.
+pub fn find_orphans(dir: &Path, crashed_at: Option<SystemTime>) -> Vec<OrphanedRecording> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut orphans = Vec::new();
+    for entry in entries.flatten() {
+        let journal = entry.path();
+        let Some(stem) = journal_stem(&journal) else {
+            continue;
+        };
+        let wav_path = dir.join(format!("{}.wav", stem));
+        if !wav_path.is_file() {
+            let _ = fs::remove_file(&journal);
+            continue;
+        }
+
+        let journal = read_journal(&wav_path);
+        let system_path = journal
+            .as_ref()
+            .filter(|journal| journal.system_track)
+            .map(|_| system_track_path(&wav_path))
+            .filter(|path| path.is_file());
+
+        let repair = match inspect_wav(&wav_path) {
+            Ok(repair) if repair.frames > 0 => repair,
+            Ok(_) => {
+                log::info!("Removing empty interrupted recording {:?}", wav_path);
+                let _ = fs::remove_file(&wav_path);
+                if let Some(system_path) = &system_path {
+                    let _ = fs::remove_file(system_path);
+                }
+                clear_journal(&wav_path);
+                continue;
+            }
+            Err(e) => {
+                // Left in place: the user can still discard it
+                log::warn!(
+                    "Could not repair interrupted recording {:?}: {}",
+                    wav_path,
+                    e
+                );
+                WavRepair {
+                    frames: 0,
+                    sample_rate: 0,
+                    repaired: false,
+                }
+            }
+        };
+        if repair.repaired {
+            log::warn!("Repaired interrupted recording {:?}", wav_path);
+        }
+
+        let modified = fs::metadata(&wav_path).and_then(|m| m.modified()).ok();
+        let crashed = match (crashed_at, modified) {
+            (Some(crash), Some(modified)) => {
+                let distance = crash
+                    .duration_since(modified)
+                    .or_else(|_| modified.duration_since(crash))
+                    .unwrap_or_default();
+                distance <= CRASH_PAIR_WINDOW
+            }
+            _ => false,
+        };
+
+        // The system track ended with the take, so it needs the same repair
+        let system_track = system_path.filter(|path| match inspect_wav(path) {
+            Ok(repair) => repair.frames > 0,
+            Err(e) => {
+                log::warn!("Could not repair system audio track {:?}: {}", path, e);
+                false
+            }
+        });
+
+        orphans.push(OrphanedRecording {
+            file_path: wav_path.to_string_lossy().to_string(),
+            started_at: journal.as_ref().map(|j| j.started_at.clone()),
+            device_name: journal.as_ref().and_then(|j| j.device_name.clone()),
+            duration_ms: if repair.sample_rate > 0 {
+                repair.frames * 1000 / repair.sample_rate as u64
+            } else {
+                0
+            },
+            privacy_mode: journal.as_ref().map_or(true, |j| j.privacy_mode),
+            repaired: repair.repaired,
+            crashed,
+            system_track: system_track.map(|path| path.to_string_lossy().to_string()),
+        });
+    }
+
+    // Oldest first, like they were recorded
+    orphans.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    orphans
+}
+
+/// Make the WAV header match the audio that actually made it to disk
+///
+/// The writer rewrites the header on every flush, so after a crash it lags
+/// the data by up to one writer tick, or still claims an empty file if the
+/// take never got flushed. A half-written frame at the end is cut off.
+pub fn repair_wav(path: &Path) -> Result<WavRepair, AudioError> {
+    let invalid = |reason: &str| AudioError::WavWriteError(format!("{:?}: {}", path, reason));
+    let io_error = |e: std::io::Error| AudioError::WavWriteError(e.to_string());
+
+    let mut file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .map_err(io_error)?;
+    let file_len = file.metadata().map_err(io_error)?.len();
+
+    let mut header = Vec::new();
+    (&mut file)
+        .take(HEADER_SEARCH_LEN)
+        .read_to_end(&mut header)
+        .map_err(io_error)?;
+    if header.len() < 12 || &header[0..4] != b"RIFF" || &header[8..12] != b"WAVE" {
+        return Err(invalid("not a WAV file"));
+    }
+
+    let read_u32 = |at: usize| {
+        u32::from_le_bytes([header[at], header[at + 1], header[at + 2], header[at + 3]])
+    };
+    let mut format: Option<(u32, u16)> = None;
+    let mut offset = 12;
+    let data_start = loop {
+        if offset + 8 > header.len() {
+            return Err(invalid("no data chunk"));
+        }
+        let id = &header[offset..offset + 4];
+        let size = read_u32(offset + 4) as usize;
+        if id == b"data" {
+            break offset + 8;
+        }
+        if id == b"fmt " && offset + 8 + 14 <= header.len() {
+            let sample_rate = read_u32(offset + 12);
+            let block_align = u16::from_le_bytes([header[offset + 20], header[offset + 21]]);
+            format = Some((sample_rate, block_align));
+        }
+        offset += 8 + size + (size & 1);
+    };
+    let (sample_rate, block_align) = format
+        .filter(|(rate, align)| *rate > 0 && *align > 0)
+        .ok_or_else(|| invalid("missing or invalid format chunk"))?;
+
+    let available = file_len.saturating_sub(data_start as u64);
+    let data_len = (available - available % block_align as u64).min(u32::MAX as u64 - 36);
+    let expected_len = data_start as u64 + data_len;
+    let riff_len = (expected_len - 8) as u32;
+    let repaired = file_len != expected_len
+        || read_u32(4) != riff_len
+        || read_u32(data_start - 4) != data_len as u32;
+
+    if repaired {
+        file.set_len(expected_len).map_err(io_error)?;
+        file.seek(SeekFrom::Start(4)).map_err(io_error)?;
+        file.write_all(&riff_len.to_le_bytes()).map_err(io_error)?;
+        file.seek(SeekFrom::Start(data_start as u64 - 4))
+            .map_err(io_error)?;
+        file.write_all(&(data_len as u32).to_le_bytes())
+            .map_err(io_error)?;
+        file.sync_all().map_err(io_error)?;
+    }
+
+    Ok(WavRepair {
+        frames: data_len / block_align as u64,
+        sample_rate,
+        repaired,
+    })
+}
+
+/// Repair an interrupted WAV, or read the length of a sealed one
+fn inspect_wav(path: &Path) -> Result<WavRepair, AudioError> {
+    // Sealed takes were finalized before the app went down, nothing to repair
+    if encryption::is_sealed(path) {
+        inspect_sealed_wav(path)
+    } else {
+        repair_wav(path)
+    }
+}
+
+/// Length of a sealed WAV, which needs the encryption to be unlocked
+fn inspect_sealed_wav(path: &Path) -> Result<WavRepair, AudioError> {
+    let data = encryption::read(path).map_err(|e| AudioError::Encryption(e.to_string()))?;
+    let reader = hound::WavReader::new(std::io::Cursor::new(data))
+        .map_err(|e| AudioError::WavWriteError(e.to_string()))?;
+    Ok(WavRepair {
+        frames: reader.duration() as u64,
+        sample_rate: reader.spec().sample_rate,
+        repaired: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::WHISPER_SAMPLE_RATE;
+    use crate::test_util::TempPath;
+
+    /// A WAV as the recorder leaves it when it dies before the next flush
+    fn write_interrupted_wav(path: &Path, flushed: u32, written: u32) {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: WHISPER_SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for i in 0..flushed {
+            writer.write_sample((i % 100) as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        // Samples that reached the disk without a header update, plus half a frame
+        let mut file = fs::OpenOptions::new().append(true).open(path).unwrap();
+        for i in 0..written - flushed {
+            file.write_all(&((i % 100) as i16).to_le_bytes()).unwrap();
+        }
+        file.write_all(&[0x7f]).unwrap();
+    }
+
+    #[test]
+    fn test_repair_wav_recovers_unflushed_samples() {
+        let dir = TempPath::dir("repair");
+        let path = dir.join("take.wav");
+        write_interrupted_wav(&path, 1600, 16000);
+
+        let repair = repair_wav(&path).unwrap();
+        assert!(repair.repaired);
+        assert_eq!(repair.frames, 16000);
+
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.len(), 16000);
+
+        // A second pass finds nothing left to fix
+        assert!(!repair_wav(&path).unwrap().repaired);
+    }
+
+    #[test]
+    fn test_find_orphans_only_lists_journaled_takes() {
+        let dir = TempPath::dir("orphans");
+        let orphan = dir.join("orphan.wav");
+        let processed = dir.join("processed.wav");
+        let empty = dir.join("empty.wav");
+        write_interrupted_wav(&orphan, 0, 8000);
+        write_interrupted_wav(&processed, 8000, 8000);
+        write_interrupted_wav(&empty, 0, 0);
+
+        let journal = TakeJournal {
+            started_at: "2026-01-01T10:00:00+01:00".to_string(),
+            device_name: Some("USB Mic".to_string()),
+            privacy_mode: false,
+            system_track: false,
+        };
+        write_journal(&orphan, &journal).unwrap();
+        write_journal(&empty, &journal).unwrap();
+        // Journal whose recording is already gone
+        write_journal(&dir.join("gone.wav"), &journal).unwrap();
+
+        let orphans = find_orphans(&dir, Some(SystemTime::now()));
+        assert_eq!(orphans.len(), 1);
+        let found = &orphans[0];
+        assert_eq!(found.file_path, orphan.to_string_lossy());
+        assert_eq!(found.duration_ms, 500);
+        assert_eq!(found.device_name.as_deref(), Some("USB Mic"));
+        assert!(found.repaired);
+        assert!(found.crashed);
+
+        assert!(!empty.exists() && !has_journal(&empty));
+        assert!(!journal_path(&dir.join("gone.wav")).exists());
+        assert!(processed.exists());
+    }
+
+    #[test]
+    fn test_system_track_is_kept_and_recovered_with_its_take() {
+        let dir = TempPath::dir("system");
+        let take = dir.join("take.wav");
+        let system = system_track_path(&take);
+        write_interrupted_wav(&take, 0, 8000);
+        write_interrupted_wav(&system, 0, 16000);
+
+        let journal = TakeJournal {
+            started_at: "2026-01-01T10:00:00+01:00".to_string(),
+            device_name: None,
+            privacy_mode: false,
+            system_track: true,
+        };
+        write_journal(&take, &journal).unwrap();
+        // The cache cleanup leaves both tracks alone
+        assert!(is_unprocessed(&take));
+        assert!(is_unprocessed(&system));
+
+        let orphans = find_orphans(&dir, None);
+        assert_eq!(orphans.len(), 1);
+        assert_eq!(
+            orphans[0].system_track.as_deref(),
+            Some(system.to_string_lossy().as_ref())
+        );
+        assert_eq!(hound::WavReader::open(&system).unwrap().len(), 16000);
+
+        clear_journal(&take);
+        assert!(!is_unprocessed(&system));
+    }
+}
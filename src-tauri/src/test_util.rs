@@ -0,0 +1,112 @@
+//! Fixtures shared by the unit tests
+
+use std::f32::consts::TAU;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// A unique path in the temp directory, removed again when the test ends
+///
+/// Cleanup runs on drop, so a failing assertion does not leave files behind.
+pub struct TempPath(PathBuf);
+
+impl TempPath {
+    /// Path for a file that does not exist yet, `name` ends in its extension
+    pub fn file(name: &str) -> Self {
+        Self(std::env::temp_dir().join(format!("evervoice-{}-{}", uuid::Uuid::new_v4(), name)))
+    }
+
+    /// A new, empty directory
+    pub fn dir(name: &str) -> Self {
+        let path = Self::file(name);
+        fs::create_dir_all(&path.0).unwrap();
+        path
+    }
+}
+
+impl Deref for TempPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        if self.0.is_dir() {
+            let _ = fs::remove_dir_all(&self.0);
+        } else {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+}
+
+/// `frames` samples of a sine tone
+pub fn sine(frequency: f32, amplitude: f32, sample_rate: u32, frames: usize) -> Vec<f32> {
+    (0..frames)
+        .map(|i| (i as f32 * frequency * TAU / sample_rate as f32).sin() * amplitude)
+        .collect()
+}
+
+/// Write interleaved samples in [-1, 1] as a 16-bit PCM WAV
+pub fn write_wav(path: &Path, sample_rate: u32, channels: u16, samples: &[f32]) {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).unwrap();
+    for sample in samples {
+        writer
+            .write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16)
+            .unwrap();
+    }
+    writer.finalize().unwrap();
+}
+
+/// Samples of a 16-bit PCM WAV in [-1, 1]
+pub fn read_wav(path: &Path) -> Vec<f32> {
+    hound::WavReader::open(path)
+        .unwrap()
+        .samples::<i16>()
+        .map(|s| s.unwrap() as f32 / i16::MAX as f32)
+        .collect()
+}
+
+/// Header of a ggml Whisper model followed by some fake weights
+pub fn ggml_model(
+    n_vocab: i32,
+    n_audio_layer: i32,
+    n_text_layer: i32,
+    n_mels: i32,
+    ftype: i32,
+) -> Vec<u8> {
+    // "ggml", then n_vocab, n_audio_ctx, n_audio_state, n_audio_head,
+    // n_audio_layer, n_text_ctx, n_text_state, n_text_head, n_text_layer,
+    // n_mels and ftype
+    let fields = [
+        0x6767_6d6c,
+        n_vocab,
+        1500,
+        384,
+        6,
+        n_audio_layer,
+        448,
+        384,
+        6,
+        n_text_layer,
+        n_mels,
+        ftype,
+    ];
+    let mut bytes: Vec<u8> = fields.iter().flat_map(|f| f.to_le_bytes()).collect();
+    bytes.extend((0..4096u32).map(|i| (i % 251) as u8));
+    bytes
+}
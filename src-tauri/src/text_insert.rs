@@ -8,6 +8,7 @@
 //! before inserting text. This ensures text goes to the app where the user
 //! was when they pressed the hotkey, not where they are after transcription.
 
+use crate::encryption;
 use arboard::Clipboard;
 use enigo::{
     Direction::{Click, Press, Release},
@@ -103,7 +104,7 @@ fn get_config_path() -> PathBuf {
 pub fn load_settings() -> TextInsertSettings {
     let config_path = get_config_path();
     if config_path.exists() {
-        if let Ok(content) = fs::read_to_string(&config_path) {
+        if let Ok(content) = encryption::read_to_string(&config_path) {
             if let Ok(settings) = serde_json::from_str(&content) {
                 return settings;
             }
@@ -116,7 +117,7 @@ pub fn load_settings() -> TextInsertSettings {
 pub fn save_settings(settings: &TextInsertSettings) -> Result<(), String> {
     let config_path = get_config_path();
     let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
-    fs::write(&config_path, json).map_err(|e| e.to_string())
+    encryption::write(&config_path, json).map_err(|e| e.to_string())
 }
 
 /// Check if text contains complex Unicode (emojis, special chars)
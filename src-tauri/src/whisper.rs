@@ -2,158 +2,176 @@
 //!
 //! Handles model management, downloading, and speech-to-text transcription.
 
+use crate::audio::{LiveAudio, SpeechSegment, WHISPER_SAMPLE_RATE};
+use crate::encryption;
+use crate::model_registry::{self, Checksum, ModelEntry, ModelRegistry};
 use futures_util::StreamExt;
-use reqwest::Client;
-use sha2::{Digest, Sha256};
+use reqwest::{Client, StatusCode};
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
-
-/// Available Whisper models
-#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
-pub enum WhisperModel {
-    Tiny,
-    Small,
-    Medium,
+use std::thread;
+use std::time::{Duration, Instant};
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+};
+
+/// Language setting that leaves the choice to auto-detection
+pub const AUTO_LANGUAGE: &str = "auto";
+
+/// A language Whisper can transcribe
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct LanguageInfo {
+    /// ISO code (de, en, fr, ...)
+    pub code: &'static str,
+    /// English name from Whisper's table (german, english, french, ...)
+    pub name: &'static str,
 }
 
-impl WhisperModel {
-    /// Get the model name for display
-    pub fn name(&self) -> &'static str {
-        match self {
-            WhisperModel::Tiny => "tiny",
-            WhisperModel::Small => "small",
-            WhisperModel::Medium => "medium",
-        }
-    }
-
-    /// Get the display name with size info
-    pub fn display_name(&self) -> &'static str {
-        match self {
-            WhisperModel::Tiny => "Tiny (~75 MB, schnell)",
-            WhisperModel::Small => "Small (~500 MB, empfohlen)",
-            WhisperModel::Medium => "Medium (~1.5 GB, genau)",
-        }
-    }
-
-    /// Get the download URL for the model
-    pub fn download_url(&self) -> &'static str {
-        match self {
-            WhisperModel::Tiny => {
-                "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin"
-            }
-            WhisperModel::Small => {
-                "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin"
-            }
-            WhisperModel::Medium => {
-                "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin"
-            }
-        }
-    }
-
-    /// Get the expected file size in bytes (approximate)
-    pub fn expected_size(&self) -> u64 {
-        match self {
-            WhisperModel::Tiny => 75 * 1024 * 1024,     // ~75 MB
-            WhisperModel::Small => 500 * 1024 * 1024,   // ~500 MB
-            WhisperModel::Medium => 1500 * 1024 * 1024, // ~1.5 GB
-        }
-    }
-
-    /// Get the expected SHA256 hash (first 16 chars for quick verification)
-    /// Note: These are placeholder hashes - real ones should be verified from Hugging Face
-    pub fn expected_hash_prefix(&self) -> &'static str {
-        match self {
-            WhisperModel::Tiny => "be7e29e",   // Partial hash for ggml-tiny.bin
-            WhisperModel::Small => "9ecf779",  // Partial hash for ggml-small.bin
-            WhisperModel::Medium => "fd9727b", // Partial hash for ggml-medium.bin
-        }
-    }
-
-    /// Get minimum RAM required in GB
-    pub fn min_ram_gb(&self) -> u8 {
-        match self {
-            WhisperModel::Tiny => 1,
-            WhisperModel::Small => 2,
-            WhisperModel::Medium => 4,
-        }
-    }
+/// All languages in Whisper's own table, in its order
+pub fn supported_languages() -> Vec<LanguageInfo> {
+    (0..=whisper_rs::get_lang_max_id())
+        .filter_map(|id| {
+            Some(LanguageInfo {
+                code: whisper_rs::get_lang_str(id)?,
+                name: whisper_rs::get_lang_str_full(id)?,
+            })
+        })
+        .collect()
+}
 
-    /// Get the model filename
-    pub fn filename(&self) -> String {
-        format!("ggml-{}.bin", self.name())
+/// Longest code in Whisper's table ("haw", "yue")
+const MAX_LANGUAGE_CODE_LEN: usize = 3;
+
+/// Whisper's id for `code`
+///
+/// Only short lowercase codes reach whisper.cpp: `get_lang_id` panics on an
+/// interior NUL, and a settings file may contain anything.
+fn language_id(code: &str) -> Option<i32> {
+    if code.is_empty()
+        || code.len() > MAX_LANGUAGE_CODE_LEN
+        || !code.bytes().all(|b| b.is_ascii_lowercase())
+    {
+        return None;
     }
+    whisper_rs::get_lang_id(code)
 }
 
-impl Default for WhisperModel {
-    fn default() -> Self {
-        WhisperModel::Small // Best balance of speed and accuracy
+/// Whisper's code for `code`, None for auto-detection and codes it does not know
+pub fn whisper_language(code: &str) -> Option<&'static str> {
+    if code == AUTO_LANGUAGE {
+        return None;
     }
+    language_id(code).and_then(whisper_rs::get_lang_str)
 }
 
-/// Supported languages for transcription
-#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
-pub enum WhisperLanguage {
-    Auto,
-    German,
-    English,
+/// English name of the language with ISO code `code`
+pub fn language_name(code: &str) -> Option<&'static str> {
+    language_id(code).and_then(whisper_rs::get_lang_str_full)
 }
 
-impl WhisperLanguage {
-    /// Get the language code for Whisper
-    pub fn code(&self) -> Option<&'static str> {
-        match self {
-            WhisperLanguage::Auto => None, // Let Whisper auto-detect
-            WhisperLanguage::German => Some("de"),
-            WhisperLanguage::English => Some("en"),
-        }
+/// ISO code for a language value, including the names stored by older versions
+pub fn normalize_language(value: &str) -> String {
+    match value.trim() {
+        "" | "Auto" => AUTO_LANGUAGE.to_string(),
+        "German" | "german" => "de".to_string(),
+        "English" | "english" => "en".to_string(),
+        code => code.to_lowercase(),
     }
+}
 
-    /// Get the display name
-    pub fn display_name(&self) -> &'static str {
-        match self {
-            WhisperLanguage::Auto => "Automatisch erkennen",
-            WhisperLanguage::German => "Deutsch",
-            WhisperLanguage::English => "English",
-        }
-    }
+fn deserialize_language<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+    Ok(normalize_language(&value))
 }
 
-impl Default for WhisperLanguage {
-    fn default() -> Self {
-        WhisperLanguage::Auto
-    }
+fn deserialize_model<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+    Ok(model_registry::normalize_model_id(&value))
 }
 
 /// Whisper configuration settings
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct WhisperSettings {
-    /// Selected model
-    pub model: WhisperModel,
-    /// Language setting
-    pub language: WhisperLanguage,
+    /// Id of the selected model in the model registry
+    #[serde(deserialize_with = "deserialize_model")]
+    pub model: String,
+    /// ISO code of the spoken language, or `AUTO_LANGUAGE`
+    #[serde(deserialize_with = "deserialize_language")]
+    pub language: String,
     /// Enable GPU acceleration (Metal on macOS)
     pub use_gpu: bool,
+    /// Transcribe while recording and show partial text in the overlay
+    #[serde(default)]
+    pub streaming: bool,
+    /// Languages auto-detection may pick from (ISO codes, empty: any)
+    #[serde(default)]
+    pub auto_languages: Vec<String>,
+    /// Base URLs tried before the manifest's download URL, e.g. a mirror or
+    /// a local file server holding the `ggml-*.bin` files
+    #[serde(default)]
+    pub download_mirrors: Vec<String>,
 }
 
 impl Default for WhisperSettings {
     fn default() -> Self {
         Self {
-            model: WhisperModel::default(),
-            language: WhisperLanguage::default(),
+            model: model_registry::DEFAULT_MODEL.to_string(),
+            language: AUTO_LANGUAGE.to_string(),
             use_gpu: true, // Enable by default on macOS
+            streaming: false,
+            auto_languages: Vec::new(),
+            download_mirrors: Vec::new(),
+        }
+    }
+}
+
+impl WhisperSettings {
+    /// Reject unknown models, languages Whisper or the model does not know
+    /// and mirrors that are not HTTP(S) URLs
+    pub fn validate(&self, registry: &ModelRegistry) -> Result<(), WhisperError> {
+        if let Some(mirror) = self
+            .download_mirrors
+            .iter()
+            .find(|mirror| !is_http_url(mirror))
+        {
+            return Err(WhisperError::InvalidMirror(mirror.clone()));
+        }
+
+        let entry = registry
+            .get(&self.model)
+            .ok_or_else(|| WhisperError::UnknownModel(self.model.clone()))?;
+        let unknown = std::iter::once(&self.language)
+            .filter(|code| code.as_str() != AUTO_LANGUAGE)
+            .chain(&self.auto_languages)
+            .find(|code| language_id(code).is_none() || !entry.covers(code));
+        match unknown {
+            Some(code) => Err(WhisperError::UnsupportedLanguage(code.clone())),
+            None => Ok(()),
         }
     }
 }
 
+/// Whether a mirror setting is an absolute HTTP(S) URL
+fn is_http_url(value: &str) -> bool {
+    url::Url::parse(value)
+        .map(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+        .unwrap_or(false)
+}
+
 /// Download progress information
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct DownloadProgress {
-    /// Model being downloaded
-    pub model: WhisperModel,
+    /// Id of the model being downloaded
+    pub model: String,
     /// Bytes downloaded so far
     pub downloaded_bytes: u64,
     /// Total bytes to download
@@ -164,13 +182,21 @@ pub struct DownloadProgress {
     pub complete: bool,
     /// Error message if failed
     pub error: Option<String>,
+    /// Attempt at the current URL, above 1 after the connection broke
+    #[serde(default)]
+    pub attempt: u32,
+    /// The file is complete and its checksum is being checked
+    #[serde(default)]
+    pub verifying: bool,
 }
 
 /// Model status information
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ModelStatus {
-    /// Model type
-    pub model: WhisperModel,
+    /// Model id
+    pub model: String,
+    /// Manifest entry with name, size and language coverage
+    pub info: ModelEntry,
     /// Whether the model is downloaded
     pub downloaded: bool,
     /// File size in bytes (if downloaded)
@@ -179,6 +205,27 @@ pub struct ModelStatus {
     pub loaded: bool,
     /// Whether a download is in progress
     pub downloading: bool,
+    /// Bytes of an interrupted download the next one resumes from
+    pub partial_bytes: Option<u64>,
+}
+
+/// Who spoke a segment of a take recorded with a separate system audio track
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Speaker {
+    /// The microphone track: the user
+    Me,
+    /// The system audio track: the other side of a call
+    Them,
+}
+
+impl Speaker {
+    /// Label written in front of each turn
+    pub fn label(self) -> &'static str {
+        match self {
+            Speaker::Me => "Ich",
+            Speaker::Them => "Gegenüber",
+        }
+    }
 }
 
 /// A single transcription segment with timestamp
@@ -190,6 +237,9 @@ pub struct TranscriptionSegment {
     pub start_ms: i64,
     /// End time in milliseconds
     pub end_ms: i64,
+    /// Track the segment came from (None for single-track takes)
+    #[serde(default)]
+    pub speaker: Option<Speaker>,
 }
 
 /// Result of a transcription
@@ -203,6 +253,327 @@ pub struct TranscriptionResult {
     pub segments: Vec<TranscriptionSegment>,
     /// Processing time in milliseconds
     pub processing_time_ms: u64,
+    /// Probability of the detected language (None when the language was set)
+    #[serde(default)]
+    pub language_probability: Option<f32>,
+    /// Auto-detection was unsure, the language may be wrong
+    #[serde(default)]
+    pub language_uncertain: bool,
+}
+
+impl TranscriptionResult {
+    /// Result for a take that was not sent to Whisper because nobody spoke
+    pub fn no_speech() -> Self {
+        Self {
+            text: String::new(),
+            language: AUTO_LANGUAGE.to_string(),
+            segments: Vec::new(),
+            processing_time_ms: 0,
+            language_probability: None,
+            language_uncertain: false,
+        }
+    }
+
+    /// Result for `segments` in the set language or the detected one
+    fn from_segments(
+        segments: Vec<TranscriptionSegment>,
+        language: Option<&str>,
+        detection: Option<&LanguageDetection>,
+        processing_time_ms: u64,
+    ) -> Self {
+        let (language, language_probability, language_uncertain) = match (language, detection) {
+            (Some(code), _) => (code.to_string(), None, false),
+            (None, Some(detection)) => (
+                detection.code.to_string(),
+                Some(detection.probability),
+                detection.is_uncertain(),
+            ),
+            // Detection failed, Whisper picked a language on its own
+            (None, None) => ("auto".to_string(), None, true),
+        };
+
+        Self {
+            text: join_segments(&segments),
+            language,
+            segments,
+            processing_time_ms,
+            language_probability,
+            language_uncertain,
+        }
+    }
+}
+
+/// Detections less likely than this are flagged as uncertain
+const LANGUAGE_CONFIDENCE_THRESHOLD: f32 = 0.6;
+
+/// Audio needed before a streaming take settles on its language
+const LANGUAGE_DETECTION_MIN_MS: u64 = 3000;
+
+/// Audio Whisper looks at for language detection (one encoder window)
+const LANGUAGE_DETECTION_MAX_MS: u64 = 30_000;
+
+/// Language picked by Whisper's language detection
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct LanguageDetection {
+    /// ISO code as Whisper names it
+    pub code: &'static str,
+    /// Probability Whisper gave it
+    pub probability: f32,
+}
+
+impl LanguageDetection {
+    pub fn is_uncertain(&self) -> bool {
+        self.probability < LANGUAGE_CONFIDENCE_THRESHOLD
+    }
+}
+
+/// Most likely of the `candidates`, limited to `allowed` codes unless it is empty
+fn pick_language(
+    candidates: impl IntoIterator<Item = (&'static str, f32)>,
+    allowed: &[String],
+) -> Option<LanguageDetection> {
+    candidates
+        .into_iter()
+        .filter(|(code, _)| allowed.is_empty() || allowed.iter().any(|a| a == code))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(code, probability)| LanguageDetection { code, probability })
+}
+
+/// Run Whisper's language detection on the first 30 seconds of `samples`
+fn detect_language(
+    state: &mut WhisperState,
+    samples: &[f32],
+    allowed: &[String],
+) -> Result<Option<LanguageDetection>, WhisperError> {
+    let whisper_err = |e| WhisperError::TranscriptionError(format!("{:?}", e));
+    let threads = thread::available_parallelism().map_or(1, |n| n.get().min(4));
+
+    // Only the first window is used, the mel of the rest would be thrown away
+    let head = &samples[..samples.len().min(ms_to_samples(LANGUAGE_DETECTION_MAX_MS))];
+    state.pcm_to_mel(head, threads).map_err(whisper_err)?;
+    let probabilities = state.lang_detect(0, threads).map_err(whisper_err)?;
+    let candidates = probabilities
+        .into_iter()
+        .enumerate()
+        .filter_map(|(id, p)| whisper_rs::get_lang_str(id as i32).map(|code| (code, p)));
+    let detection = pick_language(candidates, allowed);
+
+    if let Some(detection) = &detection {
+        log::info!(
+            "Detected language {} ({:.0}%)",
+            detection.code,
+            detection.probability * 100.0
+        );
+    }
+    Ok(detection)
+}
+
+/// Pauses shorter than this stay in the audio passed to Whisper
+const SPEECH_GAP_MERGE_MS: u64 = 1000;
+
+/// Context kept before and after each speech region
+const SPEECH_PADDING_MS: u64 = 200;
+
+/// A piece of the original recording that was kept for transcription
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct SpeechSpan {
+    /// Start in the compacted buffer
+    compact_start_ms: u64,
+    /// Start in the original recording
+    original_start_ms: u64,
+    len_ms: u64,
+}
+
+/// Pad and merge VAD segments into the regions worth transcribing
+fn speech_regions(segments: &[SpeechSegment], total_ms: u64) -> Vec<(u64, u64)> {
+    let mut regions: Vec<(u64, u64)> = Vec::new();
+    for segment in segments {
+        let start = segment.start_ms.saturating_sub(SPEECH_PADDING_MS);
+        let end = (segment.end_ms + SPEECH_PADDING_MS).min(total_ms);
+        if start >= end {
+            continue;
+        }
+        match regions.last_mut() {
+            Some(last) if start <= last.1 + SPEECH_GAP_MERGE_MS => last.1 = last.1.max(end),
+            _ => regions.push((start, end)),
+        }
+    }
+    regions
+}
+
+/// Cut everything except the speech regions out of the buffer
+fn compact_to_speech(
+    samples: &[f32],
+    sample_rate: u32,
+    regions: &[(u64, u64)],
+) -> (Vec<f32>, Vec<SpeechSpan>) {
+    let per_ms = sample_rate as u64 / 1000;
+    let mut compact = Vec::new();
+    let mut spans = Vec::with_capacity(regions.len());
+
+    for &(start_ms, end_ms) in regions {
+        let start = ((start_ms * per_ms) as usize).min(samples.len());
+        let end = ((end_ms * per_ms) as usize).min(samples.len());
+        spans.push(SpeechSpan {
+            compact_start_ms: compact.len() as u64 / per_ms,
+            original_start_ms: start_ms,
+            len_ms: end_ms - start_ms,
+        });
+        compact.extend_from_slice(&samples[start..end]);
+    }
+
+    (compact, spans)
+}
+
+/// Map a timestamp in the compacted buffer back to the original recording
+fn to_original_ms(compact_ms: i64, spans: &[SpeechSpan]) -> i64 {
+    let compact_ms = compact_ms.max(0) as u64;
+    let span = spans
+        .iter()
+        .rev()
+        .find(|span| span.compact_start_ms <= compact_ms)
+        .or(spans.first());
+    match span {
+        Some(span) => {
+            let offset = (compact_ms - span.compact_start_ms.min(compact_ms)).min(span.len_ms);
+            (span.original_start_ms + offset) as i64
+        }
+        None => compact_ms as i64,
+    }
+}
+
+/// Bring a take transcribed while it recorded onto the timeline of its file
+///
+/// The live audio is tapped before silence is trimmed from the start of the
+/// take, so every segment is `trim_offset_ms` late. Segments outside the
+/// speech regions are dropped, the file transcription never passes that
+/// audio to Whisper either. `speech_segments` are on the trimmed timeline.
+pub fn align_streamed(
+    result: &mut TranscriptionResult,
+    trim_offset_ms: u64,
+    speech_segments: Option<&[SpeechSegment]>,
+) {
+    let offset = trim_offset_ms as i64;
+    let regions = speech_regions(speech_segments.unwrap_or_default(), u64::MAX);
+    result.segments = std::mem::take(&mut result.segments)
+        .into_iter()
+        .map(|segment| TranscriptionSegment {
+            start_ms: (segment.start_ms - offset).max(0),
+            end_ms: (segment.end_ms - offset).max(0),
+            ..segment
+        })
+        .filter(|segment| {
+            regions.is_empty()
+                || regions.iter().any(|&(start, end)| {
+                    segment.start_ms < end as i64 && segment.end_ms > start as i64
+                })
+        })
+        .collect();
+    result.text = join_segments(&result.segments);
+}
+
+/// Segment starts further than this from a pause get no paragraph break
+const PARAGRAPH_BREAK_TOLERANCE_MS: i64 = 1500;
+
+/// Rebuild the text with a paragraph break at each pause in the recording
+///
+/// Whisper segments rarely start exactly where the take was paused, so each
+/// break goes before the segment starting closest to its marker.
+pub fn apply_paragraph_breaks(result: &mut TranscriptionResult, breaks_ms: &[u64]) {
+    let breaks: Vec<usize> = breaks_ms
+        .iter()
+        .filter_map(|&at_ms| {
+            result
+                .segments
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, segment)| (i, (segment.start_ms - at_ms as i64).abs()))
+                .filter(|&(_, distance)| distance <= PARAGRAPH_BREAK_TOLERANCE_MS)
+                .min_by_key(|&(_, distance)| distance)
+                .map(|(i, _)| i)
+        })
+        .collect();
+    if breaks.is_empty() {
+        return;
+    }
+
+    let mut paragraphs = vec![String::new()];
+    for (i, segment) in result.segments.iter().enumerate() {
+        let current = paragraphs.last_mut().unwrap();
+        if breaks.contains(&i) && !current.is_empty() {
+            paragraphs.push(String::new());
+        }
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let current = paragraphs.last_mut().unwrap();
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(text);
+    }
+    result.text = paragraphs.join("\n\n");
+}
+
+/// Combine the transcriptions of separately recorded tracks into one dialog
+///
+/// Segments are ordered by start time and labelled with their speaker; each
+/// run of segments from the same speaker becomes one "Ich: …" paragraph.
+pub fn merge_speaker_tracks(tracks: Vec<(Speaker, TranscriptionResult)>) -> TranscriptionResult {
+    let mut language = String::new();
+    let mut language_probability = None;
+    let mut language_uncertain = false;
+    let mut processing_time_ms = 0;
+    let mut segments = Vec::new();
+    for (speaker, track) in tracks {
+        if language.is_empty() {
+            language = track.language;
+            language_probability = track.language_probability;
+            language_uncertain = track.language_uncertain;
+        }
+        processing_time_ms += track.processing_time_ms;
+        segments.extend(
+            track
+                .segments
+                .into_iter()
+                .map(|segment| TranscriptionSegment {
+                    speaker: Some(speaker),
+                    ..segment
+                }),
+        );
+    }
+    // Stable, so the microphone goes first when both start together
+    segments.sort_by_key(|segment| segment.start_ms);
+
+    let mut turns: Vec<(Speaker, String)> = Vec::new();
+    for segment in &segments {
+        let text = segment.text.trim();
+        let Some(speaker) = segment.speaker.filter(|_| !text.is_empty()) else {
+            continue;
+        };
+        match turns.last_mut() {
+            Some((current, turn)) if *current == speaker => {
+                turn.push(' ');
+                turn.push_str(text);
+            }
+            _ => turns.push((speaker, text.to_string())),
+        }
+    }
+
+    TranscriptionResult {
+        text: turns
+            .iter()
+            .map(|(speaker, turn)| format!("{}: {}", speaker.label(), turn))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        language,
+        segments,
+        processing_time_ms,
+        language_probability,
+        language_uncertain,
+    }
 }
 
 /// Errors that can occur during Whisper operations
@@ -226,6 +597,14 @@ pub enum WhisperError {
     DownloadCancelled,
     #[error("Invalid audio file: {0}")]
     InvalidAudioFile(String),
+    #[error("Unsupported language: {0}")]
+    UnsupportedLanguage(String),
+    #[error("Unknown model: {0}")]
+    UnknownModel(String),
+    #[error("No checksum for model {0} in the manifest")]
+    MissingChecksum(String),
+    #[error("Invalid download mirror: {0}")]
+    InvalidMirror(String),
 }
 
 impl serde::Serialize for WhisperError {
@@ -243,14 +622,14 @@ pub struct WhisperManager {
     settings: WhisperSettings,
     /// Loaded Whisper context (if any)
     context: Option<WhisperContext>,
-    /// Currently loaded model
-    loaded_model: Option<WhisperModel>,
+    /// Models from the bundled and the user manifest
+    registry: ModelRegistry,
+    /// Id of the currently loaded model
+    loaded_model: Option<String>,
     /// Download state
     download_progress: Arc<Mutex<Option<DownloadProgress>>>,
     /// Download cancellation flag
     download_cancel: Arc<AtomicBool>,
-    /// Downloaded bytes counter for progress
-    downloaded_bytes: Arc<AtomicU64>,
 }
 
 impl WhisperManager {
@@ -259,10 +638,10 @@ impl WhisperManager {
         Self {
             settings: WhisperSettings::default(),
             context: None,
+            registry: ModelRegistry::load(),
             loaded_model: None,
             download_progress: Arc::new(Mutex::new(None)),
             download_cancel: Arc::new(AtomicBool::new(false)),
-            downloaded_bytes: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -277,53 +656,85 @@ impl WhisperManager {
     }
 
     /// Get the path to a model file
-    pub fn get_model_path(model: WhisperModel) -> PathBuf {
-        Self::get_models_dir().join(model.filename())
+    pub fn get_model_path(model: &ModelEntry) -> PathBuf {
+        Self::get_models_dir().join(&model.filename)
     }
 
     /// Check if a model is downloaded
-    pub fn is_model_downloaded(model: WhisperModel) -> bool {
+    pub fn is_model_downloaded(model: &ModelEntry) -> bool {
         Self::get_model_path(model).exists()
     }
 
+    /// Whether the configured model is on disk and can be loaded
+    pub fn is_model_available(&self) -> bool {
+        self.model_entry(&self.settings.model)
+            .is_ok_and(Self::is_model_downloaded)
+    }
+
     /// Get the file size of a downloaded model
-    pub fn get_model_file_size(model: WhisperModel) -> Option<u64> {
+    pub fn get_model_file_size(model: &ModelEntry) -> Option<u64> {
         let path = Self::get_model_path(model);
         fs::metadata(&path).ok().map(|m| m.len())
     }
 
+    /// Path of the partial file a download writes to
+    fn get_partial_path(model: &ModelEntry) -> PathBuf {
+        Self::get_model_path(model).with_extension("downloading")
+    }
+
+    /// Models the app knows about
+    pub fn registry(&self) -> &ModelRegistry {
+        &self.registry
+    }
+
+    /// Read the manifests again, e.g. after models were imported
+    pub fn reload_registry(&mut self) {
+        self.registry = ModelRegistry::load();
+    }
+
+    /// Manifest entry of a model
+    pub fn model_entry(&self, id: &str) -> Result<&ModelEntry, WhisperError> {
+        self.registry
+            .get(id)
+            .ok_or_else(|| WhisperError::UnknownModel(id.to_string()))
+    }
+
     /// Get status for all models
     pub fn get_all_model_status(&self) -> Vec<ModelStatus> {
-        vec![
-            WhisperModel::Tiny,
-            WhisperModel::Small,
-            WhisperModel::Medium,
-        ]
-        .into_iter()
-        .map(|model| {
-            let downloaded = Self::is_model_downloaded(model);
-            let file_size = if downloaded {
-                Self::get_model_file_size(model)
-            } else {
-                None
-            };
-            let loaded = self.loaded_model == Some(model);
-            let downloading = self
-                .download_progress
-                .lock()
-                .ok()
-                .and_then(|p| p.as_ref().map(|dp| dp.model == model && !dp.complete))
-                .unwrap_or(false);
-
-            ModelStatus {
-                model,
-                downloaded,
-                file_size,
-                loaded,
-                downloading,
-            }
-        })
-        .collect()
+        self.registry
+            .models()
+            .iter()
+            .map(|model| {
+                let downloaded = Self::is_model_downloaded(model);
+                let file_size = if downloaded {
+                    Self::get_model_file_size(model)
+                } else {
+                    None
+                };
+                let loaded = self.loaded_model.as_deref() == Some(model.id.as_str());
+                let downloading = self
+                    .download_progress
+                    .lock()
+                    .ok()
+                    .and_then(|p| p.as_ref().map(|dp| dp.model == model.id && !dp.complete))
+                    .unwrap_or(false);
+
+                let partial_bytes = fs::metadata(Self::get_partial_path(model))
+                    .ok()
+                    .map(|m| m.len())
+                    .filter(|&len| len > 0);
+
+                ModelStatus {
+                    model: model.id.clone(),
+                    info: model.clone(),
+                    downloaded,
+                    file_size,
+                    loaded,
+                    downloading,
+                    partial_bytes,
+                }
+            })
+            .collect()
     }
 
     /// Update settings
@@ -351,160 +762,48 @@ impl WhisperManager {
         self.download_cancel.store(true, Ordering::Relaxed);
     }
 
-    /// Download a model with progress reporting
-    pub async fn download_model(&self, model: WhisperModel) -> Result<(), WhisperError> {
-        let model_path = Self::get_model_path(model);
-        let temp_path = model_path.with_extension("downloading");
-
-        // Check disk space (need 2x model size for download + extraction)
-        let required_space = model.expected_size() * 2;
-        let available_space =
-            fs2::available_space(Self::get_models_dir()).map_err(|e| WhisperError::IoError(e))?;
-
-        if available_space < required_space {
-            return Err(WhisperError::InsufficientSpace);
-        }
-
-        // Reset cancellation flag
-        self.download_cancel.store(false, Ordering::Relaxed);
-        self.downloaded_bytes.store(0, Ordering::Relaxed);
-
-        // Initialize progress
-        {
-            let mut progress = self.download_progress.lock().unwrap();
-            *progress = Some(DownloadProgress {
-                model,
-                downloaded_bytes: 0,
-                total_bytes: model.expected_size(),
-                speed_bps: 0,
-                complete: false,
-                error: None,
-            });
-        }
-
-        let client = Client::new();
-        let response = client
-            .get(model.download_url())
-            .send()
-            .await
-            .map_err(|e| WhisperError::DownloadError(e.to_string()))?;
-
-        let total_size = response.content_length().unwrap_or(model.expected_size());
-
-        // Update total size in progress
-        {
-            let mut progress = self.download_progress.lock().unwrap();
-            if let Some(ref mut p) = *progress {
-                p.total_bytes = total_size;
-            }
-        }
-
-        // Create temp file
-        let mut file = fs::File::create(&temp_path)?;
-        let mut hasher = Sha256::new();
-
-        let mut stream = response.bytes_stream();
-        let start_time = std::time::Instant::now();
-        let mut last_update = start_time;
-
-        while let Some(chunk_result) = stream.next().await {
-            // Check for cancellation
-            if self.download_cancel.load(Ordering::Relaxed) {
-                // Clean up temp file
-                let _ = fs::remove_file(&temp_path);
-                {
-                    let mut progress = self.download_progress.lock().unwrap();
-                    *progress = None;
-                }
-                return Err(WhisperError::DownloadCancelled);
-            }
-
-            let chunk = chunk_result.map_err(|e| WhisperError::DownloadError(e.to_string()))?;
-
-            // Write to file and update hash
-            file.write_all(&chunk)?;
-            hasher.update(&chunk);
-
-            // Update progress
-            let downloaded = self
-                .downloaded_bytes
-                .fetch_add(chunk.len() as u64, Ordering::Relaxed)
-                + chunk.len() as u64;
-
-            // Update progress struct periodically (every 100ms)
-            if last_update.elapsed().as_millis() >= 100 {
-                let elapsed = start_time.elapsed().as_secs_f64();
-                let speed = if elapsed > 0.0 {
-                    (downloaded as f64 / elapsed) as u64
-                } else {
-                    0
-                };
-
-                {
-                    let mut progress = self.download_progress.lock().unwrap();
-                    if let Some(ref mut p) = *progress {
-                        p.downloaded_bytes = downloaded;
-                        p.speed_bps = speed;
-                    }
-                }
-                last_update = std::time::Instant::now();
-            }
-        }
-
-        file.flush()?;
-        drop(file);
-
-        // Log the hash for verification (hash check disabled - placeholders were never verified)
-        let hash = hasher.finalize();
-        let hash_hex = format!("{:x}", hash);
-        log::info!(
-            "Model {} downloaded with SHA256: {} (expected prefix: {})",
-            model.name(),
-            &hash_hex[..16],
-            model.expected_hash_prefix()
-        );
-
-        // Move temp file to final location
-        fs::rename(&temp_path, &model_path)?;
-
-        // Mark download complete
-        {
-            let mut progress = self.download_progress.lock().unwrap();
-            if let Some(ref mut p) = *progress {
-                p.complete = true;
-                p.downloaded_bytes = total_size;
-            }
+    /// Downloader that runs without holding the manager
+    ///
+    /// It shares the progress and the cancel flag with this manager, so
+    /// polling and cancelling work while the download runs.
+    pub fn downloader(&self) -> ModelDownloader {
+        ModelDownloader {
+            registry: self.registry.clone(),
+            mirrors: self.settings.download_mirrors.clone(),
+            progress: Arc::clone(&self.download_progress),
+            cancel: Arc::clone(&self.download_cancel),
         }
-
-        log::info!("Model {} downloaded successfully", model.name());
-        Ok(())
     }
 
     /// Delete a downloaded model
-    pub fn delete_model(model: WhisperModel) -> Result<(), WhisperError> {
+    pub fn delete_model(model: &ModelEntry) -> Result<(), WhisperError> {
         let path = Self::get_model_path(model);
         if path.exists() {
             fs::remove_file(&path)?;
-            log::info!("Model {} deleted", model.name());
+            log::info!("Model {} deleted", model.id);
+        }
+        let partial = Self::get_partial_path(model);
+        if partial.exists() {
+            fs::remove_file(&partial)?;
         }
         Ok(())
     }
 
     /// Load the configured model into memory
     pub fn load_model(&mut self) -> Result<(), WhisperError> {
-        let model = self.settings.model;
+        let model = self.model_entry(&self.settings.model)?.clone();
 
         // Check if already loaded
-        if self.loaded_model == Some(model) && self.context.is_some() {
+        if self.loaded_model.as_deref() == Some(model.id.as_str()) && self.context.is_some() {
             return Ok(());
         }
 
         // Check if model is downloaded
-        if !Self::is_model_downloaded(model) {
-            return Err(WhisperError::ModelNotDownloaded(model.name().to_string()));
+        if !Self::is_model_downloaded(&model) {
+            return Err(WhisperError::ModelNotDownloaded(model.id));
         }
 
-        let model_path = Self::get_model_path(model);
+        let model_path = Self::get_model_path(&model);
         log::info!("Loading Whisper model from: {:?}", model_path);
 
         // Create context parameters
@@ -515,9 +814,8 @@ impl WhisperManager {
             .map_err(|e| WhisperError::ModelLoadError(format!("{:?}", e)))?;
 
         self.context = Some(ctx);
-        self.loaded_model = Some(model);
-
-        log::info!("Whisper model {} loaded successfully", model.name());
+        log::info!("Whisper model {} loaded successfully", model.id);
+        self.loaded_model = Some(model.id);
         Ok(())
     }
 
@@ -534,7 +832,15 @@ impl WhisperManager {
     }
 
     /// Transcribe a WAV file
-    pub fn transcribe(&mut self, wav_path: &str) -> Result<TranscriptionResult, WhisperError> {
+    ///
+    /// With `speech_segments` from the recorder's VAD only the speech regions
+    /// are decoded; segment timestamps still refer to the original file. An
+    /// empty list falls back to the whole file rather than dropping the take.
+    pub fn transcribe(
+        &mut self,
+        wav_path: &str,
+        speech_segments: Option<&[SpeechSegment]>,
+    ) -> Result<TranscriptionResult, WhisperError> {
         let start_time = std::time::Instant::now();
 
         // Load model if not already loaded
@@ -547,8 +853,9 @@ impl WhisperManager {
             .as_mut()
             .ok_or_else(|| WhisperError::ModelLoadError("Context not available".to_string()))?;
 
-        // Read the WAV file
-        let reader = hound::WavReader::open(wav_path)
+        // Read the WAV file (decrypted in memory if it is sealed)
+        let data = encryption::read(Path::new(wav_path))?;
+        let reader = hound::WavReader::new(std::io::Cursor::new(data))
             .map_err(|e| WhisperError::InvalidAudioFile(e.to_string()))?;
 
         let spec = reader.spec();
@@ -583,6 +890,22 @@ impl WhisperManager {
             ));
         }
 
+        // Skip the non-speech parts of the recording
+        let total_ms = samples.len() as u64 * 1000 / spec.sample_rate as u64;
+        let regions = speech_regions(speech_segments.unwrap_or_default(), total_ms);
+        let (samples, spans) = if regions.is_empty() {
+            (samples, Vec::new())
+        } else {
+            let (compact, spans) = compact_to_speech(&samples, spec.sample_rate, &regions);
+            log::info!(
+                "Transcribing {} speech regions, {}ms of {}ms",
+                spans.len(),
+                compact.len() as u64 * 1000 / spec.sample_rate as u64,
+                total_ms
+            );
+            (compact, spans)
+        };
+
         log::info!("Transcribing {} samples", samples.len());
 
         // Create a state for this transcription
@@ -590,83 +913,476 @@ impl WhisperManager {
             .create_state()
             .map_err(|e| WhisperError::TranscriptionError(format!("{:?}", e)))?;
 
-        // Configure transcription parameters
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
-
-        // Set language
-        if let Some(lang_code) = self.settings.language.code() {
-            params.set_language(Some(lang_code));
-        } else {
-            params.set_language(None); // Auto-detect
-        }
-
-        // Disable translation (keep original language)
-        params.set_translate(false);
-
-        // Enable timestamps
-        params.set_token_timestamps(true);
+        // Auto: detect the language first, so it can be reported and restricted
+        let language = self.fixed_language();
+        let detection = match language {
+            Some(_) => None,
+            None => detect_language(&mut state, &samples, &self.settings.auto_languages)
+                .unwrap_or_else(|e| {
+                    log::warn!("Language detection failed: {}", e);
+                    None
+                }),
+        };
 
         // Run the transcription
+        let params = transcription_params(language.or(detection.as_ref().map(|d| d.code)));
         state
             .full(params, &samples)
             .map_err(|e| WhisperError::TranscriptionError(format!("{:?}", e)))?;
 
         // Extract results
-        let num_segments = state
-            .full_n_segments()
+        let mut segments = read_segments(&state)?;
+        if !spans.is_empty() {
+            for segment in &mut segments {
+                segment.start_ms = to_original_ms(segment.start_ms, &spans);
+                segment.end_ms = to_original_ms(segment.end_ms, &spans);
+            }
+        }
+        let processing_time_ms = start_time.elapsed().as_millis() as u64;
+
+        log::info!(
+            "Transcription complete: {} segments, {}ms processing time",
+            segments.len(),
+            processing_time_ms
+        );
+
+        Ok(TranscriptionResult::from_segments(
+            segments,
+            language,
+            detection.as_ref(),
+            processing_time_ms,
+        ))
+    }
+
+    /// Language to decode in: the configured one, or the only language of
+    /// a single-language model such as the `.en` ones
+    fn fixed_language(&self) -> Option<&'static str> {
+        whisper_language(&self.settings.language).or_else(|| {
+            let entry = self.registry.get(&self.settings.model)?;
+            match entry.languages.as_slice() {
+                [only] => whisper_language(only),
+                _ => None,
+            }
+        })
+    }
+
+    /// Prepare a streaming transcription of a take that is about to record
+    ///
+    /// Loads the model if needed and creates the `WhisperState` that every
+    /// window of the take reuses.
+    pub fn start_streaming(&mut self) -> Result<StreamingTranscriber<StateDecoder>, WhisperError> {
+        if self.context.is_none() {
+            self.load_model()?;
+        }
+        let ctx = self
+            .context
+            .as_ref()
+            .ok_or_else(|| WhisperError::ModelLoadError("Context not available".to_string()))?;
+        let state = ctx
+            .create_state()
             .map_err(|e| WhisperError::TranscriptionError(format!("{:?}", e)))?;
 
-        let mut full_text = String::new();
-        let mut segments = Vec::new();
+        let decoder = StateDecoder {
+            state,
+            language: self.fixed_language(),
+            auto_languages: self.settings.auto_languages.clone(),
+            detection: None,
+            detection_settled: false,
+        };
+        Ok(StreamingTranscriber::new(decoder))
+    }
+}
+
+/// Decoding parameters shared by file and streaming transcription
+fn transcription_params(language: Option<&'static str>) -> FullParams<'static, 'static> {
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
 
-        for i in 0..num_segments {
-            let segment_text = state
-                .full_get_segment_text(i)
-                .map_err(|e| WhisperError::TranscriptionError(format!("{:?}", e)))?;
+    // Set language (None: auto-detect)
+    params.set_language(language);
 
-            let start_ms = state
-                .full_get_segment_t0(i)
-                .map_err(|e| WhisperError::TranscriptionError(format!("{:?}", e)))?
-                * 10; // Convert to ms
+    // Disable translation (keep original language)
+    params.set_translate(false);
+
+    // Enable timestamps
+    params.set_token_timestamps(true);
+
+    params
+}
+
+/// Segments of the last run on `state`, timestamps relative to its input
+fn read_segments(state: &WhisperState) -> Result<Vec<TranscriptionSegment>, WhisperError> {
+    let whisper_err = |e| WhisperError::TranscriptionError(format!("{:?}", e));
+    let num_segments = state.full_n_segments().map_err(whisper_err)?;
+
+    let mut segments = Vec::with_capacity(num_segments.max(0) as usize);
+    for i in 0..num_segments {
+        segments.push(TranscriptionSegment {
+            text: state.full_get_segment_text(i).map_err(whisper_err)?,
+            // Whisper counts in 10ms steps
+            start_ms: state.full_get_segment_t0(i).map_err(whisper_err)? * 10,
+            end_ms: state.full_get_segment_t1(i).map_err(whisper_err)? * 10,
+            speaker: None,
+        });
+    }
+    Ok(segments)
+}
+
+/// Full text of a transcription from its segments
+fn join_segments(segments: &[TranscriptionSegment]) -> String {
+    let mut text = String::new();
+    for segment in segments {
+        text.push_str(&segment.text);
+        text.push(' ');
+    }
+    text.trim().to_string()
+}
+
+/// Whisper skips input shorter than this, so shorter windows are padded with silence
+const WHISPER_MIN_INPUT_MS: u64 = 1000;
+/// New audio needed before a live window is decoded again
+const STREAMING_STEP_MS: u64 = 1000;
+/// Segments ending this close to the live edge may still change
+const STREAMING_STABLE_MARGIN_MS: u64 = 1000;
+/// Longest window decoded while recording; beyond it all but the last segment is committed
+const STREAMING_MAX_WINDOW_MS: u64 = 20_000;
+/// How often the streaming session looks for new audio
+const STREAMING_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+fn samples_to_ms(samples: usize) -> u64 {
+    samples as u64 * 1000 / WHISPER_SAMPLE_RATE as u64
+}
+
+fn ms_to_samples(ms: u64) -> usize {
+    (ms * WHISPER_SAMPLE_RATE as u64 / 1000) as usize
+}
+
+/// Transcript of a take that is still recording
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PartialTranscript {
+    /// Text that will not change anymore
+    pub stable_text: String,
+    /// Latest guess for the most recent audio
+    pub unstable_text: String,
+    /// Audio covered so far in milliseconds
+    pub audio_ms: u64,
+}
 
-            let end_ms = state
-                .full_get_segment_t1(i)
-                .map_err(|e| WhisperError::TranscriptionError(format!("{:?}", e)))?
-                * 10; // Convert to ms
+/// Runs Whisper on one window of audio
+pub trait WindowDecoder {
+    /// Segments of `samples` (16kHz mono), timestamps relative to the window start
+    fn decode(&mut self, samples: &[f32]) -> Result<Vec<TranscriptionSegment>, WhisperError>;
 
-            full_text.push_str(&segment_text);
-            full_text.push(' ');
+    /// Language set for the take (None: auto-detect)
+    fn language(&self) -> Option<&'static str>;
 
-            segments.push(TranscriptionSegment {
-                text: segment_text,
-                start_ms,
-                end_ms,
-            });
+    /// What auto-detection found so far
+    fn detection(&self) -> Option<&LanguageDetection> {
+        None
+    }
+}
+
+/// Decoder that reuses one `WhisperState` for every window of a take
+pub struct StateDecoder {
+    state: WhisperState,
+    language: Option<&'static str>,
+    auto_languages: Vec<String>,
+    detection: Option<LanguageDetection>,
+    /// Detection ran on enough audio, later windows keep its language
+    detection_settled: bool,
+}
+
+impl WindowDecoder for StateDecoder {
+    fn decode(&mut self, samples: &[f32]) -> Result<Vec<TranscriptionSegment>, WhisperError> {
+        if self.language.is_none() && !self.detection_settled {
+            match detect_language(&mut self.state, samples, &self.auto_languages) {
+                Ok(detection) => self.detection = detection,
+                Err(e) => log::warn!("Language detection failed: {}", e),
+            }
+            self.detection_settled = samples.len() >= ms_to_samples(LANGUAGE_DETECTION_MIN_MS);
+        }
+
+        let language = self.language.or(self.detection.as_ref().map(|d| d.code));
+        let mut params = transcription_params(language);
+        // Committed text is final, each window is decoded on its own
+        params.set_no_context(true);
+        // A window is decoded every second, keep stdout quiet
+        params.set_print_progress(false);
+
+        self.state
+            .full(params, samples)
+            .map_err(|e| WhisperError::TranscriptionError(format!("{:?}", e)))?;
+        read_segments(&self.state)
+    }
+
+    fn language(&self) -> Option<&'static str> {
+        self.language
+    }
+
+    fn detection(&self) -> Option<&LanguageDetection> {
+        self.detection.as_ref()
+    }
+}
+
+/// Transcribes a take in sliding windows while it records
+///
+/// Every step decodes the audio after the last committed segment. Segments
+/// that two decodes in a row agree on are committed and cut from the window,
+/// so the text shown while recording only grows at its stable end.
+pub struct StreamingTranscriber<D> {
+    decoder: D,
+    /// Final segments, timestamps from the start of the take
+    committed: Vec<TranscriptionSegment>,
+    /// Uncommitted segments of the last decode
+    hypothesis: Vec<TranscriptionSegment>,
+    /// Audio after the last committed segment
+    window: Vec<f32>,
+    /// Position of the window in the take
+    window_start_ms: u64,
+    /// Window length at the last decode
+    decoded_len: usize,
+}
+
+impl<D: WindowDecoder> StreamingTranscriber<D> {
+    pub fn new(decoder: D) -> Self {
+        Self {
+            decoder,
+            committed: Vec::new(),
+            hypothesis: Vec::new(),
+            window: Vec::new(),
+            window_start_ms: 0,
+            decoded_len: 0,
+        }
+    }
+
+    /// Append recorded audio (16kHz mono)
+    pub fn push(&mut self, samples: &[f32]) {
+        self.window.extend_from_slice(samples);
+    }
+
+    /// Whether enough audio arrived since the last decode
+    pub fn is_due(&self) -> bool {
+        samples_to_ms(self.window.len() - self.decoded_len) >= STREAMING_STEP_MS
+    }
+
+    /// Decode the current window and commit what has settled
+    pub fn step(&mut self) -> Result<PartialTranscript, WhisperError> {
+        let segments = self.decode_window()?;
+        let window_ms = samples_to_ms(self.window.len());
+        let live_edge =
+            (self.window_start_ms + window_ms).saturating_sub(STREAMING_STABLE_MARGIN_MS);
+
+        // Longest prefix the previous decode agrees on, away from the live edge
+        let mut stable = segments
+            .iter()
+            .zip(&self.hypothesis)
+            .take_while(|(new, old)| {
+                new.text.trim() == old.text.trim() && new.end_ms.max(0) as u64 <= live_edge
+            })
+            .count();
+        if window_ms >= STREAMING_MAX_WINDOW_MS {
+            // Keep the window short enough for a single Whisper pass
+            stable = stable.max(segments.len().saturating_sub(1));
+            if stable == 0 {
+                stable = segments.len();
+            }
         }
 
-        // Detect language (simplified - use first segment's language if available)
-        let detected_lang = if self.settings.language == WhisperLanguage::Auto {
-            // For now, default to "de" if auto-detect
-            // In a real implementation, we'd use whisper's language detection
-            "de".to_string()
+        let mut segments = segments;
+        self.hypothesis = segments.split_off(stable);
+        let cut_ms = match segments.last() {
+            Some(last) => last.end_ms.max(0) as u64,
+            // Nothing said for a whole window: drop all but the live edge
+            None if window_ms >= STREAMING_MAX_WINDOW_MS => live_edge,
+            None => self.window_start_ms,
+        };
+        self.committed.extend(segments);
+        self.advance_to(cut_ms);
+        self.decoded_len = self.window.len();
+
+        Ok(PartialTranscript {
+            stable_text: join_segments(&self.committed),
+            unstable_text: join_segments(&self.hypothesis),
+            audio_ms: self.window_start_ms + samples_to_ms(self.window.len()),
+        })
+    }
+
+    /// Decode the rest of the take and return its full transcription
+    pub fn finish(mut self) -> Result<TranscriptionResult, WhisperError> {
+        let tail = if self.window.is_empty() {
+            std::mem::take(&mut self.hypothesis)
         } else {
-            self.settings.language.code().unwrap_or("auto").to_string()
+            self.decode_window()?
         };
+        self.committed.extend(tail);
+
+        Ok(TranscriptionResult::from_segments(
+            self.committed,
+            self.decoder.language(),
+            self.decoder.detection(),
+            0,
+        ))
+    }
 
-        let processing_time_ms = start_time.elapsed().as_millis() as u64;
+    /// Decode the window, timestamps from the start of the take
+    fn decode_window(&mut self) -> Result<Vec<TranscriptionSegment>, WhisperError> {
+        let min_len = ms_to_samples(WHISPER_MIN_INPUT_MS);
+        let segments = if self.window.len() < min_len {
+            let mut padded = self.window.clone();
+            padded.resize(min_len, 0.0);
+            self.decoder.decode(&padded)?
+        } else {
+            self.decoder.decode(&self.window)?
+        };
 
-        log::info!(
-            "Transcription complete: {} segments, {}ms processing time",
-            segments.len(),
-            processing_time_ms
-        );
+        let offset = self.window_start_ms as i64;
+        Ok(segments
+            .into_iter()
+            .filter(|segment| !segment.text.trim().is_empty())
+            .map(|segment| TranscriptionSegment {
+                start_ms: segment.start_ms + offset,
+                end_ms: segment.end_ms + offset,
+                ..segment
+            })
+            .collect())
+    }
 
-        Ok(TranscriptionResult {
-            text: full_text.trim().to_string(),
-            language: detected_lang,
-            segments,
-            processing_time_ms,
-        })
+    /// Drop the window's audio before `ms` (take time)
+    fn advance_to(&mut self, ms: u64) {
+        if ms <= self.window_start_ms {
+            return;
+        }
+        let cut = ms_to_samples(ms - self.window_start_ms).min(self.window.len());
+        self.window.drain(..cut);
+        self.window_start_ms += samples_to_ms(cut);
+    }
+}
+
+/// Receives partial transcripts while a take records
+pub type PartialHandler = Arc<dyn Fn(PartialTranscript) + Send + Sync>;
+
+/// Receives the error that ended live transcription before the take did
+pub type StreamingErrorHandler = Arc<dyn Fn(&WhisperError) + Send + Sync>;
+
+/// Streaming transcription running alongside a take
+pub struct StreamingSession {
+    stop: Arc<AtomicBool>,
+    handle: Option<thread::JoinHandle<Result<TranscriptionResult, WhisperError>>>,
+    /// Recording the session belongs to, known once the take is stopped
+    file_path: Option<String>,
+}
+
+impl StreamingSession {
+    /// Transcribe `audio` on a background thread as the recorder fills it
+    ///
+    /// `open` runs on that thread so loading the model does not delay the take.
+    /// If opening or decoding fails, `on_error` is told and the thread keeps
+    /// emptying `audio` until the take stops, so the tap never piles up.
+    pub fn spawn<D, F>(
+        open: F,
+        audio: LiveAudio,
+        on_partial: PartialHandler,
+        on_error: StreamingErrorHandler,
+    ) -> Self
+    where
+        D: WindowDecoder,
+        F: FnOnce() -> Result<StreamingTranscriber<D>, WhisperError> + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&stop);
+
+        let handle = thread::spawn(move || {
+            let mut transcriber = match follow_take(open, &audio, &stop_flag, &on_partial) {
+                Ok(transcriber) => transcriber,
+                Err(e) => {
+                    on_error(&e);
+                    while !stop_flag.load(Ordering::Acquire) {
+                        if let Ok(mut pending) = audio.lock() {
+                            pending.clear();
+                        }
+                        thread::sleep(STREAMING_POLL_INTERVAL);
+                    }
+                    return Err(e);
+                }
+            };
+
+            let start_time = Instant::now();
+            let mut result = transcriber.finish()?;
+            result.processing_time_ms = start_time.elapsed().as_millis() as u64;
+            Ok(result)
+        });
+
+        Self {
+            stop,
+            handle: Some(handle),
+            file_path: None,
+        }
+    }
+
+    /// The take has stopped; the rest is decoded in the background
+    pub fn stop(&mut self, file_path: &str) {
+        self.file_path = Some(file_path.to_string());
+        self.stop.store(true, Ordering::Release);
+    }
+
+    /// Whether this session transcribed the recording at `file_path`
+    pub fn is_for(&self, file_path: &str) -> bool {
+        self.file_path.as_deref() == Some(file_path)
+    }
+
+    /// Wait for the transcription of the whole take
+    pub fn finish(mut self) -> Result<TranscriptionResult, WhisperError> {
+        self.stop.store(true, Ordering::Release);
+        match self.handle.take().map(|handle| handle.join()) {
+            Some(Ok(result)) => result,
+            _ => Err(WhisperError::TranscriptionError(
+                "Streaming transcription thread failed".to_string(),
+            )),
+        }
+    }
+}
+
+/// Feed the take to a new transcriber until it stops, showing partial text
+fn follow_take<D, F>(
+    open: F,
+    audio: &LiveAudio,
+    stop: &AtomicBool,
+    on_partial: &PartialHandler,
+) -> Result<StreamingTranscriber<D>, WhisperError>
+where
+    D: WindowDecoder,
+    F: FnOnce() -> Result<StreamingTranscriber<D>, WhisperError>,
+{
+    let mut transcriber = open()?;
+    let mut shown = PartialTranscript::default();
+    loop {
+        // Checked before draining: once set, the recorder has written its last sample
+        let stopped = stop.load(Ordering::Acquire);
+        if let Ok(mut pending) = audio.lock() {
+            transcriber.push(&pending);
+            pending.clear();
+        }
+        if stopped {
+            return Ok(transcriber);
+        }
+
+        if transcriber.is_due() {
+            let partial = transcriber.step()?;
+            if partial.stable_text != shown.stable_text
+                || partial.unstable_text != shown.unstable_text
+            {
+                on_partial(partial.clone());
+            }
+            shown = partial;
+        } else {
+            thread::sleep(STREAMING_POLL_INTERVAL);
+        }
+    }
+}
+
+impl Drop for StreamingSession {
+    fn drop(&mut self) {
+        // An abandoned session must not keep decoding forever
+        self.stop.store(true, Ordering::Release);
     }
 }
 
@@ -676,9 +1392,348 @@ impl Default for WhisperManager {
     }
 }
 
+/// Attempts per download URL before the next one is tried
+const DOWNLOAD_ATTEMPTS: u32 = 5;
+
+/// Wait before the first retry, doubled for every further one
+const DOWNLOAD_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// Upper bound for the wait between retries
+const DOWNLOAD_MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// A connection that delivers nothing for this long counts as broken
+const DOWNLOAD_STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often progress is pushed while bytes arrive
+const DOWNLOAD_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Wait before retry number `retry` (1 for the first one)
+fn retry_delay(retry: u32) -> Duration {
+    DOWNLOAD_RETRY_DELAY
+        .saturating_mul(1 << retry.saturating_sub(1).min(16))
+        .min(DOWNLOAD_MAX_RETRY_DELAY)
+}
+
+/// Length of a partial download, 0 without one
+fn partial_len(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// First byte of a `Content-Range: bytes <first>-<last>/<total>` header
+fn content_range_start(value: &str) -> Option<u64> {
+    value
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Why one attempt to fetch a model file stopped
+enum FetchError {
+    /// Connection dropped or the server had a problem, worth another try
+    Transient(String),
+    /// The server does not have the file, the next URL may
+    Unavailable(String),
+    /// Cancelled or the file could not be written
+    Fatal(WhisperError),
+}
+
+/// Downloads models outside the `WhisperManager` lock
+///
+/// Keeps the `.downloading` file of a broken download and resumes it with
+/// an HTTP range request. A file only replaces the model once it matches the
+/// manifest's checksum.
+pub struct ModelDownloader {
+    registry: ModelRegistry,
+    mirrors: Vec<String>,
+    progress: Arc<Mutex<Option<DownloadProgress>>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl ModelDownloader {
+    /// Download a model, trying the mirrors first and the manifest URL last
+    ///
+    /// `on_progress` receives every progress update, including retries and
+    /// the final state.
+    pub async fn download(
+        &self,
+        model: &ModelEntry,
+        on_progress: impl Fn(&DownloadProgress),
+    ) -> Result<(), WhisperError> {
+        if model.custom {
+            return Err(WhisperError::DownloadError(format!(
+                "{} was imported from a file and has no download",
+                model.id
+            )));
+        }
+        let checksum =
+            Checksum::of(model).ok_or_else(|| WhisperError::MissingChecksum(model.id.clone()))?;
+        let model_path = WhisperManager::get_model_path(model);
+        let temp_path = WhisperManager::get_partial_path(model);
+        let partial = partial_len(&temp_path);
+
+        // Check disk space (need 2x model size for download + extraction)
+        let required_space = (model.size_bytes() * 2).saturating_sub(partial);
+        let available_space = fs2::available_space(WhisperManager::get_models_dir())?;
+        if available_space < required_space {
+            return Err(WhisperError::InsufficientSpace);
+        }
+
+        self.cancel.store(false, Ordering::Relaxed);
+        let mut progress = DownloadProgress {
+            model: model.id.clone(),
+            downloaded_bytes: partial,
+            total_bytes: model.size_bytes(),
+            speed_bps: 0,
+            complete: false,
+            error: None,
+            attempt: 1,
+            verifying: false,
+        };
+        self.report(&progress, &on_progress);
+
+        match self
+            .fetch_verified(model, checksum, &temp_path, &mut progress, &on_progress)
+            .await
+        {
+            Ok(()) => {
+                fs::rename(&temp_path, &model_path)?;
+                progress.complete = true;
+                progress.verifying = false;
+                progress.downloaded_bytes = progress.total_bytes;
+                self.report(&progress, &on_progress);
+                log::info!("Model {} downloaded and verified", model.id);
+                Ok(())
+            }
+            Err(WhisperError::DownloadCancelled) => {
+                // The partial file stays, the next download resumes it
+                if let Ok(mut current) = self.progress.lock() {
+                    *current = None;
+                }
+                Err(WhisperError::DownloadCancelled)
+            }
+            Err(e) => {
+                progress.verifying = false;
+                progress.error = Some(e.to_string());
+                self.report(&progress, &on_progress);
+                Err(e)
+            }
+        }
+    }
+
+    /// Fetch from each URL with retries until a file matches the checksum
+    async fn fetch_verified(
+        &self,
+        model: &ModelEntry,
+        checksum: Checksum<'_>,
+        temp_path: &Path,
+        progress: &mut DownloadProgress,
+        on_progress: &impl Fn(&DownloadProgress),
+    ) -> Result<(), WhisperError> {
+        let client = Client::builder()
+            .connect_timeout(DOWNLOAD_STALL_TIMEOUT)
+            .build()
+            .map_err(|e| WhisperError::DownloadError(e.to_string()))?;
+        let mut last_error = WhisperError::DownloadError("No download URL".to_string());
+
+        for url in self.registry.download_urls(model, &self.mirrors) {
+            // A bad file is fetched once more from zero before the next URL is tried,
+            // the resumed part may have been stale
+            let mut restarted = false;
+            let mut attempt = 0;
+            while attempt < DOWNLOAD_ATTEMPTS {
+                attempt += 1;
+                if attempt > 1 {
+                    progress.attempt = attempt;
+                    progress.speed_bps = 0;
+                    self.report(progress, on_progress);
+                    self.wait_before_retry(retry_delay(attempt - 1)).await?;
+                }
+
+                match self
+                    .fetch(&client, &url, temp_path, model, progress, on_progress)
+                    .await
+                {
+                    Ok(()) => {}
+                    Err(FetchError::Transient(e)) => {
+                        log::warn!(
+                            "Download of {} from {} broke off (attempt {}/{}): {}",
+                            model.id,
+                            url,
+                            attempt,
+                            DOWNLOAD_ATTEMPTS,
+                            e
+                        );
+                        last_error = WhisperError::DownloadError(e);
+                        continue;
+                    }
+                    Err(FetchError::Unavailable(e)) => {
+                        log::warn!("{} not available from {}: {}", model.id, url, e);
+                        last_error = WhisperError::DownloadError(e);
+                        break;
+                    }
+                    Err(FetchError::Fatal(e)) => return Err(e),
+                }
+
+                progress.verifying = true;
+                self.report(progress, on_progress);
+                if checksum.matches_file(temp_path)? {
+                    return Ok(());
+                }
+
+                // A corrupt file must not be resumed
+                log::warn!("Checksum mismatch for {} downloaded from {}", model.id, url);
+                let _ = fs::remove_file(temp_path);
+                progress.verifying = false;
+                progress.downloaded_bytes = 0;
+                last_error = WhisperError::HashVerificationFailed;
+                if restarted {
+                    break;
+                }
+                restarted = true;
+                attempt = 0;
+                progress.attempt = 1;
+            }
+            progress.attempt = 1;
+        }
+
+        Err(last_error)
+    }
+
+    /// One request, appending to the partial file when the server resumes it
+    async fn fetch(
+        &self,
+        client: &Client,
+        url: &str,
+        temp_path: &Path,
+        model: &ModelEntry,
+        progress: &mut DownloadProgress,
+        on_progress: &impl Fn(&DownloadProgress),
+    ) -> Result<(), FetchError> {
+        let offset = partial_len(temp_path);
+        let mut request = client.get(url);
+        if offset > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| FetchError::Transient(e.to_string()))?;
+
+        let status = response.status();
+        if offset > 0 && status == StatusCode::RANGE_NOT_SATISFIABLE {
+            // The partial file already holds everything, the checksum decides
+            return Ok(());
+        }
+        if status.is_server_error()
+            || status == StatusCode::TOO_MANY_REQUESTS
+            || status == StatusCode::REQUEST_TIMEOUT
+        {
+            return Err(FetchError::Transient(format!("HTTP {}", status)));
+        }
+        if !status.is_success() {
+            return Err(FetchError::Unavailable(format!("HTTP {}", status)));
+        }
+
+        // Servers without range support send the whole file again
+        let resumed = status == StatusCode::PARTIAL_CONTENT;
+        if resumed {
+            let range_start = response
+                .headers()
+                .get(reqwest::header::CONTENT_RANGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(content_range_start);
+            if range_start != Some(offset) {
+                // Appending would corrupt the file, the next attempt starts over
+                let _ = fs::remove_file(temp_path);
+                return Err(FetchError::Transient(format!(
+                    "Server resumed at {:?} instead of byte {}",
+                    range_start, offset
+                )));
+            }
+        }
+        let start = if resumed { offset } else { 0 };
+        let file = if resumed {
+            log::info!("Resuming download of {} at {} bytes", model.id, offset);
+            fs::OpenOptions::new().append(true).open(temp_path)
+        } else {
+            fs::File::create(temp_path)
+        };
+        let mut file = file.map_err(|e| FetchError::Fatal(e.into()))?;
+
+        let expected_end = response.content_length().map(|len| start + len);
+        progress.downloaded_bytes = start;
+        progress.total_bytes = expected_end.unwrap_or(model.size_bytes());
+        self.report(progress, on_progress);
+
+        let mut stream = response.bytes_stream();
+        let started = Instant::now();
+        let mut last_update = started;
+
+        loop {
+            let next = tokio::time::timeout(DOWNLOAD_STALL_TIMEOUT, stream.next())
+                .await
+                .map_err(|_| FetchError::Transient("Connection stalled".to_string()))?;
+            let Some(chunk) = next else { break };
+
+            if self.cancel.load(Ordering::Relaxed) {
+                return Err(FetchError::Fatal(WhisperError::DownloadCancelled));
+            }
+
+            let chunk = chunk.map_err(|e| FetchError::Transient(e.to_string()))?;
+            file.write_all(&chunk)
+                .map_err(|e| FetchError::Fatal(e.into()))?;
+            progress.downloaded_bytes += chunk.len() as u64;
+
+            if last_update.elapsed() >= DOWNLOAD_PROGRESS_INTERVAL {
+                let elapsed = started.elapsed().as_secs_f64();
+                progress.speed_bps = ((progress.downloaded_bytes - start) as f64 / elapsed) as u64;
+                self.report(progress, on_progress);
+                last_update = Instant::now();
+            }
+        }
+        file.flush().map_err(|e| FetchError::Fatal(e.into()))?;
+
+        // A connection closed early ends the stream without an error
+        match expected_end {
+            Some(end) if progress.downloaded_bytes < end => Err(FetchError::Transient(format!(
+                "Connection closed after {} of {} bytes",
+                progress.downloaded_bytes, end
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sleep before a retry, returning early when the download is cancelled
+    async fn wait_before_retry(&self, delay: Duration) -> Result<(), WhisperError> {
+        let deadline = Instant::now() + delay;
+        loop {
+            if self.cancel.load(Ordering::Relaxed) {
+                return Err(WhisperError::DownloadCancelled);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(());
+            }
+            tokio::time::sleep((deadline - now).min(Duration::from_millis(250))).await;
+        }
+    }
+
+    /// Store progress for polling and push it to the caller
+    fn report(&self, progress: &DownloadProgress, on_progress: &impl Fn(&DownloadProgress)) {
+        if let Ok(mut current) = self.progress.lock() {
+            *current = Some(progress.clone());
+        }
+        on_progress(progress);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::AtomicUsize;
 
     #[test]
     fn test_model_paths() {
@@ -689,17 +1744,420 @@ mod tests {
 
     #[test]
     fn test_model_urls() {
-        assert!(WhisperModel::Tiny.download_url().starts_with("https://"));
-        assert!(WhisperModel::Small.download_url().contains("small"));
-        assert!(WhisperModel::Medium.download_url().contains("medium"));
+        let registry = ModelRegistry::bundled();
+        for id in ["tiny", "small", "medium"] {
+            let url = registry.download_url(registry.get(id).unwrap());
+            assert!(url.starts_with("https://"));
+            assert!(url.contains(id));
+        }
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_up_to_a_limit() {
+        assert_eq!(retry_delay(1), DOWNLOAD_RETRY_DELAY);
+        assert_eq!(retry_delay(2), DOWNLOAD_RETRY_DELAY * 2);
+        assert_eq!(retry_delay(3), DOWNLOAD_RETRY_DELAY * 4);
+        assert_eq!(retry_delay(30), DOWNLOAD_MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_content_range_start() {
+        assert_eq!(content_range_start("bytes 1024-2047/2048"), Some(1024));
+        assert_eq!(content_range_start("bytes 0-99/*"), Some(0));
+        assert_eq!(content_range_start("bytes */2048"), None);
+        assert_eq!(content_range_start("items 1-2/3"), None);
+    }
+
+    #[test]
+    fn test_settings_reject_mirrors_that_are_not_http() {
+        let registry = ModelRegistry::bundled();
+        let mut settings = WhisperSettings {
+            download_mirrors: vec!["http://192.168.1.20:8000/models/".to_string()],
+            ..Default::default()
+        };
+        assert!(settings.validate(&registry).is_ok());
+
+        settings.download_mirrors = vec!["ftp://mirror.example/models".to_string()];
+        assert!(matches!(
+            settings.validate(&registry),
+            Err(WhisperError::InvalidMirror(_))
+        ));
+        settings.download_mirrors = vec!["models.intern".to_string()];
+        assert!(settings.validate(&registry).is_err());
+    }
+
+    #[test]
+    fn test_speech_regions_merge_short_pauses() {
+        let segments = [
+            SpeechSegment {
+                start_ms: 1000,
+                end_ms: 2000,
+            },
+            SpeechSegment {
+                start_ms: 2500,
+                end_ms: 3000,
+            },
+            SpeechSegment {
+                start_ms: 8000,
+                end_ms: 9000,
+            },
+        ];
+        assert_eq!(
+            speech_regions(&segments, 9100),
+            vec![(800, 3200), (7800, 9100)]
+        );
+    }
+
+    #[test]
+    fn test_compacted_timestamps_map_back() {
+        let samples = vec![0.0f32; 10 * 16_000];
+        let regions = [(1000, 2000), (5000, 6000)];
+        let (compact, spans) = compact_to_speech(&samples, 16_000, &regions);
+
+        assert_eq!(compact.len(), 2 * 16_000);
+        assert_eq!(to_original_ms(0, &spans), 1000);
+        assert_eq!(to_original_ms(500, &spans), 1500);
+        assert_eq!(to_original_ms(1200, &spans), 5200);
+        assert_eq!(to_original_ms(2000, &spans), 6000);
+    }
+
+    #[test]
+    fn test_paragraph_breaks_follow_pause_markers() {
+        let segment = |text: &str, start_ms: i64, end_ms: i64| TranscriptionSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            speaker: None,
+        };
+        let mut result = TranscriptionResult {
+            text: "Erster Satz. Zweiter Satz. Dritter Satz.".to_string(),
+            language: "de".to_string(),
+            segments: vec![
+                segment(" Erster Satz.", 0, 2000),
+                segment(" Zweiter Satz.", 2100, 4000),
+                segment(" Dritter Satz.", 4300, 6000),
+            ],
+            processing_time_ms: 0,
+            language_probability: None,
+            language_uncertain: false,
+        };
+
+        // A pause far from any segment start is ignored
+        apply_paragraph_breaks(&mut result, &[9000]);
+        assert_eq!(result.text, "Erster Satz. Zweiter Satz. Dritter Satz.");
+
+        apply_paragraph_breaks(&mut result, &[4000]);
+        assert_eq!(result.text, "Erster Satz. Zweiter Satz.\n\nDritter Satz.");
+    }
+
+    #[test]
+    fn test_streamed_segments_follow_trimmed_lead_in() {
+        let segment = |text: &str, start_ms: i64, end_ms: i64| TranscriptionSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            speaker: None,
+        };
+        // Live timeline: 2s of silence before the first word were trimmed from
+        // the file, the take was paused at 3s of the trimmed take
+        let mut result = TranscriptionResult {
+            text: String::new(),
+            language: "de".to_string(),
+            segments: vec![
+                segment(" Erster Absatz.", 2100, 4900),
+                segment(" Zweiter Absatz.", 5000, 7000),
+                segment(" Untertitel im Auftrag des ZDF.", 20_000, 21_000),
+            ],
+            processing_time_ms: 0,
+            language_probability: None,
+            language_uncertain: false,
+        };
+        let speech = [
+            SpeechSegment {
+                start_ms: 100,
+                end_ms: 2900,
+            },
+            SpeechSegment {
+                start_ms: 3000,
+                end_ms: 5000,
+            },
+        ];
+        let pause_ms = 3000;
+
+        align_streamed(&mut result, 2000, Some(&speech));
+        assert_eq!(result.segments.len(), 2);
+        assert_eq!(result.segments[0].start_ms, 100);
+        assert_eq!(result.segments[1].start_ms, pause_ms);
+
+        apply_paragraph_breaks(&mut result, &[pause_ms as u64]);
+        assert_eq!(result.text, "Erster Absatz.\n\nZweiter Absatz.");
+    }
+
+    #[test]
+    fn test_speaker_tracks_merge_into_turns() {
+        let track = |segments: &[(&str, i64, i64)]| TranscriptionResult {
+            text: String::new(),
+            language: "de".to_string(),
+            segments: segments
+                .iter()
+                .map(|&(text, start_ms, end_ms)| TranscriptionSegment {
+                    text: text.to_string(),
+                    start_ms,
+                    end_ms,
+                    speaker: None,
+                })
+                .collect(),
+            processing_time_ms: 100,
+            language_probability: None,
+            language_uncertain: false,
+        };
+        let me = track(&[
+            (" Hallo.", 0, 1000),
+            (" Wie geht's?", 1000, 2000),
+            (" Gut.", 5000, 6000),
+        ]);
+        let them = track(&[(" Danke, gut.", 2500, 4000), (" Und dir?", 4000, 4800)]);
+
+        let merged = merge_speaker_tracks(vec![(Speaker::Me, me), (Speaker::Them, them)]);
+        assert_eq!(
+            merged.text,
+            "Ich: Hallo. Wie geht's?\n\nGegenüber: Danke, gut. Und dir?\n\nIch: Gut."
+        );
+        assert_eq!(merged.segments.len(), 5);
+        assert_eq!(merged.segments[2].speaker, Some(Speaker::Them));
+        assert_eq!(merged.processing_time_ms, 200);
+    }
+
+    #[test]
+    fn test_language_detection_respects_allowed_languages() {
+        let probabilities = [("en", 0.2), ("de", 0.7), ("fr", 0.1)];
+
+        let detection = pick_language(probabilities, &[]).unwrap();
+        assert_eq!(detection.code, "de");
+        assert!(!detection.is_uncertain());
+
+        // Limited to English and French, German audio still gets a pick but an unsure one
+        let allowed = ["en".to_string(), "fr".to_string()];
+        let detection = pick_language(probabilities, &allowed).unwrap();
+        assert_eq!(detection.code, "en");
+        assert!(detection.is_uncertain());
+
+        let result = TranscriptionResult::from_segments(Vec::new(), None, Some(&detection), 0);
+        assert_eq!(result.language, "en");
+        assert_eq!(result.language_probability, Some(0.2));
+        assert!(result.language_uncertain);
+
+        let result = TranscriptionResult::from_segments(Vec::new(), Some("de"), None, 0);
+        assert_eq!(result.language, "de");
+        assert!(!result.language_uncertain);
+    }
+
+    #[test]
+    fn test_settings_migrate_language_names_to_codes() {
+        let json = r#"{"model":"Small","language":"German","use_gpu":true}"#;
+        let settings: WhisperSettings = serde_json::from_str(json).unwrap();
+        assert_eq!(settings.model, "small");
+        assert_eq!(settings.language, "de");
+        assert!(settings.auto_languages.is_empty());
+
+        let json = r#"{"model":"Small","language":"Auto","use_gpu":true}"#;
+        let settings: WhisperSettings = serde_json::from_str(json).unwrap();
+        assert_eq!(settings.language, AUTO_LANGUAGE);
+        assert_eq!(normalize_language("FR"), "fr");
+    }
+
+    #[test]
+    fn test_settings_reject_unknown_model() {
+        let registry = ModelRegistry::bundled();
+        let mut settings = WhisperSettings::default();
+        assert!(settings.validate(&registry).is_ok());
+
+        settings.model = "large-v9".to_string();
+        assert!(matches!(
+            settings.validate(&registry),
+            Err(WhisperError::UnknownModel(id)) if id == "large-v9"
+        ));
+    }
+
+    #[test]
+    fn test_settings_reject_malformed_language_codes() {
+        let registry = ModelRegistry::bundled();
+        for code in ["d\0e", "DE", "deutsch", "", " de"] {
+            let settings = WhisperSettings {
+                language: code.to_string(),
+                ..Default::default()
+            };
+            assert!(
+                matches!(
+                    settings.validate(&registry),
+                    Err(WhisperError::UnsupportedLanguage(_))
+                ),
+                "{:?}",
+                code
+            );
+        }
+
+        let settings = WhisperSettings {
+            auto_languages: vec!["de".to_string(), "e\0n".to_string()],
+            ..Default::default()
+        };
+        assert!(settings.validate(&registry).is_err());
+        assert_eq!(whisper_language("d\0e"), None);
+        assert_eq!(language_name("en\0"), None);
     }
 
     #[test]
     fn test_default_settings() {
         let settings = WhisperSettings::default();
-        assert_eq!(settings.model, WhisperModel::Small);
-        assert_eq!(settings.language, WhisperLanguage::Auto);
+        assert_eq!(settings.model, "small");
+        assert_eq!(settings.language, AUTO_LANGUAGE);
         assert!(settings.use_gpu);
+        assert!(!settings.streaming);
     }
-}
 
+    fn segment(text: &str, start_ms: i64, end_ms: i64) -> TranscriptionSegment {
+        TranscriptionSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            speaker: None,
+        }
+    }
+
+    /// Plays back scripted decodes and records the window lengths it saw
+    struct ScriptedDecoder {
+        results: Vec<Vec<TranscriptionSegment>>,
+        windows: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl WindowDecoder for ScriptedDecoder {
+        fn decode(&mut self, samples: &[f32]) -> Result<Vec<TranscriptionSegment>, WhisperError> {
+            self.windows.lock().unwrap().push(samples.len());
+            Ok(if self.results.len() > 1 {
+                self.results.remove(0)
+            } else {
+                self.results[0].clone()
+            })
+        }
+
+        fn language(&self) -> Option<&'static str> {
+            Some("de")
+        }
+    }
+
+    fn scripted(
+        results: Vec<Vec<TranscriptionSegment>>,
+    ) -> (
+        StreamingTranscriber<ScriptedDecoder>,
+        Arc<Mutex<Vec<usize>>>,
+    ) {
+        let windows = Arc::new(Mutex::new(Vec::new()));
+        let decoder = ScriptedDecoder {
+            results,
+            windows: Arc::clone(&windows),
+        };
+        (StreamingTranscriber::new(decoder), windows)
+    }
+
+    #[test]
+    fn test_streaming_commits_what_two_decodes_agree_on() {
+        let (mut transcriber, windows) = scripted(vec![
+            vec![segment("Hallo", 0, 800), segment("Welt", 900, 1900)],
+            vec![
+                segment("Hallo", 0, 800),
+                segment("Welt", 900, 1900),
+                segment("wie", 2000, 2900),
+            ],
+            vec![segment("wie geht's", 100, 1000)],
+        ]);
+
+        transcriber.push(&vec![0.0; 2 * 16_000]);
+        assert!(transcriber.is_due());
+        let partial = transcriber.step().unwrap();
+        assert_eq!(partial.stable_text, "");
+        assert_eq!(partial.unstable_text, "Hallo Welt");
+        assert!(!transcriber.is_due());
+
+        transcriber.push(&vec![0.0; 16_000]);
+        let partial = transcriber.step().unwrap();
+        assert_eq!(partial.stable_text, "Hallo Welt");
+        assert_eq!(partial.unstable_text, "wie");
+        assert_eq!(partial.audio_ms, 3000);
+
+        // Only the audio after the committed "Welt" is decoded again
+        let result = transcriber.finish().unwrap();
+        assert_eq!(windows.lock().unwrap()[2], 17_600);
+        assert_eq!(result.text, "Hallo Welt wie geht's");
+        assert_eq!(result.segments[2].start_ms, 2000);
+        assert_eq!(result.segments[2].end_ms, 2900);
+    }
+
+    #[test]
+    fn test_streaming_window_stays_bounded() {
+        let (mut transcriber, windows) = scripted(vec![vec![]]);
+
+        // A long silence is dropped up to the live edge
+        transcriber.push(&vec![0.0; 21 * 16_000]);
+        let partial = transcriber.step().unwrap();
+        assert_eq!(partial.audio_ms, 21_000);
+        assert_eq!(transcriber.window_start_ms, 20_000);
+
+        transcriber.finish().unwrap();
+        assert_eq!(*windows.lock().unwrap(), vec![21 * 16_000, 16_000]);
+
+        // Short takes are padded to what Whisper accepts
+        let (mut transcriber, windows) = scripted(vec![vec![]]);
+        transcriber.push(&vec![0.0; 4_800]);
+        transcriber.finish().unwrap();
+        assert_eq!(*windows.lock().unwrap(), vec![16_000]);
+    }
+
+    #[test]
+    fn test_streaming_session_returns_whole_take() {
+        let (transcriber, _) = scripted(vec![vec![segment("Hallo", 0, 500)]]);
+        let audio = LiveAudio::default();
+        audio.lock().unwrap().extend(vec![0.0; 3 * 16_000]);
+
+        let session = StreamingSession::spawn(
+            move || Ok(transcriber),
+            audio,
+            Arc::new(|_| {}),
+            Arc::new(|e| panic!("unexpected streaming error: {}", e)),
+        );
+        let result = session.finish().unwrap();
+        assert_eq!(result.text, "Hallo");
+        assert_eq!(result.language, "de");
+    }
+
+    #[test]
+    fn test_failed_streaming_session_keeps_emptying_the_tap() {
+        let audio = LiveAudio::default();
+        let errors = Arc::new(AtomicUsize::new(0));
+        let reported = Arc::clone(&errors);
+        let mut session = StreamingSession::spawn(
+            || -> Result<StreamingTranscriber<ScriptedDecoder>, WhisperError> {
+                Err(WhisperError::ModelNotDownloaded("base".to_string()))
+            },
+            audio.clone(),
+            Arc::new(|_| {}),
+            Arc::new(move |_| {
+                reported.fetch_add(1, Ordering::SeqCst);
+            }),
+        );
+
+        // The recorder goes on filling the tap after the session failed
+        audio.lock().unwrap().extend(vec![0.0; 16_000]);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !audio.lock().unwrap().is_empty() {
+            assert!(Instant::now() < deadline, "tap was not emptied");
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(errors.load(Ordering::SeqCst), 1);
+
+        session.stop("take.wav");
+        assert!(matches!(
+            session.finish(),
+            Err(WhisperError::ModelNotDownloaded(_))
+        ));
+    }
+}
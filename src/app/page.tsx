@@ -6,14 +6,18 @@ import { Button } from '@/components/ui/button'
 import { StatusIndicator } from '@/components/status-indicator'
 import { AccessibilityPermissionDialog } from '@/components/accessibility-permission-dialog'
 import { MicrophonePermissionDialog } from '@/components/microphone-permission-dialog'
+import { RecoveredRecordings } from '@/components/recovered-recordings'
+import { EncryptionUnlock } from '@/components/encryption-settings'
 import { useAppStatus } from '@/hooks/use-app-status'
 import { useHotkey, RecordingStopResult, AppContext } from '@/hooks/use-hotkey'
+import type { AudioSettings } from '@/hooks/use-audio-recording'
 import { useWhisper } from '@/hooks/use-whisper'
 import { useTextInsert } from '@/hooks/use-text-insert'
 import { useOllama } from '@/hooks/use-ollama'
 import { useTauri } from '@/hooks/use-tauri'
 import { invoke } from '@tauri-apps/api/core'
-import { Mic, X, Copy, Check, Brain, Settings } from 'lucide-react'
+import { open } from '@tauri-apps/plugin-dialog'
+import { Mic, X, Copy, Check, Brain, Settings, FileAudio } from 'lucide-react'
 import { showSuccess, showWarning, showInfo, showErrorByCode } from '@/lib/app-error'
 import Link from 'next/link'
 
@@ -53,7 +57,12 @@ export default function Home() {
   }, [setStatus])
 
   // PROJ-8/PROJ-9: Accept AppContext for context-aware text processing
-  const handleRecordingStop = useCallback(async (result: RecordingStopResult, context?: AppContext) => {
+  // Imported and recovered files skip text insertion: there is no target app to paste into
+  const handleRecordingStop = useCallback(async (
+    result: RecordingStopResult,
+    context?: AppContext,
+    source: 'recording' | 'import' | 'recovery' = 'recording'
+  ) => {
     setStatus('processing')
 
     // Check if Whisper model is downloaded
@@ -70,10 +79,30 @@ export default function Home() {
     // PROJ-10: Detect if we're in a chat context
     const isChatContext = context?.category === 'chat'
 
+    // Quality check of the raw input: skip takes without speech, warn about the rest
+    // (the microphone may stay silent while the system audio track carries the talk)
+    const quality = result.quality
+    const systemSpeech = (result.system_track?.speech_segments.length ?? 0) > 0
+    if (quality?.quality === 'NoSpeech' && !systemSpeech && isTauri) {
+      const audioSettings = await invoke<AudioSettings>('get_audio_settings').catch(() => null)
+      if (audioSettings?.skip_silent_takes ?? true) {
+        const hints = quality.suggestions.filter((_, i) => quality.issues[i] !== 'NoSpeech')
+        showInfo('Keine Sprache erkannt', hints.length > 0 ? hints.join(' · ') : 'Bitte versuche es erneut.')
+        invoke('mark_recording_processed', { filePath: result.file_path }).catch(() => {})
+        setStatus('idle')
+        return
+      }
+    } else if (quality && (quality.quality === 'Poor' || quality.quality === 'Fair') && quality.suggestions.length > 0) {
+      showWarning(
+        quality.quality === 'Poor' ? 'Schlechte Aufnahmequalität' : 'Aufnahmequalität eingeschränkt',
+        quality.suggestions.join(' · ')
+      )
+    }
+
     // Start transcription (PROJ-4)
     if (result.file_path && isTauri) {
       try {
-        const transcriptionResult = await transcribe(result.file_path)
+        const transcriptionResult = await transcribe(result.file_path, result.speech_segments, result.pause_markers, result.system_track, result.trim_offset_ms)
         if (transcriptionResult?.text) {
           let finalText = transcriptionResult.text
           let totalProcessingTime = transcriptionResult.processing_time_ms
@@ -107,7 +136,7 @@ export default function Home() {
           // PROJ-6: Automatically insert text into active text field
           // PROJ-6 FIX: Pass the original app's bundle_id to focus it before inserting
           // This ensures the text goes to the app where the user was when they pressed the hotkey
-          if (textInsertSettings.enabled) {
+          if (textInsertSettings.enabled && source === 'recording') {
             const insertResult = await insertText(finalText, context?.bundle_id)
             if (insertResult?.success) {
               showSuccess('Text eingefuegt', `${finalText.length} Zeichen in ${(totalProcessingTime / 1000).toFixed(1)}s`)
@@ -127,14 +156,16 @@ export default function Home() {
           try {
             const archiveData = {
               date: new Date().toISOString(),
-              app_name: context?.app_name || 'Desktop',
+              app_name: context?.app_name || (source === 'import' ? 'Import' : source === 'recovery' ? 'Wiederhergestellt' : 'Desktop'),
               category: context?.category || 'other',
               duration_seconds: Math.round(result.duration_ms / 1000),
               word_count: finalText.split(/\s+/).filter(Boolean).length,
-              language: transcriptionResult.language || 'de',
+              language: transcriptionResult.language || 'auto',
               was_edited: ollamaSettings.enabled && finalText !== transcriptionResult.text,
               edited_text: finalText,
               original_text: transcriptionResult.text,
+              // Privacy mode keeps no audio, not even in the archive
+              audio_path: result.privacy_mode ? null : result.file_path,
             }
             invoke<{ success: boolean; error?: string }>('archive_transcription', { data: archiveData })
               .then((archiveResult) => {
@@ -168,6 +199,41 @@ export default function Home() {
     setStatus('idle')
   }, [setStatus, transcribe, improveText, ollamaSettings.enabled, insertText, textInsertSettings.enabled, modelStatus, whisperSettings.model, isTauri])
 
+  // Transcribe an audio file from disk through the same pipeline as a recording
+  const handleImportFile = useCallback(async () => {
+    if (!isTauri) return
+
+    try {
+      const extensions = await invoke<string[]>('get_import_extensions')
+      const selected = await open({
+        multiple: false,
+        directory: false,
+        title: 'Audiodatei importieren',
+        filters: [{ name: 'Audio', extensions }],
+      })
+      if (!selected || Array.isArray(selected)) return
+
+      setStatus('processing')
+      setTranscriptionText(null)
+      const result = await invoke<RecordingStopResult>('import_audio_file', { path: selected })
+      try {
+        await handleRecordingStop(result, undefined, 'import')
+      } finally {
+        // The converted WAV is only a working copy, the original stays untouched
+        if (result.privacy_mode) {
+          invoke('delete_recording', { filePath: result.file_path }).catch(() => {})
+        }
+      }
+    } catch (err) {
+      console.error('Audio import failed:', err)
+      showErrorByCode('ERR_IMPORT', 'page', {
+        overrideMessage: 'Audiodatei konnte nicht importiert werden',
+        details: String(err),
+      })
+      setStatus('idle')
+    }
+  }, [isTauri, setStatus, handleRecordingStop])
+
   const handleRecordingCancel = useCallback((reason: string) => {
     setStatus('idle')
     console.log('Recording cancelled:', reason)
@@ -282,6 +348,17 @@ export default function Home() {
           </div>
         </header>
 
+        {/* Passphrase prompt while the encrypted data is locked */}
+        {isTauri && <EncryptionUnlock />}
+
+        {/* Recordings left over from a crash */}
+        {isTauri && (
+          <RecoveredRecordings
+            onTranscribe={(result) => handleRecordingStop(result, undefined, 'recovery')}
+            disabled={status === 'processing' || recordingState !== 'idle'}
+          />
+        )}
+
         {/* Main Recording Area */}
         <Card className="overflow-hidden">
           <CardHeader className="text-center pb-2">
@@ -374,6 +451,20 @@ export default function Home() {
                   <span className="text-destructive">Ein Fehler ist aufgetreten</span>
                 )}
               </p>
+
+              {/* Import an existing audio file instead of recording */}
+              {isTauri && (
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  onClick={handleImportFile}
+                  disabled={status === 'processing' || recordingState !== 'idle'}
+                  className="gap-1.5 text-muted-foreground"
+                >
+                  <FileAudio className="h-4 w-4" />
+                  Audiodatei transkribieren
+                </Button>
+              )}
             </div>
           </CardContent>
         </Card>
@@ -427,6 +518,14 @@ export default function Home() {
                       Sprache: {lastTranscription.language} · {lastTranscription.segments.length} Segment(e) · {(lastTranscription.processing_time_ms / 1000).toFixed(1)}s
                     </p>
                   )}
+                  {lastTranscription?.language_uncertain && (
+                    <p className="text-xs text-amber-500">
+                      Sprache unsicher erkannt
+                      {lastTranscription.language_probability !== null &&
+                        ` (${Math.round(lastTranscription.language_probability * 100)}%)`}
+                      . Wähle die Sprache in den Whisper-Einstellungen, falls sie falsch ist.
+                    </p>
+                  )}
                 </div>
               )}
             </CardContent>
@@ -22,17 +22,25 @@ import {
   FolderOpen,
   CheckCircle2,
   XCircle,
+  AudioLines,
+  CalendarClock,
 } from 'lucide-react'
 
 /** Folder structure options */
 type FolderStructure = 'Flat' | 'Nested'
 
+/** Format of the audio kept next to each note */
+type AudioFormat = 'Opus' | 'Flac'
+
 /** Archive settings from backend */
 interface ArchiveSettings {
   enabled: boolean
   path: string
   include_original: boolean
   folder_structure: FolderStructure
+  audio_format: AudioFormat | null
+  /** 0 keeps everything */
+  retention_days: number
 }
 
 const DEFAULT_SETTINGS: ArchiveSettings = {
@@ -40,8 +48,38 @@ const DEFAULT_SETTINGS: ArchiveSettings = {
   path: '',
   include_original: true,
   folder_structure: 'Flat',
+  audio_format: null,
+  retention_days: 0,
 }
 
+/** Audio options ('none' stands for null in the select) */
+const AUDIO_FORMAT_OPTIONS = [
+  {
+    value: 'none',
+    label: 'Keine Audiodatei',
+    description: 'Nur der Text wird archiviert',
+  },
+  {
+    value: 'Opus',
+    label: 'Opus',
+    description: 'Klein, etwa 180 KB pro Minute',
+  },
+  {
+    value: 'Flac',
+    label: 'FLAC',
+    description: 'Verlustfrei, etwa 1 MB pro Minute',
+  },
+]
+
+/** Retention options in days */
+const RETENTION_OPTIONS = [
+  { value: 0, label: 'Unbegrenzt' },
+  { value: 30, label: '30 Tage' },
+  { value: 90, label: '90 Tage' },
+  { value: 180, label: '6 Monate' },
+  { value: 365, label: '1 Jahr' },
+]
+
 /** Folder structure options */
 const FOLDER_STRUCTURE_OPTIONS = [
   {
@@ -351,6 +389,71 @@ export function ArchiveSettings() {
               />
             </div>
 
+            {/* Audio next to the note */}
+            <div className="space-y-3">
+              <Label className="flex items-center gap-2 text-sm font-medium">
+                <AudioLines className="h-4 w-4 text-muted-foreground" />
+                Audio behalten
+              </Label>
+              <Select
+                value={settings.audio_format ?? 'none'}
+                onValueChange={(value) =>
+                  updateSettings({ audio_format: value === 'none' ? null : (value as AudioFormat) })
+                }
+              >
+                <SelectTrigger>
+                  <SelectValue>
+                    {AUDIO_FORMAT_OPTIONS.find((f) => f.value === (settings.audio_format ?? 'none'))?.label}
+                  </SelectValue>
+                </SelectTrigger>
+                <SelectContent>
+                  {AUDIO_FORMAT_OPTIONS.map((option) => (
+                    <SelectItem key={option.value} value={option.value}>
+                      <div className="flex flex-col">
+                        <span>{option.label}</span>
+                        <span className="text-xs text-muted-foreground">{option.description}</span>
+                      </div>
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+              <p className="text-xs text-muted-foreground">
+                Wird neben der Markdown-Datei gespeichert und im Frontmatter verlinkt.
+                Im Privatsphäre-Modus wird keine Audiodatei behalten.
+              </p>
+            </div>
+
+            {/* Retention */}
+            <div className="space-y-3">
+              <Label className="flex items-center gap-2 text-sm font-medium">
+                <CalendarClock className="h-4 w-4 text-muted-foreground" />
+                Aufbewahrung
+              </Label>
+              <Select
+                value={String(settings.retention_days)}
+                onValueChange={(value) => updateSettings({ retention_days: Number(value) })}
+              >
+                <SelectTrigger>
+                  <SelectValue>
+                    {RETENTION_OPTIONS.find((r) => r.value === settings.retention_days)?.label ??
+                      `${settings.retention_days} Tage`}
+                  </SelectValue>
+                </SelectTrigger>
+                <SelectContent>
+                  {RETENTION_OPTIONS.map((option) => (
+                    <SelectItem key={option.value} value={String(option.value)}>
+                      {option.label}
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+              <p className="text-xs text-muted-foreground">
+                {settings.retention_days > 0
+                  ? 'Ältere Notizen werden samt Audiodatei gelöscht, eigene Dateien im Ordner bleiben unberührt'
+                  : 'Notizen und Audiodateien bleiben erhalten'}
+              </p>
+            </div>
+
             {/* Preview */}
             <div className="space-y-3">
               <Label className="text-sm font-medium text-muted-foreground">Datei-Vorschau</Label>
@@ -369,6 +472,13 @@ export function ArchiveSettings() {
                 {'\n'}
                 <span className="text-blue-500">language</span>: de
                 {'\n'}
+                {settings.audio_format && (
+                  <>
+                    <span className="text-blue-500">audio</span>: &quot;[[2024-01-15_14-32_slack_nachricht.
+                    {settings.audio_format === 'Opus' ? 'opus' : 'flac'}]]&quot;
+                    {'\n'}
+                  </>
+                )}
                 <span className="text-blue-500">tags</span>:
                 {'\n'}
                 {'  '}- transkription
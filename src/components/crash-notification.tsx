@@ -28,7 +28,7 @@ import {
  * Displays on app startup if the app crashed in the previous session.
  */
 export function CrashNotification() {
-  const { hasCrash, crashInfo, clearCrashNotification, getCrashLog, crashLog } = useCrashRecovery()
+  const { hasCrash, crashInfo, clearCrashNotification, getCrashLog, crashLog, orphanedRecordings } = useCrashRecovery()
   const [showDetails, setShowDetails] = useState(false)
 
   if (!hasCrash || !crashInfo) {
@@ -75,6 +75,14 @@ export function CrashNotification() {
             <p className="font-medium">Zeitpunkt:</p>
             <p className="text-muted-foreground">{crashInfo.timestamp}</p>
           </div>
+          {orphanedRecordings.some((recording) => recording.crashed) && (
+            <div className="rounded-md bg-muted p-3 text-sm">
+              <p className="font-medium">Aufnahme gesichert:</p>
+              <p className="text-muted-foreground">
+                Die unterbrochene Aufnahme wurde gerettet und kann auf der Startseite transkribiert werden.
+              </p>
+            </div>
+          )}
           {crashInfo.message && (
             <div className="rounded-md bg-muted p-3 text-sm">
               <p className="font-medium">Fehler:</p>
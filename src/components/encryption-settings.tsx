@@ -0,0 +1,284 @@
+'use client'
+
+import { useState } from 'react'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Label } from '@/components/ui/label'
+import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '@/components/ui/select'
+import { Switch } from '@/components/ui/switch'
+import { Input } from '@/components/ui/input'
+import { Button } from '@/components/ui/button'
+import { Alert, AlertDescription } from '@/components/ui/alert'
+import { useTauri } from '@/hooks/use-tauri'
+import { useEncryption, KeySource } from '@/hooks/use-encryption'
+import { showSuccess } from '@/lib/app-error'
+import { AlertCircle, KeyRound, Loader2, Lock, RefreshCw, ShieldCheck } from 'lucide-react'
+
+const KEY_SOURCE_OPTIONS: { value: KeySource; label: string; description: string }[] = [
+  {
+    value: 'Keyring',
+    label: 'Schlüsselbund des Systems',
+    description: 'Der Schlüssel liegt im Schlüsselbund, nichts einzugeben',
+  },
+  {
+    value: 'Passphrase',
+    label: 'Passphrase',
+    description: 'Wird bei jedem Start abgefragt, ohne sie sind die Daten nicht lesbar',
+  },
+]
+
+/**
+ * Opt-in encryption at rest for recordings, archive and settings files
+ */
+export function EncryptionSettings() {
+  const { isTauri } = useTauri()
+  const { status, isLocked, enable, disable, rotateKey } = useEncryption()
+  const [keySource, setKeySource] = useState<KeySource>('Keyring')
+  const [passphrase, setPassphrase] = useState('')
+  const [confirmation, setConfirmation] = useState('')
+  const [busy, setBusy] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+
+  if (!isTauri || !status) {
+    return null
+  }
+
+  const needsPassphrase = status.enabled
+    ? status.key_source === 'Passphrase'
+    : keySource === 'Passphrase'
+  const passphraseMismatch = passphrase !== confirmation
+
+  const run = async (action: () => Promise<void>) => {
+    setBusy(true)
+    setError(null)
+    try {
+      await action()
+      setPassphrase('')
+      setConfirmation('')
+    } catch (err) {
+      console.error('Encryption action failed:', err)
+      setError(String(err))
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  const handleToggle = (checked: boolean) =>
+    run(async () => {
+      if (checked) {
+        const sealed = await enable(keySource, needsPassphrase ? passphrase : undefined)
+        showSuccess('Verschlüsselung aktiviert', `${sealed} Dateien verschlüsselt.`)
+      } else {
+        const opened = await disable()
+        showSuccess('Verschlüsselung deaktiviert', `${opened} Dateien entschlüsselt.`)
+      }
+    })
+
+  const handleRotate = () =>
+    run(async () => {
+      const resealed = await rotateKey(needsPassphrase && passphrase ? passphrase : undefined)
+      showSuccess('Schlüssel gewechselt', `${resealed} Dateien neu verschlüsselt.`)
+    })
+
+  const passphraseFields = (
+    <div className="space-y-2">
+      <Input
+        type="password"
+        placeholder={status.enabled ? 'Neue Passphrase (optional)' : 'Passphrase'}
+        value={passphrase}
+        onChange={(e) => setPassphrase(e.target.value)}
+        disabled={busy}
+        aria-label="Passphrase"
+      />
+      <Input
+        type="password"
+        placeholder="Passphrase wiederholen"
+        value={confirmation}
+        onChange={(e) => setConfirmation(e.target.value)}
+        disabled={busy}
+        aria-label="Passphrase wiederholen"
+      />
+      {passphrase && passphraseMismatch && (
+        <p className="text-xs text-destructive">Die Passphrasen stimmen nicht überein.</p>
+      )}
+    </div>
+  )
+
+  return (
+    <Card>
+      <CardHeader className="pb-4">
+        <CardTitle className="flex items-center gap-2 text-lg font-light">
+          <ShieldCheck className="h-4 w-4 text-muted-foreground" />
+          <span className="font-medium">Verschlüsselung</span>
+        </CardTitle>
+        <CardDescription>
+          Aufnahmen, Archiv und Einstellungen verschlüsselt speichern
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-6">
+        {error && (
+          <Alert variant="destructive" className="border-destructive/30 bg-destructive/10">
+            <AlertCircle className="h-4 w-4" />
+            <AlertDescription>{error}</AlertDescription>
+          </Alert>
+        )}
+
+        <div className="flex items-center justify-between py-2">
+          <div className="flex items-center space-x-4">
+            <div className="flex h-10 w-10 items-center justify-center rounded-lg bg-muted/50">
+              <Lock className="h-5 w-5 text-muted-foreground" />
+            </div>
+            <div className="space-y-0.5">
+              <Label htmlFor="encryption-enabled" className="text-sm font-medium">
+                Verschlüsselung aktivieren
+              </Label>
+              <p className="text-xs text-muted-foreground">
+                {status.enabled
+                  ? isLocked
+                    ? 'Gesperrt – Passphrase auf der Startseite eingeben'
+                    : 'Aktiv'
+                  : 'Dateien liegen unverschlüsselt auf der Festplatte'}
+              </p>
+            </div>
+          </div>
+          {busy ? (
+            <Loader2 className="h-5 w-5 animate-spin text-muted-foreground" />
+          ) : (
+            <Switch
+              id="encryption-enabled"
+              checked={status.enabled}
+              onCheckedChange={handleToggle}
+              disabled={
+                isLocked ||
+                (!status.enabled && needsPassphrase && (!passphrase || passphraseMismatch))
+              }
+              aria-label="Verschlüsselung aktivieren/deaktivieren"
+            />
+          )}
+        </div>
+
+        {!status.enabled && (
+          <div className="space-y-2">
+            <Label className="text-sm font-medium">Schlüssel</Label>
+            <Select
+              value={keySource}
+              onValueChange={(value) => setKeySource(value as KeySource)}
+              disabled={busy}
+            >
+              <SelectTrigger>
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {KEY_SOURCE_OPTIONS.map((option) => (
+                  <SelectItem key={option.value} value={option.value}>
+                    {option.label}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+            <p className="text-xs text-muted-foreground">
+              {KEY_SOURCE_OPTIONS.find((option) => option.value === keySource)?.description}
+            </p>
+            {needsPassphrase && passphraseFields}
+          </div>
+        )}
+
+        {status.enabled && !isLocked && (
+          <div className="space-y-2">
+            <Label className="text-sm font-medium">Schlüssel wechseln</Label>
+            {needsPassphrase && passphraseFields}
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={handleRotate}
+              disabled={busy || (needsPassphrase && passphraseMismatch)}
+            >
+              <RefreshCw className="mr-2 h-4 w-4" />
+              Neuen Schlüssel erzeugen
+            </Button>
+            <p className="text-xs text-muted-foreground">
+              Alle Dateien werden mit einem neuen Schlüssel neu verschlüsselt.
+            </p>
+          </div>
+        )}
+
+        <div className="rounded-lg bg-muted/20 p-4">
+          <p className="text-xs text-muted-foreground leading-relaxed">
+            Gelöschte Aufnahmen werden vorher überschrieben. Vorhandene Notizen im
+            Archiv-Ordner bleiben unverändert, nur neue Einträge werden verschlüsselt
+            und sind dann nur noch in dieser App lesbar.
+          </p>
+        </div>
+      </CardContent>
+    </Card>
+  )
+}
+
+/**
+ * Asks for the passphrase while the encrypted data is locked
+ */
+export function EncryptionUnlock() {
+  const { isLocked, status, unlock } = useEncryption()
+  const [passphrase, setPassphrase] = useState('')
+  const [busy, setBusy] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+
+  if (!isLocked) {
+    return null
+  }
+
+  const handleUnlock = async () => {
+    setBusy(true)
+    setError(null)
+    try {
+      await unlock(passphrase)
+      setPassphrase('')
+      // The settings views load their data once, start over with the real settings
+      window.location.reload()
+    } catch (err) {
+      setError(String(err))
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  return (
+    <Card className="border-amber-500/50">
+      <CardHeader className="pb-3">
+        <CardTitle className="flex items-center gap-2 text-base font-medium">
+          <KeyRound className="h-4 w-4 text-amber-500" />
+          Verschlüsselte Daten gesperrt
+        </CardTitle>
+        <CardDescription>
+          {status?.key_source === 'Passphrase'
+            ? 'Gib deine Passphrase ein, um aufzunehmen und deine Einstellungen zu laden.'
+            : 'Der Schlüssel konnte nicht aus dem Schlüsselbund geladen werden.'}
+        </CardDescription>
+      </CardHeader>
+      <CardContent>
+        <form
+          className="flex gap-2"
+          onSubmit={(e) => {
+            e.preventDefault()
+            handleUnlock()
+          }}
+        >
+          {status?.key_source === 'Passphrase' && (
+            <Input
+              type="password"
+              placeholder="Passphrase"
+              value={passphrase}
+              onChange={(e) => setPassphrase(e.target.value)}
+              disabled={busy}
+              aria-label="Passphrase"
+              autoFocus
+            />
+          )}
+          <Button type="submit" disabled={busy}>
+            {busy ? <Loader2 className="h-4 w-4 animate-spin" /> : 'Entsperren'}
+          </Button>
+        </form>
+        {error && <p className="mt-2 text-xs text-destructive">{error}</p>}
+      </CardContent>
+    </Card>
+  )
+}
@@ -25,7 +25,7 @@ import {
 import { Badge } from '@/components/ui/badge'
 import { useHotkey, HotkeyMode } from '@/hooks/use-hotkey'
 import { useTauri } from '@/hooks/use-tauri'
-import { Keyboard, AlertCircle, Mic, ToggleLeft } from 'lucide-react'
+import { Keyboard, AlertCircle, Mic, ToggleLeft, Pause, X } from 'lucide-react'
 
 /** Map of modifier keys to display names */
 const MODIFIER_DISPLAY: Record<string, string> = {
@@ -64,6 +64,8 @@ export function HotkeySettings() {
   } = useHotkey()
 
   const [isRecordingShortcut, setIsRecordingShortcut] = useState(false)
+  // Which shortcut the dialog is recording
+  const [shortcutTarget, setShortcutTarget] = useState<'shortcut' | 'pause_shortcut'>('shortcut')
   const [pendingShortcut, setPendingShortcut] = useState<string | null>(null)
   const [conflictWarning, setConflictWarning] = useState<string | null>(null)
   const [saveError, setSaveError] = useState<string | null>(null)
@@ -118,12 +120,24 @@ export function HotkeySettings() {
     }
   }, [isRecordingShortcut, handleKeyDown])
 
+  // Open the recording dialog for one of the shortcuts
+  const recordShortcut = (target: 'shortcut' | 'pause_shortcut') => {
+    setShortcutTarget(target)
+    setIsRecordingShortcut(true)
+  }
+
   // Save pending shortcut
   const saveShortcut = async () => {
     if (!pendingShortcut) return
 
+    const other = shortcutTarget === 'shortcut' ? settings.pause_shortcut : settings.shortcut
+    if (pendingShortcut === other) {
+      setConflictWarning('Aufnahme- und Pause-Hotkey müssen sich unterscheiden')
+      return
+    }
+
     try {
-      await updateSettings({ shortcut: pendingShortcut })
+      await updateSettings({ [shortcutTarget]: pendingShortcut })
       setPendingShortcut(null)
       setConflictWarning(null)
       setSaveError(null)
@@ -139,6 +153,16 @@ export function HotkeySettings() {
     setIsRecordingShortcut(false)
   }
 
+  // Remove the pause shortcut
+  const clearPauseShortcut = async () => {
+    try {
+      await updateSettings({ pause_shortcut: null })
+      setSaveError(null)
+    } catch (err) {
+      setSaveError('Einstellung konnte nicht gespeichert werden')
+    }
+  }
+
   // Toggle hotkey enabled
   const toggleEnabled = async () => {
     try {
@@ -222,12 +246,47 @@ export function HotkeySettings() {
                     <Button
                       variant="outline"
                       className="min-w-[160px] font-mono"
-                      onClick={() => setIsRecordingShortcut(true)}
+                      onClick={() => recordShortcut('shortcut')}
                     >
                       {formatShortcut(settings.shortcut)}
                     </Button>
                   </div>
 
+                  {/* Pause Shortcut */}
+                  <div className="flex items-center justify-between py-2">
+                    <div className="flex items-center space-x-4">
+                      <div className="flex h-10 w-10 items-center justify-center rounded-lg bg-muted/50">
+                        <Pause className="h-5 w-5 text-muted-foreground" />
+                      </div>
+                      <div className="space-y-0.5">
+                        <Label className="text-sm font-medium">Pause-Hotkey</Label>
+                        <p className="text-xs text-muted-foreground">
+                          Unterbricht die Aufnahme, ohne sie zu beenden
+                        </p>
+                      </div>
+                    </div>
+                    <div className="flex items-center gap-1">
+                      <Button
+                        variant="outline"
+                        className="min-w-[160px] font-mono"
+                        onClick={() => recordShortcut('pause_shortcut')}
+                      >
+                        {settings.pause_shortcut ? formatShortcut(settings.pause_shortcut) : 'Nicht belegt'}
+                      </Button>
+                      {settings.pause_shortcut && (
+                        <Button
+                          variant="ghost"
+                          size="sm"
+                          onClick={clearPauseShortcut}
+                          className="h-8 w-8 p-0"
+                          aria-label="Pause-Hotkey entfernen"
+                        >
+                          <X className="h-4 w-4" />
+                        </Button>
+                      )}
+                    </div>
+                  </div>
+
                   {/* Mode Selection */}
                   <div className="flex items-center justify-between py-2">
                     <div className="flex items-center space-x-4">
@@ -310,7 +369,11 @@ export function HotkeySettings() {
         <DialogContent className="sm:max-w-[425px]">
           <DialogHeader>
             <DialogTitle>
-              {isRecordingShortcut ? 'Neue Tastenkombination' : 'Tastenkombination bestätigen'}
+              {isRecordingShortcut
+                ? shortcutTarget === 'pause_shortcut'
+                  ? 'Neuer Pause-Hotkey'
+                  : 'Neue Tastenkombination'
+                : 'Tastenkombination bestätigen'}
             </DialogTitle>
             <DialogDescription>
               {isRecordingShortcut
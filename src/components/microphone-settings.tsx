@@ -2,6 +2,7 @@
 
 import { useEffect, useState, useCallback } from 'react'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
 import { Label } from '@/components/ui/label'
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '@/components/ui/select'
@@ -12,7 +13,8 @@ import { Alert, AlertDescription } from '@/components/ui/alert'
 import { Progress } from '@/components/ui/progress'
 import { Skeleton } from '@/components/ui/skeleton'
 import { useTauri } from '@/hooks/use-tauri'
-import { Mic, MicOff, Volume2, Shield, Clock, AlertCircle, RefreshCw, AlertTriangle } from 'lucide-react'
+import { dbfsToLevel, type MeterFrame, type RecordingResult } from '@/hooks/use-audio-recording'
+import { Mic, MicOff, Volume2, Shield, Clock, AlertCircle, RefreshCw, AlertTriangle, Scissors, Timer, VolumeX, AudioWaveform, Radio, ArrowUp, ArrowDown, X, ListOrdered, BarChart3, Split, Speaker } from 'lucide-react'
 import { cn } from '@/lib/utils'
 import { showWarning, showSuccess } from '@/lib/app-error'
 
@@ -21,13 +23,48 @@ interface AudioDevice {
   id: string
   name: string
   is_default: boolean
+  host: string
+  channels: number
 }
 
+/** How input channels become the recorded mono signal (channel numbers are 0-based) */
+type ChannelMode = 'Average' | 'Loudest' | { Channel: number } | { Mix: number[] }
+
+/** Whether system audio is recorded: not at all, mixed into the take or as its own track */
+type SystemAudioMode = 'Off' | 'Mix' | 'SeparateTrack'
+
 /** Audio settings from backend */
 interface AudioSettings {
   device_id: string | null
+  device_priority: string[]
   max_duration_minutes: number
   privacy_mode: boolean
+  trim_silence: boolean
+  silence_auto_stop_seconds: number | null
+  high_pass_filter: boolean
+  noise_suppression: boolean
+  loudness_normalization: boolean
+  warm_mic: boolean
+  pre_roll_ms: number
+  meter_spectrum: boolean
+  skip_silent_takes: boolean
+  channel_mode: ChannelMode
+  system_audio: SystemAudioMode
+  system_audio_device: string | null
+}
+
+/** Select value for a channel mode */
+function channelModeValue(mode: ChannelMode): string {
+  if (mode === 'Average') return 'average'
+  if (mode === 'Loudest') return 'loudest'
+  if ('Channel' in mode) return `channel-${mode.Channel}`
+  return 'mix'
+}
+
+/** Display name stored in a device id (`host:index:fingerprint:name`, or a plain name from older versions) */
+function deviceNameFromId(id: string): string {
+  const match = /^[A-Za-z0-9]+:\d+:[0-9a-fA-F]{8}:(.+)$/.exec(id)
+  return match ? match[1] : id
 }
 
 export function MicrophoneSettings() {
@@ -35,9 +72,24 @@ export function MicrophoneSettings() {
   const [devices, setDevices] = useState<AudioDevice[]>([])
   const [settings, setSettings] = useState<AudioSettings>({
     device_id: null,
+    device_priority: [],
     max_duration_minutes: 6,
     privacy_mode: true,
+    trim_silence: true,
+    silence_auto_stop_seconds: null,
+    high_pass_filter: false,
+    noise_suppression: false,
+    loudness_normalization: false,
+    warm_mic: false,
+    pre_roll_ms: 1500,
+    meter_spectrum: false,
+    skip_silent_takes: true,
+    channel_mode: 'Average',
+    system_audio: 'Off',
+    system_audio_device: null,
   })
+  const [systemSources, setSystemSources] = useState<AudioDevice[]>([])
+  const [warmMicActive, setWarmMicActive] = useState(false)
   const [isLoading, setIsLoading] = useState(true)
   const [error, setError] = useState<string | null>(null)
   const [isTesting, setIsTesting] = useState(false)
@@ -54,12 +106,16 @@ export function MicrophoneSettings() {
 
     try {
       setError(null)
-      const [deviceList, audioSettings] = await Promise.all([
+      const [deviceList, audioSettings, warmActive, sourceList] = await Promise.all([
         invoke<AudioDevice[]>('list_audio_devices'),
         invoke<AudioSettings>('get_audio_settings'),
+        invoke<boolean>('get_warm_mic_state'),
+        invoke<AudioDevice[]>('list_system_audio_sources').catch(() => []),
       ])
       setDevices(deviceList)
+      setSystemSources(sourceList)
       setSettings(audioSettings)
+      setWarmMicActive(warmActive)
     } catch (err) {
       console.error('Failed to load audio data:', err)
       setError(err instanceof Error ? err.message : 'Fehler beim Laden der Audio-Einstellungen')
@@ -72,6 +128,24 @@ export function MicrophoneSettings() {
     loadData()
   }, [loadData])
 
+  // Follow the warm microphone toggle in the tray menu
+  useEffect(() => {
+    if (!isTauri) return
+
+    const unlisten = listen<boolean>('warm-mic-changed', async (event) => {
+      setWarmMicActive(event.payload)
+      try {
+        setSettings(await invoke<AudioSettings>('get_audio_settings'))
+      } catch {
+        // Keep the current view, the next load picks it up
+      }
+    })
+
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [isTauri])
+
   // Monitor for device changes (BUG-2 fix)
   useEffect(() => {
     if (!isTauri) return
@@ -91,9 +165,17 @@ export function MicrophoneSettings() {
           const selectedExists = currentDevices.some(d => d.id === settings.device_id)
           if (!selectedExists && !deviceDisconnected) {
             setDeviceDisconnected(true)
-            showWarning('Mikrofon getrennt', 'Das ausgewaehlte Mikrofon wurde getrennt. Wechsle zu Standard-Mikrofon.')
-            // Auto-fallback to default
-            await updateSettings({ device_id: null })
+            // The backend falls back along the priority list on its own
+            const fallback = settings.device_priority
+              .map((id) => currentDevices.find((d) => d.id === id))
+              .find((d) => d !== undefined)
+            if (fallback) {
+              showWarning('Mikrofon getrennt', `Das ausgewaehlte Mikrofon wurde getrennt. Aufnahmen nutzen ${fallback.name} aus der Ersatz-Reihenfolge.`)
+            } else {
+              showWarning('Mikrofon getrennt', 'Das ausgewaehlte Mikrofon wurde getrennt. Wechsle zu Standard-Mikrofon.')
+              // Auto-fallback to default
+              await updateSettings({ device_id: null })
+            }
             setDevices(currentDevices)
           }
         }
@@ -113,7 +195,35 @@ export function MicrophoneSettings() {
 
     const interval = setInterval(checkDevices, 3000)
     return () => clearInterval(interval)
-  }, [isTauri, settings.device_id, devices.length, deviceDisconnected, previousDeviceId])
+  }, [isTauri, settings.device_id, settings.device_priority, devices.length, deviceDisconnected, previousDeviceId])
+
+  // Channels of the microphone in use (the system default if none is selected)
+  const selectedDevice = devices.find((d) =>
+    settings.device_id ? d.id === settings.device_id : d.is_default
+  )
+  const channelCount = selectedDevice?.channels ?? 0
+  const mixGains = typeof settings.channel_mode === 'object' && 'Mix' in settings.channel_mode
+    ? settings.channel_mode.Mix
+    : []
+
+  const setChannelMode = (value: string) => {
+    if (value === 'average') {
+      updateSettings({ channel_mode: 'Average' })
+    } else if (value === 'loudest') {
+      updateSettings({ channel_mode: 'Loudest' })
+    } else if (value === 'mix') {
+      // Start from an even mix of all channels
+      updateSettings({ channel_mode: { Mix: Array.from({ length: channelCount }, () => 1 / channelCount) } })
+    } else {
+      updateSettings({ channel_mode: { Channel: Number(value.replace('channel-', '')) } })
+    }
+  }
+
+  const setMixGain = (channel: number, gain: number) => {
+    const gains = Array.from({ length: Math.max(channelCount, mixGains.length) }, (_, i) => mixGains[i] ?? 0)
+    gains[channel] = gain
+    updateSettings({ channel_mode: { Mix: gains } })
+  }
 
   // Update settings
   const updateSettings = async (newSettings: Partial<AudioSettings>) => {
@@ -131,42 +241,47 @@ export function MicrophoneSettings() {
     }
   }
 
-  // Test microphone
+  // Test microphone: record for 3 seconds and follow the level meter pushed by the backend
   const startMicTest = async () => {
     if (!isTauri || isTesting) return
 
     setIsTesting(true)
     setTestLevel(0)
 
+    const unlisten = await listen<MeterFrame>('audio-meter', (event) => {
+      setTestLevel(dbfsToLevel(event.payload.rms_dbfs))
+    })
     try {
       await invoke('start_audio_recording')
-
-      // Poll audio level for 3 seconds
-      const startTime = Date.now()
-      const pollInterval = setInterval(async () => {
-        try {
-          const level = await invoke<number>('get_audio_level')
-          setTestLevel(level)
-
-          if (Date.now() - startTime > 3000) {
-            clearInterval(pollInterval)
-            await invoke('stop_audio_recording')
-            setIsTesting(false)
-            setTestLevel(0)
-          }
-        } catch {
-          clearInterval(pollInterval)
-          setIsTesting(false)
-          setTestLevel(0)
-        }
-      }, 100)
+      await new Promise((resolve) => setTimeout(resolve, 3000))
+      const result = await invoke<RecordingResult>('stop_audio_recording')
+      // The test take is not a recording, it must not come back as one to recover
+      if (result.file_path) {
+        await invoke('delete_recording', { filePath: result.file_path })
+      }
     } catch (err) {
       console.error('Mic test failed:', err)
-      setIsTesting(false)
       setError('Mikrofon-Test fehlgeschlagen')
+    } finally {
+      unlisten()
+      setIsTesting(false)
+      setTestLevel(0)
     }
   }
 
+  // Reorder the fallback list
+  const movePriority = (index: number, offset: number) => {
+    const next = [...settings.device_priority]
+    const [entry] = next.splice(index, 1)
+    next.splice(index + offset, 0, entry)
+    updateSettings({ device_priority: next })
+  }
+
+  // Devices that can still be added to the fallback list
+  const priorityCandidates = devices.filter(
+    (d) => d.id !== settings.device_id && !settings.device_priority.includes(d.id)
+  )
+
   // Get selected device display name
   const getSelectedDeviceName = () => {
     if (!settings.device_id) {
@@ -290,6 +405,203 @@ export function MicrophoneSettings() {
           )}
         </div>
 
+        {/* Input channels, only relevant for multi-channel interfaces */}
+        {(channelCount > 1 || settings.channel_mode !== 'Average') && (
+          <div className="space-y-3">
+            <Label className="flex items-center gap-2 text-sm font-medium">
+              <Split className="h-4 w-4 text-muted-foreground" />
+              Eingangskanäle
+              {channelCount > 0 && (
+                <span className="font-normal text-muted-foreground">({channelCount} verfügbar)</span>
+              )}
+            </Label>
+            <Select value={channelModeValue(settings.channel_mode)} onValueChange={setChannelMode}>
+              <SelectTrigger>
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="average">Alle Kanäle mitteln</SelectItem>
+                <SelectItem value="loudest">Lautester Kanal (automatisch)</SelectItem>
+                {Array.from({ length: channelCount }, (_, channel) => (
+                  <SelectItem key={channel} value={`channel-${channel}`}>
+                    Nur Kanal {channel + 1}
+                  </SelectItem>
+                ))}
+                {typeof settings.channel_mode === 'object' && 'Channel' in settings.channel_mode &&
+                  settings.channel_mode.Channel >= channelCount && (
+                  <SelectItem value={`channel-${settings.channel_mode.Channel}`}>
+                    Nur Kanal {settings.channel_mode.Channel + 1} (nicht verfügbar)
+                  </SelectItem>
+                )}
+                {channelCount > 1 && <SelectItem value="mix">Eigene Mischung</SelectItem>}
+              </SelectContent>
+            </Select>
+            {channelModeValue(settings.channel_mode) === 'mix' && (
+              <div className="space-y-2">
+                {Array.from({ length: Math.max(channelCount, mixGains.length) }, (_, channel) => (
+                  <div key={channel} className="flex items-center gap-3">
+                    <span className="w-16 text-xs text-muted-foreground">Kanal {channel + 1}</span>
+                    <Slider
+                      value={[Math.round((mixGains[channel] ?? 0) * 100)]}
+                      onValueChange={([value]) => setMixGain(channel, value / 100)}
+                      min={0}
+                      max={100}
+                      step={5}
+                      className="flex-1"
+                    />
+                    <span className="w-10 text-right text-xs text-muted-foreground">
+                      {Math.round((mixGains[channel] ?? 0) * 100)}%
+                    </span>
+                  </div>
+                ))}
+              </div>
+            )}
+            <p className="text-xs text-muted-foreground">
+              Bei Audio-Interfaces mit mehreren Eingängen: einen Kanal wählen statt einen stillen Eingang mitzumitteln.
+            </p>
+          </div>
+        )}
+
+        {/* Fallback order */}
+        <div className="space-y-3">
+          <div className="flex items-center gap-2">
+            <ListOrdered className="h-4 w-4 text-muted-foreground" />
+            <Label className="text-sm font-medium">Ersatz-Reihenfolge</Label>
+          </div>
+          {settings.device_priority.length > 0 && (
+            <ul className="space-y-1">
+              {settings.device_priority.map((id, index) => {
+                const connected = devices.some((d) => d.id === id)
+                return (
+                  <li
+                    key={id}
+                    className="flex items-center gap-2 rounded-md border border-border/50 px-2 py-1 text-sm"
+                  >
+                    <span className="w-4 text-xs text-muted-foreground">{index + 1}.</span>
+                    <span className={cn('flex-1 truncate', !connected && 'text-muted-foreground')}>
+                      {deviceNameFromId(id)}
+                      {!connected && ' (nicht verbunden)'}
+                    </span>
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      onClick={() => movePriority(index, -1)}
+                      disabled={index === 0}
+                      className="h-7 w-7 p-0"
+                      aria-label="Nach oben"
+                    >
+                      <ArrowUp className="h-3.5 w-3.5" />
+                    </Button>
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      onClick={() => movePriority(index, 1)}
+                      disabled={index === settings.device_priority.length - 1}
+                      className="h-7 w-7 p-0"
+                      aria-label="Nach unten"
+                    >
+                      <ArrowDown className="h-3.5 w-3.5" />
+                    </Button>
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      onClick={() =>
+                        updateSettings({
+                          device_priority: settings.device_priority.filter((entry) => entry !== id),
+                        })
+                      }
+                      className="h-7 w-7 p-0"
+                      aria-label="Entfernen"
+                    >
+                      <X className="h-3.5 w-3.5" />
+                    </Button>
+                  </li>
+                )
+              })}
+            </ul>
+          )}
+          {priorityCandidates.length > 0 && (
+            <Select
+              value=""
+              onValueChange={(value) =>
+                updateSettings({ device_priority: [...settings.device_priority, value] })
+              }
+            >
+              <SelectTrigger>
+                <SelectValue placeholder="Mikrofon hinzufügen" />
+              </SelectTrigger>
+              <SelectContent>
+                {priorityCandidates.map((device) => (
+                  <SelectItem key={device.id} value={device.id}>
+                    {device.name}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          )}
+          <p className="text-xs text-muted-foreground">
+            Fehlt das ausgewählte Mikrofon, wird das erste verbundene Gerät dieser Liste verwendet – auch mitten in einer Aufnahme.
+          </p>
+        </div>
+
+        {/* System audio (monitor / loopback source) */}
+        <div className="space-y-3">
+          <Label className="flex items-center gap-2 text-sm font-medium">
+            <Speaker className="h-4 w-4 text-muted-foreground" />
+            Systemaudio
+          </Label>
+          <Select
+            value={settings.system_audio}
+            onValueChange={(value) => updateSettings({ system_audio: value as SystemAudioMode })}
+          >
+            <SelectTrigger>
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value="Off">Nicht aufnehmen</SelectItem>
+              <SelectItem value="Mix">Mit dem Mikrofon mischen</SelectItem>
+              <SelectItem value="SeparateTrack">Eigene Spur (Ich / Gegenüber)</SelectItem>
+            </SelectContent>
+          </Select>
+          {settings.system_audio !== 'Off' && (
+            systemSources.length > 0 ? (
+              <Select
+                value={settings.system_audio_device || 'auto'}
+                onValueChange={(value) =>
+                  updateSettings({ system_audio_device: value === 'auto' ? null : value })
+                }
+              >
+                <SelectTrigger>
+                  <SelectValue placeholder="Quelle auswählen" />
+                </SelectTrigger>
+                <SelectContent>
+                  <SelectItem value="auto">Automatisch</SelectItem>
+                  {systemSources.map((source) => (
+                    <SelectItem key={source.id} value={source.id}>
+                      {source.name}
+                      {source.is_default && ' (Standard)'}
+                    </SelectItem>
+                  ))}
+                  {settings.system_audio_device &&
+                    !systemSources.some((source) => source.id === settings.system_audio_device) && (
+                    <SelectItem value={settings.system_audio_device}>
+                      {deviceNameFromId(settings.system_audio_device)} (nicht verbunden)
+                    </SelectItem>
+                  )}
+                </SelectContent>
+              </Select>
+            ) : (
+              <p className="text-xs text-amber-600">
+                Keine Monitor- oder Loopback-Quelle gefunden. Aufnahmen enthalten nur das Mikrofon.
+              </p>
+            )
+          )}
+          <p className="text-xs text-muted-foreground">
+            Nimmt auf, was der Computer abspielt (z. B. die Gegenseite eines Calls). Als eigene Spur werden
+            beide Seiten getrennt transkribiert und im Text als „Ich“ und „Gegenüber“ markiert.
+          </p>
+        </div>
+
         {/* Mic Test */}
         <div className="space-y-3">
           <Label className="text-sm font-medium">Mikrofon testen</Label>
@@ -323,6 +635,29 @@ export function MicrophoneSettings() {
           </p>
         </div>
 
+        {/* Spectrum in the recording overlay */}
+        <div className="flex items-center justify-between py-2">
+          <div className="flex items-center space-x-4">
+            <div className="flex h-10 w-10 items-center justify-center rounded-lg bg-muted/50">
+              <BarChart3 className="h-5 w-5 text-muted-foreground" />
+            </div>
+            <div className="space-y-0.5">
+              <Label htmlFor="meter-spectrum" className="text-sm font-medium">
+                Frequenzspektrum anzeigen
+              </Label>
+              <p className="text-xs text-muted-foreground">
+                Zeigt während der Aufnahme Frequenzbänder statt der Wellenform im Overlay
+              </p>
+            </div>
+          </div>
+          <Switch
+            id="meter-spectrum"
+            checked={settings.meter_spectrum}
+            onCheckedChange={(checked) => updateSettings({ meter_spectrum: checked })}
+            className="data-[state=checked]:bg-primary"
+          />
+        </div>
+
         {/* Max Duration */}
         <div className="space-y-3">
           <div className="flex items-center justify-between">
@@ -347,6 +682,172 @@ export function MicrophoneSettings() {
           </p>
         </div>
 
+        {/* Silence Auto-Stop (Toggle mode only) */}
+        <div className="space-y-3">
+          <div className="flex items-center justify-between">
+            <Label className="flex items-center gap-2 text-sm font-medium">
+              <Timer className="h-4 w-4 text-muted-foreground" />
+              Automatisch stoppen bei Stille
+            </Label>
+            <span className="text-sm text-muted-foreground">
+              {settings.silence_auto_stop_seconds ? `${settings.silence_auto_stop_seconds} Sek.` : 'Aus'}
+            </span>
+          </div>
+          <Slider
+            value={[settings.silence_auto_stop_seconds ?? 0]}
+            onValueChange={([value]) => updateSettings({ silence_auto_stop_seconds: value > 0 ? value : null })}
+            min={0}
+            max={10}
+            step={1}
+            className="w-full"
+          />
+          <p className="text-xs text-muted-foreground">
+            Nur im Toggle-Modus: Die Aufnahme endet, wenn nach dem Sprechen so lange Stille herrscht.
+          </p>
+        </div>
+
+        {/* Trim Silence */}
+        <div className="flex items-center justify-between py-2">
+          <div className="flex items-center space-x-4">
+            <div className="flex h-10 w-10 items-center justify-center rounded-lg bg-muted/50">
+              <Scissors className="h-5 w-5 text-muted-foreground" />
+            </div>
+            <div className="space-y-0.5">
+              <Label htmlFor="trim-silence" className="text-sm font-medium">
+                Stille entfernen
+              </Label>
+              <p className="text-xs text-muted-foreground">
+                Stille am Anfang und Ende der Aufnahme abschneiden
+              </p>
+            </div>
+          </div>
+          <Switch
+            id="trim-silence"
+            checked={settings.trim_silence}
+            onCheckedChange={(checked) => updateSettings({ trim_silence: checked })}
+            className="data-[state=checked]:bg-primary"
+          />
+        </div>
+
+        {/* Skip takes without speech */}
+        <div className="flex items-center justify-between py-2">
+          <div className="flex items-center space-x-4">
+            <div className="flex h-10 w-10 items-center justify-center rounded-lg bg-muted/50">
+              <VolumeX className="h-5 w-5 text-muted-foreground" />
+            </div>
+            <div className="space-y-0.5">
+              <Label htmlFor="skip-silent-takes" className="text-sm font-medium">
+                Aufnahmen ohne Sprache überspringen
+              </Label>
+              <p className="text-xs text-muted-foreground">
+                Nicht transkribieren, wenn die Qualitätsprüfung keine Sprache findet
+              </p>
+            </div>
+          </div>
+          <Switch
+            id="skip-silent-takes"
+            checked={settings.skip_silent_takes}
+            onCheckedChange={(checked) => updateSettings({ skip_silent_takes: checked })}
+            className="data-[state=checked]:bg-primary"
+          />
+        </div>
+
+        {/* Preprocessing */}
+        <div className="space-y-3">
+          <Label className="flex items-center gap-2 text-sm font-medium">
+            <AudioWaveform className="h-4 w-4 text-muted-foreground" />
+            Audio-Vorverarbeitung
+          </Label>
+          <div className="space-y-3 rounded-lg bg-muted/20 p-4">
+            <div className="flex items-center justify-between">
+              <div className="space-y-0.5">
+                <Label htmlFor="high-pass-filter" className="text-sm">Trittschall- und Brummfilter</Label>
+                <p className="text-xs text-muted-foreground">Entfernt tiefes Brummen und Gleichspannungsanteile</p>
+              </div>
+              <Switch
+                id="high-pass-filter"
+                checked={settings.high_pass_filter}
+                onCheckedChange={(checked) => updateSettings({ high_pass_filter: checked })}
+                className="data-[state=checked]:bg-primary"
+              />
+            </div>
+            <div className="flex items-center justify-between">
+              <div className="space-y-0.5">
+                <Label htmlFor="noise-suppression" className="text-sm">Rauschunterdrückung</Label>
+                <p className="text-xs text-muted-foreground">Dämpft gleichmäßige Hintergrundgeräusche (Lüfter, Klimaanlage)</p>
+              </div>
+              <Switch
+                id="noise-suppression"
+                checked={settings.noise_suppression}
+                onCheckedChange={(checked) => updateSettings({ noise_suppression: checked })}
+                className="data-[state=checked]:bg-primary"
+              />
+            </div>
+            <div className="flex items-center justify-between">
+              <div className="space-y-0.5">
+                <Label htmlFor="loudness-normalization" className="text-sm">Lautstärke angleichen</Label>
+                <p className="text-xs text-muted-foreground">Hebt leise Aufnahmen automatisch an</p>
+              </div>
+              <Switch
+                id="loudness-normalization"
+                checked={settings.loudness_normalization}
+                onCheckedChange={(checked) => updateSettings({ loudness_normalization: checked })}
+                className="data-[state=checked]:bg-primary"
+              />
+            </div>
+          </div>
+        </div>
+
+        {/* Warm Microphone (pre-roll) */}
+        <div className="space-y-3">
+          <div className="flex items-center justify-between py-2">
+            <div className="flex items-center space-x-4">
+              <div className="flex h-10 w-10 items-center justify-center rounded-lg bg-muted/50">
+                <Radio className={cn('h-5 w-5', warmMicActive ? 'text-red-500' : 'text-muted-foreground')} />
+              </div>
+              <div className="space-y-0.5">
+                <Label htmlFor="warm-mic" className="text-sm font-medium">
+                  Mikrofon vorhalten
+                </Label>
+                <p className="text-xs text-muted-foreground">
+                  Das erste Wort wird nicht abgeschnitten, wenn Sie gleichzeitig mit dem Hotkey lossprechen
+                </p>
+              </div>
+            </div>
+            <Switch
+              id="warm-mic"
+              checked={settings.warm_mic}
+              onCheckedChange={(checked) => updateSettings({ warm_mic: checked })}
+              className="data-[state=checked]:bg-primary"
+            />
+          </div>
+          {settings.warm_mic && (
+            <div className="space-y-3 rounded-lg bg-muted/20 p-4">
+              <div className="flex items-center justify-between">
+                <Label className="text-sm">Vorlauf</Label>
+                <span className="text-sm text-muted-foreground">
+                  {(settings.pre_roll_ms / 1000).toFixed(1)} Sek.
+                </span>
+              </div>
+              <Slider
+                value={[settings.pre_roll_ms]}
+                onValueChange={([value]) => updateSettings({ pre_roll_ms: value })}
+                min={500}
+                max={2000}
+                step={250}
+                className="w-full"
+              />
+              <p className="text-xs text-muted-foreground leading-relaxed">
+                {warmMicActive
+                  ? 'Das Mikrofon ist dauerhaft geöffnet, die Mikrofon-Anzeige des Systems bleibt aktiv. '
+                  : 'Das Mikrofon konnte nicht geöffnet werden, Aufnahmen starten ohne Vorlauf. '}
+                Nur die letzten Sekunden werden im Arbeitsspeicher gehalten, nie auf die Festplatte
+                geschrieben und nach jeder Aufnahme verworfen. Abschalten geht auch über das Tray-Menü.
+              </p>
+            </div>
+          )}
+        </div>
+
         {/* Privacy Mode */}
         <div className="flex items-center justify-between py-2">
           <div className="flex items-center space-x-4">
@@ -2,12 +2,13 @@
 
 import { useEffect, useState } from 'react'
 import { Badge } from '@/components/ui/badge'
+import { Button } from '@/components/ui/button'
 import { Progress } from '@/components/ui/progress'
 import { useHotkey } from '@/hooks/use-hotkey'
 import { useAudioRecording } from '@/hooks/use-audio-recording'
 import { useTauri } from '@/hooks/use-tauri'
 import { useWhisper } from '@/hooks/use-whisper'
-import { Mic, Loader2, Volume2, Brain, CheckCircle2 } from 'lucide-react'
+import { Mic, Loader2, Volume2, Brain, CheckCircle2, Pause, Play } from 'lucide-react'
 import { cn } from '@/lib/utils'
 
 /** Maximum recording time in milliseconds (6 minutes) */
@@ -29,7 +30,7 @@ interface RecordingIndicatorProps {
  */
 export function RecordingIndicator({ compact = false, className }: RecordingIndicatorProps) {
   const { isTauri } = useTauri()
-  const { recordingState, recordingDuration, settings } = useHotkey()
+  const { recordingState, recordingDuration, settings, isPaused, togglePause } = useHotkey()
   const { audioLevel } = useAudioRecording()
   const { isTranscribing, lastTranscription } = useWhisper()
   const [showWarning, setShowWarning] = useState(false)
@@ -152,12 +153,13 @@ export function RecordingIndicator({ compact = false, className }: RecordingIndi
             <>
               <div
                 className={cn(
-                  'h-3 w-3 rounded-full bg-red-500',
-                  !showWarning && 'animate-pulse'
+                  'h-3 w-3 rounded-full',
+                  isPaused ? 'bg-yellow-500' : 'bg-red-500',
+                  !showWarning && !isPaused && 'animate-pulse'
                 )}
               />
               <span className="text-sm font-medium">
-                Aufnahme aktiv
+                {isPaused ? 'Aufnahme pausiert' : 'Aufnahme aktiv'}
               </span>
             </>
           ) : effectiveState === 'transcribing' ? (
@@ -215,12 +217,21 @@ export function RecordingIndicator({ compact = false, className }: RecordingIndi
               showWarning && '[&>div]:bg-yellow-500'
             )}
           />
-          <div className="flex justify-between mt-2 text-xs text-muted-foreground">
+          <div className="flex items-center justify-between mt-2 text-xs text-muted-foreground">
             <span>
               {settings.mode === 'PushToTalk'
                 ? 'Loslassen zum Beenden'
                 : 'Hotkey oder Esc zum Beenden'}
             </span>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={togglePause}
+              className="h-6 gap-1 px-2 text-xs"
+            >
+              {isPaused ? <Play className="h-3 w-3" /> : <Pause className="h-3 w-3" />}
+              {isPaused ? 'Fortsetzen' : 'Pause'}
+            </Button>
             <span>Max: 6:00</span>
           </div>
           {showWarning && (
@@ -229,7 +240,7 @@ export function RecordingIndicator({ compact = false, className }: RecordingIndi
             </p>
           )}
           {/* Low audio warning */}
-          {audioLevel < 5 && recordingDuration > 3000 && (
+          {!isPaused && audioLevel < 5 && recordingDuration > 3000 && (
             <p className="mt-2 text-xs text-yellow-600 font-medium">
               Mikrofon-Eingabe sehr leise. Bitte näher sprechen.
             </p>
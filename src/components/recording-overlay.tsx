@@ -1,11 +1,12 @@
 'use client'
 
-import { useEffect, useState, useCallback } from 'react'
+import { useEffect, useState, useCallback, useRef } from 'react'
 import { motion, AnimatePresence } from 'framer-motion'
 import { listen, UnlistenFn } from '@tauri-apps/api/event'
 import { Mic, Loader2, CheckCircle2, XCircle, Brain, Sparkles } from 'lucide-react'
 import { cn } from '@/lib/utils'
 import { useTauri } from '@/hooks/use-tauri'
+import { dbfsToLevel, type MeterFrame } from '@/hooks/use-audio-recording'
 
 /** Maximum recording time in milliseconds (6 minutes) */
 const MAX_RECORDING_TIME = 6 * 60 * 1000
@@ -29,6 +30,23 @@ interface ErrorPayload {
   message: string
 }
 
+interface DeviceChangedPayload {
+  device: string
+}
+
+/** Live transcript of the running take */
+interface PartialTranscriptPayload {
+  stable_text: string
+  unstable_text: string
+  audio_ms: number
+}
+
+/** How long a device notice stays in the overlay */
+const DEVICE_NOTICE_MS = 4000
+
+/** Characters of the live transcript shown in the overlay */
+const PARTIAL_TEXT_CHARS = 80
+
 /**
  * Floating Recording Overlay Component
  *
@@ -39,10 +57,17 @@ export function RecordingOverlay() {
   const { isTauri } = useTauri()
   const [status, setStatus] = useState<OverlayStatus>('idle')
   const [audioLevel, setAudioLevel] = useState(0)
+  const [meter, setMeter] = useState<MeterFrame | null>(null)
   const [recordingStartTime, setRecordingStartTime] = useState<number | null>(null)
   const [duration, setDuration] = useState(0)
   const [errorMessage, setErrorMessage] = useState<string | null>(null)
   const [isVisible, setIsVisible] = useState(false)
+  const [deviceNotice, setDeviceNotice] = useState<string | null>(null)
+  const [isPaused, setIsPaused] = useState(false)
+  const [partial, setPartial] = useState<PartialTranscriptPayload | null>(null)
+  // Paused time is left out of the recording, so the timer skips it too
+  const pausedAtRef = useRef<number | null>(null)
+  const pausedTotalRef = useRef(0)
 
   // Calculate if we should show warning (30 seconds before max)
   const showWarning = duration >= WARNING_TIME
@@ -62,7 +87,9 @@ export function RecordingOverlay() {
     }
 
     const interval = setInterval(() => {
-      setDuration(Date.now() - recordingStartTime)
+      if (pausedAtRef.current === null) {
+        setDuration(Date.now() - recordingStartTime - pausedTotalRef.current)
+      }
     }, 100)
 
     return () => clearInterval(interval)
@@ -80,6 +107,10 @@ export function RecordingOverlay() {
         setStatus('recording')
         setRecordingStartTime(event.payload?.timestamp || Date.now())
         setDuration(0)
+        setIsPaused(false)
+        pausedAtRef.current = null
+        pausedTotalRef.current = 0
+        setPartial(null)
         setErrorMessage(null)
         setIsVisible(true)
       })
@@ -90,6 +121,10 @@ export function RecordingOverlay() {
         setStatus('recording')
         setRecordingStartTime(Date.now())
         setDuration(0)
+        setIsPaused(false)
+        pausedAtRef.current = null
+        pausedTotalRef.current = 0
+        setPartial(null)
         setErrorMessage(null)
         setIsVisible(true)
       })
@@ -101,10 +136,63 @@ export function RecordingOverlay() {
       })
       unlisteners.push(unlistenLevel)
 
+      // Level meter pushed by the backend while recording
+      const unlistenMeter = await listen<MeterFrame>('audio-meter', (event) => {
+        setMeter(event.payload)
+        setAudioLevel(dbfsToLevel(event.payload.rms_dbfs))
+      })
+      unlisteners.push(unlistenMeter)
+
+      // Microphone dropped mid-recording, the recorder looks for a replacement
+      const unlistenDeviceLost = await listen('recording-device-lost', () => {
+        setDeviceNotice('Mikrofon getrennt – suche Ersatz...')
+      })
+      unlisteners.push(unlistenDeviceLost)
+
+      // Recording continues on another microphone
+      const unlistenDeviceChanged = await listen<DeviceChangedPayload>('recording-device-changed', (event) => {
+        setDeviceNotice(`Weiter mit: ${event.payload?.device || 'anderem Mikrofon'}`)
+        setTimeout(() => setDeviceNotice(null), DEVICE_NOTICE_MS)
+      })
+      unlisteners.push(unlistenDeviceChanged)
+
+      // Live transcript while recording (streaming transcription)
+      const unlistenPartial = await listen<PartialTranscriptPayload>('transcription-partial', (event) => {
+        setPartial(event.payload)
+      })
+      unlisteners.push(unlistenPartial)
+
+      // Live transcript failed, the take is transcribed from the file once it stops
+      const unlistenStreamError = await listen<string>('transcription-stream-error', () => {
+        setPartial(null)
+      })
+      unlisteners.push(unlistenStreamError)
+
+      // Take paused or resumed
+      const unlistenPaused = await listen('recording-paused', () => {
+        pausedAtRef.current = Date.now()
+        setIsPaused(true)
+        setAudioLevel(0)
+        setMeter(null)
+      })
+      unlisteners.push(unlistenPaused)
+
+      const unlistenResumed = await listen('recording-resumed', () => {
+        if (pausedAtRef.current !== null) {
+          pausedTotalRef.current += Date.now() - pausedAtRef.current
+          pausedAtRef.current = null
+        }
+        setIsPaused(false)
+      })
+      unlisteners.push(unlistenResumed)
+
       // Recording stopped - start processing
       const unlistenStopped = await listen('overlay-recording-stopped', () => {
         setStatus('processing')
         setAudioLevel(0)
+        setMeter(null)
+        setDeviceNotice(null)
+        setIsPaused(false)
       })
       unlisteners.push(unlistenStopped)
 
@@ -255,11 +343,28 @@ export function RecordingOverlay() {
             {/* Main Content */}
             <div className="flex flex-col gap-1 flex-1">
               {/* Status Text */}
-              <StatusText status={status} errorMessage={errorMessage} />
+              <StatusText status={status} errorMessage={errorMessage} paused={isPaused} />
 
               {/* Audio Level (only during recording) */}
               {status === 'recording' && (
-                <AudioLevelMeter level={audioLevel} />
+                <AudioLevelMeter
+                  level={audioLevel}
+                  peak={meter ? dbfsToLevel(meter.peak_dbfs) : audioLevel}
+                  clipped={(meter?.clip_count ?? 0) > 0}
+                />
+              )}
+
+              {/* Waveform or spectrum (only during recording) */}
+              {status === 'recording' && meter && <MeterGraph meter={meter} />}
+
+              {/* Live transcript until the final text is there */}
+              {partial && (status === 'recording' || status === 'processing' || status === 'transcribing') && (
+                <PartialText partial={partial} />
+              )}
+
+              {/* Device failover notice (only during recording) */}
+              {status === 'recording' && deviceNotice && (
+                <span className="text-xs text-amber-300">{deviceNotice}</span>
               )}
             </div>
 
@@ -274,6 +379,29 @@ export function RecordingOverlay() {
   )
 }
 
+/**
+ * Partial Text - Tail of the live transcript, the part that may still change is dimmed
+ */
+function PartialText({ partial }: { partial: PartialTranscriptPayload }) {
+  const unstable = partial.unstable_text
+  // Keep the newest words, cut the stable part first
+  const room = Math.max(PARTIAL_TEXT_CHARS - unstable.length, 0)
+  const stable =
+    partial.stable_text.length > room
+      ? '…' + partial.stable_text.slice(partial.stable_text.length - room)
+      : partial.stable_text
+
+  if (!stable && !unstable) return null
+
+  return (
+    <p className="max-w-[260px] text-xs leading-snug" aria-live="polite">
+      <span className="text-white/90">{stable}</span>
+      {stable && unstable && ' '}
+      <span className="text-white/50">{unstable}</span>
+    </p>
+  )
+}
+
 /**
  * Status Indicator - Shows visual icon for current state
  */
@@ -359,11 +487,19 @@ function StatusIndicator({ status }: { status: OverlayStatus }) {
 /**
  * Status Text - Shows current state description
  */
-function StatusText({ status, errorMessage }: { status: OverlayStatus; errorMessage: string | null }) {
+function StatusText({
+  status,
+  errorMessage,
+  paused,
+}: {
+  status: OverlayStatus
+  errorMessage: string | null
+  paused: boolean
+}) {
   const getText = () => {
     switch (status) {
       case 'recording':
-        return 'Aufnahme...'
+        return paused ? 'Pausiert' : 'Aufnahme...'
       case 'processing':
         return 'Verarbeite...'
       case 'transcribing':
@@ -384,7 +520,7 @@ function StatusText({ status, errorMessage }: { status: OverlayStatus; errorMess
   const getColor = () => {
     switch (status) {
       case 'recording':
-        return 'text-white'
+        return paused ? 'text-amber-300' : 'text-white'
       case 'processing':
         return 'text-blue-300'
       case 'transcribing':
@@ -417,18 +553,22 @@ function StatusText({ status, errorMessage }: { status: OverlayStatus; errorMess
 /**
  * Audio Level Meter - Visualizes microphone input level
  */
-function AudioLevelMeter({ level }: { level: number }) {
+function AudioLevelMeter({ level, peak, clipped }: { level: number; peak: number; clipped: boolean }) {
   // Create 10 bars
   const bars = 10
+  // Bar holding the current peak, drawn as an outline above the RMS level
+  const peakBar = Math.min(Math.ceil(peak / (100 / bars)) - 1, bars - 1)
 
   return (
     <div className="flex items-center gap-0.5 h-2">
       {Array.from({ length: bars }).map((_, i) => {
         const threshold = (i + 1) * (100 / bars)
         const isActive = level >= threshold - (100 / bars / 2)
+        const isPeak = !isActive && i === peakBar
 
         // Color coding: green -> yellow -> red
         const getBarColor = () => {
+          if (isPeak) return 'bg-white/50'
           if (!isActive) return 'bg-white/20'
           if (i < 6) return 'bg-green-400'
           if (i < 8) return 'bg-yellow-400'
@@ -441,13 +581,38 @@ function AudioLevelMeter({ level }: { level: number }) {
             className={cn('w-1.5 rounded-sm transition-colors', getBarColor())}
             initial={{ height: 4 }}
             animate={{
-              height: isActive ? 8 + (i * 0.5) : 4,
-              opacity: isActive ? 1 : 0.5
+              height: isActive || isPeak ? 8 + (i * 0.5) : 4,
+              opacity: isActive || isPeak ? 1 : 0.5
             }}
             transition={{ duration: 0.05 }}
           />
         )
       })}
+      {/* Clip indicator, stays lit for the rest of the take */}
+      <div
+        className={cn('ml-1 h-2 w-2 rounded-full', clipped ? 'bg-red-500' : 'bg-white/10')}
+        title={clipped ? 'Übersteuert' : undefined}
+      />
+    </div>
+  )
+}
+
+/**
+ * Meter Graph - Frequency bands when the spectrum is enabled, otherwise the waveform
+ */
+function MeterGraph({ meter }: { meter: MeterFrame }) {
+  const spectrum = meter.bands.length > 0
+  const values = spectrum ? meter.bands.map((band) => dbfsToLevel(band) / 100) : meter.waveform
+
+  return (
+    <div className="flex items-center gap-px h-3.5">
+      {values.map((value, i) => (
+        <div
+          key={i}
+          className={cn('flex-1 rounded-[1px]', spectrum ? 'self-end bg-sky-400/80' : 'bg-white/60')}
+          style={{ height: `${Math.max(value * 100, 8)}%` }}
+        />
+      ))}
     </div>
   )
 }
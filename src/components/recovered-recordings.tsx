@@ -0,0 +1,134 @@
+'use client'
+
+import { useState } from 'react'
+import { useCrashRecovery, RecoveryAction, OrphanedRecording } from '@/hooks/use-crash-recovery'
+import { RecordingStopResult } from '@/hooks/use-hotkey'
+import { Button } from '@/components/ui/button'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Archive, FileText, History, Trash2 } from 'lucide-react'
+import { showSuccess, showErrorByCode } from '@/lib/app-error'
+
+interface RecoveredRecordingsProps {
+  /** Runs a recovered recording through the transcription pipeline */
+  onTranscribe: (result: RecordingStopResult) => Promise<void>
+  /** Blocks the actions while another take is recorded or processed */
+  disabled?: boolean
+}
+
+/** Start time of a recording, or a placeholder if the journal was lost */
+function formatStartedAt(recording: OrphanedRecording): string {
+  if (!recording.started_at) return 'Unbekannter Zeitpunkt'
+  return new Date(recording.started_at).toLocaleString('de-DE', {
+    dateStyle: 'medium',
+    timeStyle: 'short',
+  })
+}
+
+function formatDuration(ms: number): string {
+  const totalSeconds = Math.round(ms / 1000)
+  return `${Math.floor(totalSeconds / 60)}:${(totalSeconds % 60).toString().padStart(2, '0')}`
+}
+
+/**
+ * Lists recordings the previous session left unprocessed (e.g. after a crash)
+ * and lets the user transcribe, archive or discard each of them.
+ */
+export function RecoveredRecordings({ onTranscribe, disabled = false }: RecoveredRecordingsProps) {
+  const { orphanedRecordings, resolveOrphanedRecording } = useCrashRecovery()
+  const [busyPath, setBusyPath] = useState<string | null>(null)
+
+  if (orphanedRecordings.length === 0) {
+    return null
+  }
+
+  const handleAction = async (recording: OrphanedRecording, action: RecoveryAction) => {
+    setBusyPath(recording.file_path)
+    try {
+      const result = await resolveOrphanedRecording(recording.file_path, action)
+      if (action === 'Transcribe' && result) {
+        await onTranscribe(result)
+      } else if (action === 'Archive') {
+        showSuccess('Aufnahme archiviert', 'Die Audiodatei liegt jetzt im Archiv-Ordner.')
+      }
+    } catch (err) {
+      console.error('Failed to recover recording:', err)
+      showErrorByCode('ERR_UNKNOWN', 'recovery', {
+        overrideMessage: 'Aufnahme konnte nicht verarbeitet werden',
+        details: String(err),
+      })
+    } finally {
+      setBusyPath(null)
+    }
+  }
+
+  return (
+    <Card className="border-amber-500/50">
+      <CardHeader className="pb-3">
+        <CardTitle className="flex items-center gap-2 text-base font-medium">
+          <History className="h-4 w-4 text-amber-500" />
+          Nicht verarbeitete Aufnahmen
+        </CardTitle>
+        <CardDescription>
+          Diese Aufnahmen wurden beim letzten Mal nicht fertig verarbeitet.
+        </CardDescription>
+      </CardHeader>
+      <CardContent className="space-y-3">
+        {orphanedRecordings.map((recording) => {
+          const busy = busyPath === recording.file_path
+          return (
+            <div
+              key={recording.file_path}
+              className="flex items-center justify-between gap-3 rounded-md bg-muted/50 p-3"
+            >
+              <div className="min-w-0 space-y-0.5">
+                <p className="text-sm font-medium">
+                  {formatStartedAt(recording)} · {formatDuration(recording.duration_ms)}
+                </p>
+                <p className="truncate text-xs text-muted-foreground">
+                  {[
+                    recording.device_name,
+                    recording.system_track && 'mit Systemaudio',
+                    recording.crashed && 'beim Absturz unterbrochen',
+                    recording.repaired && 'repariert',
+                  ].filter(Boolean).join(' · ') || 'Aufnahme'}
+                </p>
+              </div>
+              <div className="flex shrink-0 items-center gap-1">
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  onClick={() => handleAction(recording, 'Transcribe')}
+                  disabled={disabled || busy || recording.duration_ms === 0}
+                  aria-label="Transkribieren"
+                  title="Transkribieren"
+                >
+                  <FileText className="h-4 w-4" />
+                </Button>
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  onClick={() => handleAction(recording, 'Archive')}
+                  disabled={disabled || busy}
+                  aria-label="Audio archivieren"
+                  title="Audio archivieren"
+                >
+                  <Archive className="h-4 w-4" />
+                </Button>
+                <Button
+                  variant="ghost"
+                  size="icon"
+                  onClick={() => handleAction(recording, 'Discard')}
+                  disabled={disabled || busy}
+                  aria-label="Verwerfen"
+                  title="Verwerfen"
+                >
+                  <Trash2 className="h-4 w-4" />
+                </Button>
+              </div>
+            </div>
+          )
+        })}
+      </CardContent>
+    </Card>
+  )
+}
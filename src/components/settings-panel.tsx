@@ -13,6 +13,7 @@ import { WhisperSettings } from '@/components/whisper-settings'
 import { TextInsertSettings } from '@/components/text-insert-settings'
 import { OllamaSettings } from '@/components/ollama-settings'
 import { ArchiveSettings } from '@/components/archive-settings'
+import { EncryptionSettings } from '@/components/encryption-settings'
 import { AlertCircle, Power, Settings } from 'lucide-react'
 
 export function SettingsPanel() {
@@ -39,6 +40,9 @@ export function SettingsPanel() {
       {/* Archive Settings (PROJ-18) */}
       <ArchiveSettings />
 
+      {/* Encryption at rest */}
+      <EncryptionSettings />
+
       {/* General Settings */}
       <Card>
         <CardHeader className="pb-4">
@@ -2,15 +2,26 @@
 
 import { useEffect, useState, useCallback } from 'react'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { open } from '@tauri-apps/plugin-dialog'
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
 import { Label } from '@/components/ui/label'
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '@/components/ui/select'
 import { Switch } from '@/components/ui/switch'
+import { Checkbox } from '@/components/ui/checkbox'
 import { Button } from '@/components/ui/button'
 import { Alert, AlertDescription } from '@/components/ui/alert'
 import { Progress } from '@/components/ui/progress'
+import { Textarea } from '@/components/ui/textarea'
 import { Skeleton } from '@/components/ui/skeleton'
 import { useTauri } from '@/hooks/use-tauri'
+import {
+  AUTO_LANGUAGE,
+  formatModelSize,
+  getLanguageDisplayName,
+  type LanguageInfo,
+  type ModelInfo,
+} from '@/hooks/use-whisper'
 import {
   Brain,
   Download,
@@ -20,7 +31,11 @@ import {
   AlertCircle,
   Cpu,
   Languages,
+  Radio,
   HardDrive,
+  FileInput,
+  FolderInput,
+  Server,
   X,
   PlayCircle,
   PauseCircle,
@@ -28,26 +43,35 @@ import {
 import { toast } from 'sonner'
 import { cn } from '@/lib/utils'
 
-/** Available Whisper models */
-type WhisperModel = 'Tiny' | 'Small' | 'Medium'
+/** Id of a Whisper model in the model registry */
+type WhisperModel = string
 
-/** Supported languages */
-type WhisperLanguage = 'Auto' | 'German' | 'English'
+/** ISO code of the spoken language or AUTO_LANGUAGE */
+type WhisperLanguage = string
 
 /** Whisper settings from backend */
 interface WhisperSettings {
   model: WhisperModel
   language: WhisperLanguage
   use_gpu: boolean
+  /** Transcribe while recording, partial text shows in the overlay */
+  streaming: boolean
+  /** Languages auto-detection may pick from (ISO codes, empty: any) */
+  auto_languages: string[]
+  /** Base URLs tried before Hugging Face, e.g. a local file server */
+  download_mirrors: string[]
 }
 
 /** Model status info */
 interface ModelStatus {
   model: WhisperModel
+  info: ModelInfo
   downloaded: boolean
   file_size: number | null
   loaded: boolean
   downloading: boolean
+  /** Bytes of an interrupted download the next one resumes from */
+  partial_bytes: number | null
 }
 
 /** Download progress info */
@@ -58,21 +82,14 @@ interface DownloadProgress {
   speed_bps: number
   complete: boolean
   error: string | null
+  /** Attempt at the current URL, above 1 after the connection broke */
+  attempt: number
+  /** The file is complete and its checksum is being checked */
+  verifying: boolean
 }
 
-const ALL_MODELS: WhisperModel[] = ['Tiny', 'Small', 'Medium']
-
-const MODEL_INFO: Record<WhisperModel, { name: string; size: string; description: string }> = {
-  Tiny: { name: 'Tiny', size: '~75 MB', description: 'Schnell, weniger genau' },
-  Small: { name: 'Small', size: '~500 MB', description: 'Empfohlen - gute Balance' },
-  Medium: { name: 'Medium', size: '~1.5 GB', description: 'Sehr genau, langsamer' },
-}
-
-const LANGUAGE_OPTIONS: { value: WhisperLanguage; label: string }[] = [
-  { value: 'Auto', label: 'Automatisch erkennen' },
-  { value: 'German', label: 'Deutsch' },
-  { value: 'English', label: 'English' },
-]
+/** Offered first when limiting auto-detection (ISO codes) */
+const COMMON_LANGUAGES = ['de', 'en', 'fr', 'nl', 'pl', 'es', 'it']
 
 /** Format bytes to human-readable size */
 function formatBytes(bytes: number): string {
@@ -91,27 +108,20 @@ function formatSpeed(bps: number): string {
 export function WhisperSettings() {
   const { isTauri } = useTauri()
   const [settings, setSettings] = useState<WhisperSettings>({
-    model: 'Small',
-    language: 'Auto',
+    model: 'small',
+    language: AUTO_LANGUAGE,
     use_gpu: true,
+    streaming: false,
+    auto_languages: [],
+    download_mirrors: [],
   })
   const [modelStatus, setModelStatus] = useState<ModelStatus[]>([])
+  const [languages, setLanguages] = useState<LanguageInfo[]>([])
   const [downloadProgress, setDownloadProgress] = useState<DownloadProgress | null>(null)
   const [isLoading, setIsLoading] = useState(true)
   const [error, setError] = useState<string | null>(null)
-  const [interruptedDownloads, setInterruptedDownloads] = useState<Record<string, number>>({})
-
-  // Load interrupted downloads from localStorage on mount
-  useEffect(() => {
-    const stored = localStorage.getItem('whisper-interrupted-downloads')
-    if (stored) {
-      try {
-        setInterruptedDownloads(JSON.parse(stored))
-      } catch {
-        // Ignore parse errors
-      }
-    }
-  }, [])
+  const [importing, setImporting] = useState(false)
+  const [mirrorsDraft, setMirrorsDraft] = useState('')
 
   // Load settings and model status
   const loadData = useCallback(async () => {
@@ -122,12 +132,15 @@ export function WhisperSettings() {
 
     try {
       setError(null)
-      const [whisperSettings, status] = await Promise.all([
+      const [whisperSettings, status, supported] = await Promise.all([
         invoke<WhisperSettings>('get_whisper_settings'),
         invoke<ModelStatus[]>('get_whisper_model_status'),
+        invoke<LanguageInfo[]>('get_whisper_languages'),
       ])
       setSettings(whisperSettings)
+      setMirrorsDraft(whisperSettings.download_mirrors.join('\n'))
       setModelStatus(status)
+      setLanguages(supported)
     } catch (err) {
       console.error('Failed to load whisper data:', err)
       setError(err instanceof Error ? err.message : 'Fehler beim Laden der Whisper-Einstellungen')
@@ -140,30 +153,17 @@ export function WhisperSettings() {
     loadData()
   }, [loadData])
 
-  // Poll download progress
+  // Download progress, pushed by the backend
   useEffect(() => {
-    if (!isTauri || !downloadProgress || downloadProgress.complete) return
-
-    const interval = setInterval(async () => {
-      try {
-        const progress = await invoke<DownloadProgress | null>('get_whisper_download_progress')
-        if (progress) {
-          setDownloadProgress(progress)
-          if (progress.complete) {
-            // Refresh model status
-            const status = await invoke<ModelStatus[]>('get_whisper_model_status')
-            setModelStatus(status)
-          }
-        } else {
-          setDownloadProgress(null)
-        }
-      } catch {
-        // Ignore polling errors
-      }
-    }, 200)
+    if (!isTauri) return
 
-    return () => clearInterval(interval)
-  }, [isTauri, downloadProgress?.complete])
+    const unlisten = listen<DownloadProgress>('whisper-download-progress', (event) => {
+      setDownloadProgress(event.payload.complete || event.payload.error ? null : event.payload)
+    })
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [isTauri])
 
   // Update settings
   const updateSettings = async (newSettings: Partial<WhisperSettings>) => {
@@ -194,16 +194,12 @@ export function WhisperSettings() {
         speed_bps: 0,
         complete: false,
         error: null,
+        attempt: 1,
+        verifying: false,
       })
 
       await invoke('download_whisper_model', { model })
 
-      // Clear any interrupted state for this model
-      const newInterrupted = { ...interruptedDownloads }
-      delete newInterrupted[model]
-      setInterruptedDownloads(newInterrupted)
-      localStorage.setItem('whisper-interrupted-downloads', JSON.stringify(newInterrupted))
-
       // Refresh status after download
       const status = await invoke<ModelStatus[]>('get_whisper_model_status')
       setModelStatus(status)
@@ -212,22 +208,18 @@ export function WhisperSettings() {
       console.error('Download failed:', err)
       setError(err instanceof Error ? err.message : 'Download fehlgeschlagen')
       setDownloadProgress(null)
+      // A broken download leaves a partial file to resume
+      const status = await invoke<ModelStatus[]>('get_whisper_model_status').catch(() => null)
+      if (status) setModelStatus(status)
     }
   }
 
-  // Cancel download (BUG-3 fix: save progress for resume)
+  // Cancel download, the backend keeps the partial file for resume
   const cancelDownload = async () => {
     if (!isTauri) return
 
     try {
-      // Save the current progress before canceling
       if (downloadProgress && downloadProgress.downloaded_bytes > 0) {
-        const newInterrupted = {
-          ...interruptedDownloads,
-          [downloadProgress.model]: downloadProgress.downloaded_bytes,
-        }
-        setInterruptedDownloads(newInterrupted)
-        localStorage.setItem('whisper-interrupted-downloads', JSON.stringify(newInterrupted))
         toast.info('Download pausiert', {
           description: `${formatBytes(downloadProgress.downloaded_bytes)} gespeichert. Klicke "Fortsetzen" um weiterzumachen.`,
         })
@@ -239,26 +231,60 @@ export function WhisperSettings() {
     }
   }
 
-  // Resume download (BUG-3 fix)
+  // Resume download, the backend continues the partial file
   const resumeDownload = async (model: WhisperModel) => {
-    // Clear the interrupted state for this model
-    const newInterrupted = { ...interruptedDownloads }
-    delete newInterrupted[model]
-    setInterruptedDownloads(newInterrupted)
-    localStorage.setItem('whisper-interrupted-downloads', JSON.stringify(newInterrupted))
-
-    // Start the download again (backend handles resume if supported)
     await downloadModel(model)
   }
 
   // Check if a model has an interrupted download
   const hasInterruptedDownload = (model: WhisperModel): boolean => {
-    return model in interruptedDownloads && interruptedDownloads[model] > 0
+    return (getStatus(model)?.partial_bytes ?? 0) > 0
   }
 
   // Get interrupted download progress
   const getInterruptedProgress = (model: WhisperModel): number => {
-    return interruptedDownloads[model] || 0
+    return getStatus(model)?.partial_bytes ?? 0
+  }
+
+  // Save the mirror list, one URL per line
+  const saveMirrors = () => {
+    const mirrors = mirrorsDraft
+      .split('\n')
+      .map((line) => line.trim())
+      .filter(Boolean)
+    if (mirrors.join('\n') !== settings.download_mirrors.join('\n')) {
+      updateSettings({ download_mirrors: mirrors })
+    }
+  }
+
+  // Import ggml files from disk, e.g. on machines without internet access
+  const importModels = async (directory: boolean) => {
+    if (!isTauri) return
+
+    try {
+      const selected = await open({
+        directory,
+        multiple: false,
+        title: directory ? 'Ordner mit Modellen auswählen' : 'Modelldatei auswählen',
+        filters: directory ? undefined : [{ name: 'Whisper-Modell', extensions: ['bin'] }],
+      })
+      if (!selected || typeof selected !== 'string') return
+
+      setError(null)
+      setImporting(true)
+      const imported = await invoke<ModelInfo[]>('import_whisper_models', { path: selected })
+      toast.success(
+        imported.length === 1 ? 'Modell importiert' : `${imported.length} Modelle importiert`,
+        { description: imported.map((info) => info.display_name).join(', ') }
+      )
+      const status = await invoke<ModelStatus[]>('get_whisper_model_status')
+      setModelStatus(status)
+    } catch (err) {
+      console.error('Import failed:', err)
+      setError(typeof err === 'string' ? err : 'Import fehlgeschlagen')
+    } finally {
+      setImporting(false)
+    }
   }
 
   // Delete model
@@ -280,6 +306,14 @@ export function WhisperSettings() {
     return modelStatus.find((s) => s.model === model)
   }
 
+  const currentInfo = getStatus(settings.model)?.info
+
+  // Whisper's table under German names, alphabetically
+  const languageOptions = languages
+    .map((language) => ({ code: language.code, label: getLanguageDisplayName(language.code) }))
+    .sort((a, b) => a.label.localeCompare(b.label, 'de'))
+  const detectionChoices = Array.from(new Set([...COMMON_LANGUAGES, ...settings.auto_languages]))
+
   // Check if current model is ready
   const isCurrentModelReady = () => {
     const status = getStatus(settings.model)
@@ -351,20 +385,21 @@ export function WhisperSettings() {
           >
             <SelectTrigger>
               <SelectValue>
-                {MODEL_INFO[settings.model].name} ({MODEL_INFO[settings.model].size})
+                {currentInfo
+                  ? `${currentInfo.display_name} (${formatModelSize(currentInfo)})`
+                  : settings.model}
               </SelectValue>
             </SelectTrigger>
             <SelectContent>
-              {ALL_MODELS.map((model) => {
-                const status = getStatus(model)
-                const info = MODEL_INFO[model]
+              {modelStatus.map((status) => {
+                const info = status.info
                 return (
-                  <SelectItem key={model} value={model}>
+                  <SelectItem key={status.model} value={status.model}>
                     <div className="flex items-center gap-2">
                       <span>
-                        {info.name} ({info.size})
+                        {info.display_name} ({formatModelSize(info)})
                       </span>
-                      {status?.downloaded && (
+                      {status.downloaded && (
                         <CheckCircle2 className="h-3.5 w-3.5 text-green-500" />
                       )}
                     </div>
@@ -373,9 +408,13 @@ export function WhisperSettings() {
               })}
             </SelectContent>
           </Select>
-          <p className="text-xs text-muted-foreground">
-            {MODEL_INFO[settings.model].description}
-          </p>
+          {currentInfo && (
+            <p className="text-xs text-muted-foreground">
+              {currentInfo.description}
+              {currentInfo.description && ' · '}
+              ab {currentInfo.min_ram_gb} GB RAM
+            </p>
+          )}
         </div>
 
         {/* Model Status / Download */}
@@ -391,7 +430,11 @@ export function WhisperSettings() {
               <div className="flex items-center justify-between text-sm">
                 <span className="flex items-center gap-2">
                   <Loader2 className="h-4 w-4 animate-spin text-primary" />
-                  Download läuft...
+                  {downloadProgress.verifying
+                    ? 'Prüfe Datei...'
+                    : downloadProgress.attempt > 1
+                      ? `Verbindung unterbrochen, Versuch ${downloadProgress.attempt}...`
+                      : 'Download läuft...'}
                 </span>
                 <span className="text-muted-foreground">
                   {formatBytes(downloadProgress.downloaded_bytes)} /{' '}
@@ -469,21 +512,37 @@ export function WhisperSettings() {
               <div className="flex items-center gap-2">
                 <AlertCircle className="h-5 w-5 text-yellow-600" />
                 <div>
-                  <p className="text-sm font-medium text-yellow-700">Modell nicht heruntergeladen</p>
+                  <p className="text-sm font-medium text-yellow-700">
+                    {currentInfo?.custom ? 'Importierte Datei fehlt' : 'Modell nicht heruntergeladen'}
+                  </p>
                   <p className="text-xs text-yellow-600">
-                    {MODEL_INFO[settings.model].size} benötigt
+                    {currentInfo ? formatModelSize(currentInfo) : 'Speicherplatz'} benötigt
                   </p>
                 </div>
               </div>
-              <Button
-                variant="outline"
-                size="sm"
-                onClick={() => downloadModel(settings.model)}
-                className="h-8 gap-1 border-yellow-500/50 bg-yellow-500/10 hover:bg-yellow-500/20"
-              >
-                <Download className="h-4 w-4" />
-                Herunterladen
-              </Button>
+              {currentInfo?.custom ? (
+                // Imported models have no download URL
+                <Button
+                  variant="outline"
+                  size="sm"
+                  onClick={() => importModels(false)}
+                  disabled={importing}
+                  className="h-8 gap-1 border-yellow-500/50 bg-yellow-500/10 hover:bg-yellow-500/20"
+                >
+                  <FileInput className="h-4 w-4" />
+                  Erneut importieren
+                </Button>
+              ) : (
+                <Button
+                  variant="outline"
+                  size="sm"
+                  onClick={() => downloadModel(settings.model)}
+                  className="h-8 gap-1 border-yellow-500/50 bg-yellow-500/10 hover:bg-yellow-500/20"
+                >
+                  <Download className="h-4 w-4" />
+                  Herunterladen
+                </Button>
+              )}
             </div>
           )}
         </div>
@@ -499,13 +558,12 @@ export function WhisperSettings() {
             onValueChange={(value) => updateSettings({ language: value as WhisperLanguage })}
           >
             <SelectTrigger>
-              <SelectValue>
-                {LANGUAGE_OPTIONS.find((l) => l.value === settings.language)?.label}
-              </SelectValue>
+              <SelectValue>{getLanguageDisplayName(settings.language)}</SelectValue>
             </SelectTrigger>
-            <SelectContent>
-              {LANGUAGE_OPTIONS.map((option) => (
-                <SelectItem key={option.value} value={option.value}>
+            <SelectContent className="max-h-72">
+              <SelectItem value={AUTO_LANGUAGE}>{getLanguageDisplayName(AUTO_LANGUAGE)}</SelectItem>
+              {languageOptions.map((option) => (
+                <SelectItem key={option.code} value={option.code}>
                   {option.label}
                 </SelectItem>
               ))}
@@ -515,6 +573,34 @@ export function WhisperSettings() {
             "Automatisch" erkennt die Sprache selbst. Explizite Auswahl kann die Genauigkeit
             verbessern.
           </p>
+
+          {settings.language === AUTO_LANGUAGE && (
+            <div className="space-y-2 pt-1">
+              <Label className="text-xs font-medium text-muted-foreground">
+                Erkennung beschränken auf
+              </Label>
+              <div className="grid grid-cols-2 gap-2">
+                {detectionChoices.map((choice) => (
+                  <label key={choice} className="flex items-center gap-2 text-sm">
+                    <Checkbox
+                      checked={settings.auto_languages.includes(choice)}
+                      onCheckedChange={(checked) =>
+                        updateSettings({
+                          auto_languages: checked
+                            ? [...settings.auto_languages, choice]
+                            : settings.auto_languages.filter((code) => code !== choice),
+                        })
+                      }
+                    />
+                    {getLanguageDisplayName(choice)}
+                  </label>
+                ))}
+              </div>
+              <p className="text-xs text-muted-foreground">
+                Nichts ausgewählt: alle Sprachen, die Whisper kennt.
+              </p>
+            </div>
+          )}
         </div>
 
         {/* GPU Acceleration */}
@@ -540,6 +626,50 @@ export function WhisperSettings() {
           />
         </div>
 
+        {/* Live transcription */}
+        <div className="flex items-center justify-between py-2">
+          <div className="flex items-center space-x-4">
+            <div className="flex h-10 w-10 items-center justify-center rounded-lg bg-muted/50">
+              <Radio className="h-5 w-5 text-muted-foreground" />
+            </div>
+            <div className="space-y-0.5">
+              <Label htmlFor="streaming" className="text-sm font-medium">
+                Live-Transkription
+              </Label>
+              <p className="text-xs text-muted-foreground">
+                Text schon während der Aufnahme im Overlay zeigen, fertig kurz nach dem Stopp
+              </p>
+            </div>
+          </div>
+          <Switch
+            id="streaming"
+            checked={settings.streaming}
+            onCheckedChange={(checked) => updateSettings({ streaming: checked })}
+            className="data-[state=checked]:bg-primary"
+          />
+        </div>
+
+        {/* Download mirrors */}
+        <div className="space-y-3">
+          <Label htmlFor="download-mirrors" className="flex items-center gap-2 text-sm font-medium">
+            <Server className="h-4 w-4 text-muted-foreground" />
+            Download-Spiegel
+          </Label>
+          <Textarea
+            id="download-mirrors"
+            value={mirrorsDraft}
+            onChange={(e) => setMirrorsDraft(e.target.value)}
+            onBlur={saveMirrors}
+            placeholder="http://192.168.1.20:8000/whisper/"
+            rows={2}
+            className="font-mono text-xs"
+          />
+          <p className="text-xs text-muted-foreground">
+            Eine Adresse pro Zeile, z. B. ein lokaler Dateiserver mit den ggml-Dateien. Wird vor
+            Hugging Face versucht, jede Datei wird vor dem Einsatz geprüft.
+          </p>
+        </div>
+
         {/* Info Box */}
         <div className="rounded-lg bg-muted/20 p-4">
           <p className="text-xs text-muted-foreground leading-relaxed">
@@ -551,11 +681,43 @@ export function WhisperSettings() {
 
         {/* All Models Overview */}
         <div className="space-y-3">
-          <Label className="text-sm font-medium text-muted-foreground">Alle Modelle</Label>
+          <div className="flex items-center justify-between">
+            <Label className="text-sm font-medium text-muted-foreground">Alle Modelle</Label>
+            <div className="flex gap-2">
+              <Button
+                variant="outline"
+                size="sm"
+                onClick={() => importModels(false)}
+                disabled={importing}
+                className="h-8 gap-1"
+              >
+                {importing ? (
+                  <Loader2 className="h-4 w-4 animate-spin" />
+                ) : (
+                  <FileInput className="h-4 w-4" />
+                )}
+                Datei importieren
+              </Button>
+              <Button
+                variant="outline"
+                size="sm"
+                onClick={() => importModels(true)}
+                disabled={importing}
+                className="h-8 gap-1"
+              >
+                <FolderInput className="h-4 w-4" />
+                Ordner importieren
+              </Button>
+            </div>
+          </div>
+          <p className="text-xs text-muted-foreground">
+            Ohne Internet: Lade ggml-Modelle auf einem anderen Rechner herunter und importiere
+            sie hier. Bekannte Modelle werden an der Prüfsumme erkannt.
+          </p>
           <div className="grid gap-2">
-            {ALL_MODELS.map((model) => {
-              const status = getStatus(model)
-              const info = MODEL_INFO[model]
+            {modelStatus.map((status) => {
+              const model = status.model
+              const info = status.info
               const isSelected = settings.model === model
               const isDownloading =
                 downloadProgress?.model === model && !downloadProgress.complete
@@ -571,7 +733,7 @@ export function WhisperSettings() {
                   )}
                 >
                   <div className="flex items-center gap-3">
-                    {status?.downloaded ? (
+                    {status.downloaded ? (
                       <CheckCircle2 className="h-4 w-4 text-green-500" />
                     ) : isDownloading ? (
                       <Loader2 className="h-4 w-4 animate-spin text-primary" />
@@ -581,11 +743,14 @@ export function WhisperSettings() {
                       <div className="h-4 w-4 rounded-full border-2 border-muted-foreground/30" />
                     )}
                     <div>
-                      <p className="text-sm font-medium">{info.name}</p>
-                      <p className="text-xs text-muted-foreground">{info.size}</p>
+                      <p className="text-sm font-medium">{info.display_name}</p>
+                      <p className="text-xs text-muted-foreground">
+                        {formatModelSize(info)}
+                        {info.custom && ' · Importiert'}
+                      </p>
                     </div>
                   </div>
-                  {status?.downloaded && status.file_size && (
+                  {status.downloaded && status.file_size && (
                     <span className="text-xs text-muted-foreground">
                       {formatBytes(status.file_size)}
                     </span>
@@ -1,6 +1,6 @@
 'use client'
 
-import { useState, useEffect, useCallback, useRef } from 'react'
+import { useState, useEffect, useCallback } from 'react'
 import { invoke } from '@tauri-apps/api/core'
 import { listen, UnlistenFn } from '@tauri-apps/api/event'
 import { useTauri } from './use-tauri'
@@ -8,16 +8,96 @@ import { showErrorByCode } from '@/lib/app-error'
 
 /** Audio device info from backend */
 export interface AudioDevice {
+  /** Stable identity: host, index among same-named devices, config fingerprint and name */
   id: string
   name: string
   is_default: boolean
+  host: string
+  /** Most input channels the device offers (0 if unknown) */
+  channels: number
+}
+
+/** How input channels become the recorded mono signal (channel numbers are 0-based) */
+export type ChannelMode = 'Average' | 'Loudest' | { Channel: number } | { Mix: number[] }
+
+/** Whether system audio is recorded: not at all, mixed into the take or as its own track */
+export type SystemAudioMode = 'Off' | 'Mix' | 'SeparateTrack'
+
+/** Region of detected speech in a recording (ms from start) */
+export interface SpeechSegment {
+  start_ms: number
+  end_ms: number
+}
+
+/** Audio missing from a take while the recorder switched microphones */
+export interface DeviceGap {
+  at_ms: number
+  lost_ms: number
+}
+
+/** Spot where the take was paused; the paused time is not in the file */
+export interface PauseMarker {
+  at_ms: number
+  paused_ms: number
+}
+
+/** Overall verdict of the pre-transcription quality check */
+export type RecordingQuality = 'Good' | 'Fair' | 'Poor' | 'NoSpeech'
+
+export type QualityIssue = 'NoSignal' | 'TooQuiet' | 'Clipping' | 'Noisy' | 'DcOffset' | 'NoSpeech'
+
+/** Quality check of the raw input of a take */
+export interface QualityReport {
+  quality: RecordingQuality
+  speech_level_dbfs: number
+  noise_floor_dbfs: number
+  peak_dbfs: number
+  snr_db: number
+  /** Share of samples at full scale (0-1) */
+  clipping_ratio: number
+  /** Share of the take with speech (0-1) */
+  speech_ratio: number
+  dc_offset: number
+  issues: QualityIssue[]
+  /** Suggested fixes, one per issue (German, ready to display) */
+  suggestions: string[]
+}
+
+/** Input configuration the device was opened with */
+export interface CaptureConfig {
+  sample_rate: number
+  channels: number
+  sample_format: string
 }
 
 /** Audio settings from backend */
 export interface AudioSettings {
   device_id: string | null
+  /** Fallback devices, in order, when the selected one is missing */
+  device_priority: string[]
   max_duration_minutes: number
   privacy_mode: boolean
+  trim_silence: boolean
+  silence_auto_stop_seconds: number | null
+  high_pass_filter: boolean
+  noise_suppression: boolean
+  loudness_normalization: boolean
+  warm_mic: boolean
+  pre_roll_ms: number
+  /** Add frequency bands to the pushed level meter */
+  meter_spectrum: boolean
+  /** Skip transcription of takes the quality check finds no speech in */
+  skip_silent_takes: boolean
+  channel_mode: ChannelMode
+  system_audio: SystemAudioMode
+  /** Monitor or loopback source (null = first one found) */
+  system_audio_device: string | null
+}
+
+/** System audio of a take, recorded to its own file on the same timeline */
+export interface SystemTrack {
+  file_path: string
+  speech_segments: SpeechSegment[]
 }
 
 /** Recording result from backend */
@@ -25,11 +105,41 @@ export interface RecordingResult {
   file_path: string
   duration_ms: number
   privacy_mode: boolean
+  speech_segments: SpeechSegment[]
+  dropped_frames: number
+  capture_config: CaptureConfig | null
+  device_gaps: DeviceGap[]
+  pause_markers: PauseMarker[]
+  quality: QualityReport | null
+  system_track: SystemTrack | null
+  trim_offset_ms: number
+}
+
+/** Level meter reading pushed by the backend while recording ('audio-meter') */
+export interface MeterFrame {
+  peak_dbfs: number
+  rms_dbfs: number
+  /** Clipped samples since the take started */
+  clip_count: number
+  /** Peak magnitude (0-1) per slice of the last interval */
+  waveform: number[]
+  /** Level in dBFS per frequency band, empty unless the spectrum is enabled */
+  bands: number[]
+}
+
+/** Lowest level shown by the meters, in dBFS */
+export const METER_RANGE_DBFS = 60
+
+/** Map a dBFS value onto the 0-100 scale of the level meters */
+export function dbfsToLevel(dbfs: number): number {
+  return Math.round(Math.min(Math.max((dbfs + METER_RANGE_DBFS) / METER_RANGE_DBFS, 0), 1) * 100)
 }
 
 interface UseAudioRecordingReturn {
   /** Current audio level (0-100) */
   audioLevel: number
+  /** Latest level meter reading (null when not recording) */
+  meter: MeterFrame | null
   /** Whether audio is currently being recorded */
   isRecording: boolean
   /** List of available audio devices */
@@ -54,8 +164,21 @@ interface UseAudioRecordingReturn {
 
 const DEFAULT_SETTINGS: AudioSettings = {
   device_id: null,
+  device_priority: [],
   max_duration_minutes: 6,
   privacy_mode: true,
+  trim_silence: true,
+  silence_auto_stop_seconds: null,
+  high_pass_filter: false,
+  noise_suppression: false,
+  loudness_normalization: false,
+  warm_mic: false,
+  pre_roll_ms: 1500,
+  meter_spectrum: false,
+  skip_silent_takes: true,
+  channel_mode: 'Average',
+  system_audio: 'Off',
+  system_audio_device: null,
 }
 
 /**
@@ -64,13 +187,13 @@ const DEFAULT_SETTINGS: AudioSettings = {
 export function useAudioRecording(): UseAudioRecordingReturn {
   const { isTauri } = useTauri()
   const [audioLevel, setAudioLevel] = useState(0)
+  const [meter, setMeter] = useState<MeterFrame | null>(null)
   const [isRecording, setIsRecording] = useState(false)
   const [devices, setDevices] = useState<AudioDevice[]>([])
   const [settings, setSettings] = useState<AudioSettings>(DEFAULT_SETTINGS)
   const [error, setError] = useState<string | null>(null)
 
   // Polling interval for audio level
-  const levelPollRef = useRef<ReturnType<typeof setInterval> | null>(null)
 
   // Load initial data
   useEffect(() => {
@@ -156,30 +279,18 @@ export function useAudioRecording(): UseAudioRecordingReturn {
     }
   }, [isTauri])
 
-  // Poll audio level while recording
+  // Follow the level meter pushed by the backend while recording
   useEffect(() => {
-    if (!isTauri || !isRecording) {
-      if (levelPollRef.current) {
-        clearInterval(levelPollRef.current)
-        levelPollRef.current = null
-      }
-      return
-    }
+    if (!isTauri || !isRecording) return
 
-    levelPollRef.current = setInterval(async () => {
-      try {
-        const level = await invoke<number>('get_audio_level')
-        setAudioLevel(level)
-      } catch {
-        // Ignore errors during polling
-      }
-    }, 100)
+    const unlisten = listen<MeterFrame>('audio-meter', (event) => {
+      setMeter(event.payload)
+      setAudioLevel(dbfsToLevel(event.payload.rms_dbfs))
+    })
 
     return () => {
-      if (levelPollRef.current) {
-        clearInterval(levelPollRef.current)
-        levelPollRef.current = null
-      }
+      unlisten.then((fn) => fn())
+      setMeter(null)
     }
   }, [isTauri, isRecording])
 
@@ -278,6 +389,7 @@ export function useAudioRecording(): UseAudioRecordingReturn {
 
   return {
     audioLevel,
+    meter,
     isRecording,
     devices,
     settings,
@@ -3,6 +3,7 @@
 import { useState, useEffect, useCallback } from 'react'
 import { invoke } from '@tauri-apps/api/core'
 import { useTauri } from './use-tauri'
+import type { RecordingResult } from './use-audio-recording'
 
 /** Crash information from the backend */
 export interface CrashInfo {
@@ -11,6 +12,24 @@ export interface CrashInfo {
   location: string | null
 }
 
+/** Recording the previous session never processed, found on startup */
+export interface OrphanedRecording {
+  file_path: string
+  /** Start of the take (RFC 3339) */
+  started_at: string | null
+  device_name: string | null
+  duration_ms: number
+  privacy_mode: boolean
+  /** The WAV header had to be fixed to match the audio on disk */
+  repaired: boolean
+  /** Last written around the time of the recorded crash */
+  crashed: boolean
+  /** Separate system audio track of the take, if it survived */
+  system_track: string | null
+}
+
+export type RecoveryAction = 'Transcribe' | 'Archive' | 'Discard'
+
 /**
  * Hook to manage crash recovery and notifications
  */
@@ -19,6 +38,7 @@ export function useCrashRecovery() {
   const [crashInfo, setCrashInfo] = useState<CrashInfo | null>(null)
   const [hasCrash, setHasCrash] = useState(false)
   const [crashLog, setCrashLog] = useState<string>('')
+  const [orphanedRecordings, setOrphanedRecordings] = useState<OrphanedRecording[]>([])
 
   /** Check if there was a previous crash */
   const checkForCrash = useCallback(async () => {
@@ -37,6 +57,32 @@ export function useCrashRecovery() {
     }
   }, [isTauri])
 
+  /** Load the recordings the previous session left unprocessed */
+  const checkForOrphans = useCallback(async () => {
+    if (!isTauri) return []
+
+    try {
+      const orphans = await invoke<OrphanedRecording[]>('get_orphaned_recordings')
+      setOrphanedRecordings(orphans)
+      return orphans
+    } catch (error) {
+      console.error('Failed to check for unprocessed recordings:', error)
+      return []
+    }
+  }, [isTauri])
+
+  /**
+   * Transcribe, archive or discard a recovered recording.
+   * 'Transcribe' returns the recording for the normal transcription pipeline.
+   */
+  const resolveOrphanedRecording = useCallback(async (filePath: string, action: RecoveryAction) => {
+    if (!isTauri) return null
+
+    const result = await invoke<RecordingResult | null>('resolve_orphaned_recording', { filePath, action })
+    setOrphanedRecordings((orphans) => orphans.filter((orphan) => orphan.file_path !== filePath))
+    return result
+  }, [isTauri])
+
   /** Clear the crash notification (user acknowledged) */
   const clearCrashNotification = useCallback(async () => {
     if (!isTauri) return
@@ -80,14 +126,17 @@ export function useCrashRecovery() {
   useEffect(() => {
     if (isTauri) {
       checkForCrash()
+      checkForOrphans()
     }
-  }, [isTauri, checkForCrash])
+  }, [isTauri, checkForCrash, checkForOrphans])
 
   return {
     hasCrash,
     crashInfo,
     crashLog,
+    orphanedRecordings,
     checkForCrash,
+    resolveOrphanedRecording,
     clearCrashNotification,
     getCrashLog,
     clearCrashLog,
@@ -0,0 +1,85 @@
+'use client'
+
+import { useState, useEffect, useCallback } from 'react'
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { useTauri } from './use-tauri'
+
+/** Where the key for encryption at rest comes from */
+export type KeySource = 'Keyring' | 'Passphrase'
+
+/** Encryption at rest state from the backend */
+export interface EncryptionStatus {
+  enabled: boolean
+  key_source: KeySource
+  /** Keys are loaded; false while a passphrase still has to be entered */
+  unlocked: boolean
+}
+
+/**
+ * Hook for the optional encryption of recordings, archive and configs
+ */
+export function useEncryption() {
+  const { isTauri } = useTauri()
+  const [status, setStatus] = useState<EncryptionStatus | null>(null)
+
+  const refresh = useCallback(async () => {
+    if (!isTauri) return
+    try {
+      setStatus(await invoke<EncryptionStatus>('get_encryption_status'))
+    } catch (error) {
+      console.error('Failed to load encryption status:', error)
+    }
+  }, [isTauri])
+
+  useEffect(() => {
+    refresh()
+  }, [refresh])
+
+  // Other windows and components may unlock too
+  useEffect(() => {
+    if (!isTauri) return
+    const unlisten = listen('encryption-unlocked', () => {
+      refresh()
+    })
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [isTauri, refresh])
+
+  /** Turn encryption on; returns the number of files sealed */
+  const enable = useCallback(async (keySource: KeySource, passphrase?: string) => {
+    const sealed = await invoke<number>('enable_encryption', {
+      keySource,
+      passphrase: passphrase ?? null,
+    })
+    await refresh()
+    return sealed
+  }, [refresh])
+
+  /** Decrypt everything and turn encryption off */
+  const disable = useCallback(async () => {
+    const opened = await invoke<number>('disable_encryption')
+    await refresh()
+    return opened
+  }, [refresh])
+
+  const unlock = useCallback(async (passphrase: string) => {
+    await invoke('unlock_encryption', { passphrase })
+    await refresh()
+  }, [refresh])
+
+  /** Reseal everything with a new key, optionally with a new passphrase */
+  const rotateKey = useCallback(async (newPassphrase?: string) => {
+    return invoke<number>('rotate_encryption_key', { newPassphrase: newPassphrase ?? null })
+  }, [])
+
+  return {
+    status,
+    isLocked: status?.enabled === true && !status.unlocked,
+    enable,
+    disable,
+    unlock,
+    rotateKey,
+  }
+}
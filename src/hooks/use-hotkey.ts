@@ -5,6 +5,7 @@ import { invoke } from '@tauri-apps/api/core'
 import { listen, UnlistenFn } from '@tauri-apps/api/event'
 import { useTauri } from './use-tauri'
 import { showErrorByCode, showWarning, showInfo } from '@/lib/app-error'
+import type { CaptureConfig, DeviceGap, PauseMarker, QualityReport, SpeechSegment, SystemTrack } from './use-audio-recording'
 
 /** Hotkey mode: Push-to-Talk or Toggle */
 export type HotkeyMode = 'PushToTalk' | 'Toggle'
@@ -14,6 +15,8 @@ export interface HotkeySettings {
   shortcut: string
   mode: HotkeyMode
   enabled: boolean
+  /** Optional shortcut that pauses and resumes the running take */
+  pause_shortcut: string | null
 }
 
 /** Recording state for the hotkey system */
@@ -24,6 +27,14 @@ export interface RecordingStopResult {
   file_path: string
   duration_ms: number
   privacy_mode: boolean
+  speech_segments: SpeechSegment[]
+  dropped_frames: number
+  capture_config: CaptureConfig | null
+  device_gaps: DeviceGap[]
+  pause_markers: PauseMarker[]
+  quality: QualityReport | null
+  system_track: SystemTrack | null
+  trim_offset_ms: number
 }
 
 /** App category for context-aware processing (PROJ-8) */
@@ -68,8 +79,12 @@ interface UseHotkeyReturn {
   requestAccessibilityPermission: () => Promise<void>
   /** Recording start time (for duration display) */
   recordingStartTime: number | null
-  /** Time elapsed since recording started (in ms) */
+  /** Time recorded so far (in ms), paused spans excluded */
   recordingDuration: number
+  /** Whether the running take is paused */
+  isPaused: boolean
+  /** Pause or resume the running take */
+  togglePause: () => Promise<void>
   /** Current app context detected at recording start (PROJ-8/PROJ-9) */
   currentContext: AppContext | null
   /** Reset recording state to idle (call after transcription completes) */
@@ -83,9 +98,10 @@ const DEFAULT_SETTINGS: HotkeySettings = {
   shortcut: 'CommandOrControl+Shift+Space',
   mode: 'Toggle',
   enabled: true,
+  pause_shortcut: null,
 }
 
-/** Default max recording time in minutes */
+/** Max recording time in minutes without the recorder (browser mode) */
 const DEFAULT_MAX_DURATION_MINUTES = 6
 
 /** Warning offset before max (30 seconds) */
@@ -105,8 +121,8 @@ export function useHotkey(events?: HotkeyEvents): UseHotkeyReturn {
   const [error, setError] = useState<string | null>(null)
   const [recordingStartTime, setRecordingStartTime] = useState<number | null>(null)
   const [recordingDuration, setRecordingDuration] = useState(0)
+  const [isPaused, setIsPaused] = useState(false)
   const [accessibilityPermissionRequired, setAccessibilityPermissionRequired] = useState(false)
-  const [maxDurationMinutes, setMaxDurationMinutes] = useState(DEFAULT_MAX_DURATION_MINUTES)
   // PROJ-8/PROJ-9: Store detected app context at recording start
   const [currentContext, setCurrentContext] = useState<AppContext | null>(null)
 
@@ -120,6 +136,11 @@ export function useHotkey(events?: HotkeyEvents): UseHotkeyReturn {
   const currentContextRef = useRef<AppContext | null>(null)
   // BUG-8 FIX: Guard to prevent multiple concurrent stopRecording calls
   const isStoppingRef = useRef<boolean>(false)
+  // Capture overruns are reported once per take
+  const overrunWarnedRef = useRef<boolean>(false)
+  // Pause bookkeeping so the duration only counts recorded time
+  const pausedAtRef = useRef<number | null>(null)
+  const pausedTotalRef = useRef<number>(0)
 
   // Load settings on mount
   useEffect(() => {
@@ -132,10 +153,6 @@ export function useHotkey(events?: HotkeyEvents): UseHotkeyReturn {
       try {
         const loaded = await invoke<HotkeySettings>('get_hotkey_settings')
         setSettings(loaded)
-
-        // Load audio settings for max duration (BUG-1 fix)
-        const audioSettings = await invoke<{ max_duration_minutes: number }>('get_audio_settings')
-        setMaxDurationMinutes(audioSettings.max_duration_minutes || DEFAULT_MAX_DURATION_MINUTES)
       } catch (err) {
         console.error('Failed to load hotkey settings:', err)
         setError('Hotkey-Einstellungen konnten nicht geladen werden')
@@ -164,6 +181,10 @@ export function useHotkey(events?: HotkeyEvents): UseHotkeyReturn {
     setRecordingStartTime(now)
     setRecordingDuration(0)
     setError(null)
+    overrunWarnedRef.current = false
+    setIsPaused(false)
+    pausedAtRef.current = null
+    pausedTotalRef.current = 0
     // PROJ-8/PROJ-9: Store context for later use in text processing
     setCurrentContext(context || null)
     currentContextRef.current = context || null
@@ -184,7 +205,9 @@ export function useHotkey(events?: HotkeyEvents): UseHotkeyReturn {
 
     // Start duration tracking
     durationIntervalRef.current = setInterval(() => {
-      setRecordingDuration(Date.now() - now)
+      if (pausedAtRef.current === null) {
+        setRecordingDuration(Date.now() - now - pausedTotalRef.current)
+      }
     }, 100)
 
     // Start health check polling (BUG-2 fix: Device disconnect handling)
@@ -194,10 +217,11 @@ export function useHotkey(events?: HotkeyEvents): UseHotkeyReturn {
           const streamError = await invoke<string | null>('check_audio_health')
           if (streamError) {
             // Device disconnected or stream error
+            // The take is streamed to disk, so finish it instead of discarding it
             showErrorByCode('ERR_MIC_DISCONNECTED', 'hotkey', {
-              details: 'Die Aufnahme wurde abgebrochen, da das Mikrofon getrennt wurde.',
+              details: 'Die Aufnahme wurde beendet, da das Mikrofon getrennt wurde. Der bisherige Teil wird verarbeitet.',
             })
-            cancelRecording('Mikrofon getrennt')
+            stopRecording()
           }
         } catch (err) {
           console.error('Health check failed:', err)
@@ -205,24 +229,22 @@ export function useHotkey(events?: HotkeyEvents): UseHotkeyReturn {
       }, 500) // Check every 500ms
     }
 
-    // Calculate timeouts based on user settings (BUG-1 fix)
-    const maxRecordingTimeMs = maxDurationMinutes * 60 * 1000
-    const warningTimeMs = maxRecordingTimeMs - WARNING_OFFSET_MS
-
-    // Set warning timeout (30 seconds before max)
-    warningTimeoutRef.current = setTimeout(() => {
-      showWarning('Noch 30 Sekunden', 'Maximale Aufnahmezeit wird bald erreicht')
-    }, warningTimeMs)
-
-    // Set max timeout (user-configured duration)
-    maxTimeoutRef.current = setTimeout(() => {
-      showInfo('Maximale Aufnahmezeit erreicht', 'Aufnahme wird automatisch beendet')
-      stopRecording()
-    }, maxRecordingTimeMs)
+    // Max duration is enforced by the recorder (recording-limit-* events),
+    // without it the timers below stand in
+    if (!isTauri) {
+      const maxRecordingTimeMs = DEFAULT_MAX_DURATION_MINUTES * 60 * 1000
+      warningTimeoutRef.current = setTimeout(() => {
+        showWarning('Noch 30 Sekunden', 'Maximale Aufnahmezeit wird bald erreicht')
+      }, maxRecordingTimeMs - WARNING_OFFSET_MS)
+      maxTimeoutRef.current = setTimeout(() => {
+        showInfo('Maximale Aufnahmezeit erreicht', 'Aufnahme wird automatisch beendet')
+        stopRecording()
+      }, maxRecordingTimeMs)
+    }
 
     // PROJ-8/PROJ-9: Pass context to callback
     events?.onRecordingStart?.(context)
-  }, [settings.mode, events, isTauri, maxDurationMinutes])
+  }, [settings.mode, events, isTauri])
 
   // Stop recording handler
   const stopRecording = useCallback(async () => {
@@ -234,7 +256,7 @@ export function useHotkey(events?: HotkeyEvents): UseHotkeyReturn {
     }
     isStoppingRef.current = true
 
-    // Clear timeouts
+    // Clear timers
     if (maxTimeoutRef.current) {
       clearTimeout(maxTimeoutRef.current)
       maxTimeoutRef.current = null
@@ -279,13 +301,14 @@ export function useHotkey(events?: HotkeyEvents): UseHotkeyReturn {
         }
       } else {
         // Non-Tauri mode: pass dummy result
-        await events?.onRecordingStop?.({ file_path: '', duration_ms: 0, privacy_mode: false }, currentContextRef.current || undefined)
+        await events?.onRecordingStop?.({ file_path: '', duration_ms: 0, privacy_mode: false, speech_segments: [], dropped_frames: 0, capture_config: null, device_gaps: [], pause_markers: [], quality: null, system_track: null, trim_offset_ms: 0 }, currentContextRef.current || undefined)
       }
     } finally {
       // Reset state after transcription completes (BUGFIX: was resetting prematurely)
       setRecordingState('idle')
       setRecordingStartTime(null)
       setRecordingDuration(0)
+      setIsPaused(false)
       setCurrentContext(null)
       currentContextRef.current = null
       // BUG-8 FIX: Reset guard after completion (always, even on error)
@@ -296,7 +319,7 @@ export function useHotkey(events?: HotkeyEvents): UseHotkeyReturn {
   // Cancel recording handler
   const cancelRecording = useCallback(
     async (reason: string) => {
-      // Clear timeouts
+      // Clear timers
       if (maxTimeoutRef.current) {
         clearTimeout(maxTimeoutRef.current)
         maxTimeoutRef.current = null
@@ -333,6 +356,7 @@ export function useHotkey(events?: HotkeyEvents): UseHotkeyReturn {
       setRecordingState('idle')
       setRecordingStartTime(null)
       setRecordingDuration(0)
+      setIsPaused(false)
 
       events?.onRecordingCancel?.(reason)
     },
@@ -400,6 +424,73 @@ export function useHotkey(events?: HotkeyEvents): UseHotkeyReturn {
       })
       if (mounted) unlisteners.push(unlistenStop)
 
+      // Recorder is 30 seconds away from the configured max duration
+      const unlistenLimitWarning = await listen<{ remaining_ms: number }>('recording-limit-warning', (event) => {
+        if (!mounted) return
+        const seconds = Math.round(event.payload.remaining_ms / 1000)
+        showWarning(`Noch ${seconds} Sekunden`, 'Maximale Aufnahmezeit wird bald erreicht')
+      })
+      if (mounted) unlisteners.push(unlistenLimitWarning)
+
+      // Recorder hit the max duration and already finalized the WAV file
+      const unlistenLimitReached = await listen('recording-limit-reached', () => {
+        if (!mounted) return
+        if (recordingStateRef.current === 'recording') {
+          showInfo('Maximale Aufnahmezeit erreicht', 'Aufnahme wird automatisch beendet')
+          stopRecording()
+        }
+      })
+      if (mounted) unlisteners.push(unlistenLimitReached)
+
+      // Toggle mode: recorder stopped itself after the configured silence
+      const unlistenSilenceStop = await listen('recording-silence-stop', () => {
+        if (!mounted) return
+        if (recordingStateRef.current === 'recording') {
+          stopRecording()
+        }
+      })
+      if (mounted) unlisteners.push(unlistenSilenceStop)
+
+      // Recorder switched to another microphone after the current one dropped
+      const unlistenDeviceChanged = await listen<{ device: string }>('recording-device-changed', (event) => {
+        if (!mounted) return
+        showInfo('Mikrofon gewechselt', `Die Aufnahme läuft weiter mit: ${event.payload.device}`)
+      })
+      if (mounted) unlisteners.push(unlistenDeviceChanged)
+
+      // System audio was requested but the take runs with the microphone only
+      const unlistenSystemAudio = await listen<{ reason: string }>('recording-system-audio-unavailable', (event) => {
+        if (!mounted) return
+        showWarning('Systemaudio nicht verfügbar', `Es wird nur das Mikrofon aufgenommen (${event.payload.reason})`)
+      })
+      if (mounted) unlisteners.push(unlistenSystemAudio)
+
+      // Take paused or resumed (button, pause hotkey)
+      const unlistenPaused = await listen('recording-paused', () => {
+        if (!mounted) return
+        pausedAtRef.current = Date.now()
+        setIsPaused(true)
+      })
+      if (mounted) unlisteners.push(unlistenPaused)
+
+      const unlistenResumed = await listen('recording-resumed', () => {
+        if (!mounted) return
+        if (pausedAtRef.current !== null) {
+          pausedTotalRef.current += Date.now() - pausedAtRef.current
+          pausedAtRef.current = null
+        }
+        setIsPaused(false)
+      })
+      if (mounted) unlisteners.push(unlistenResumed)
+
+      // Capture worker fell behind and audio was dropped
+      const unlistenOverrun = await listen('recording-overrun', () => {
+        if (!mounted || overrunWarnedRef.current) return
+        overrunWarnedRef.current = true
+        showWarning('Aussetzer in der Aufnahme', 'Das System war ausgelastet, kurze Teile der Aufnahme fehlen')
+      })
+      if (mounted) unlisteners.push(unlistenOverrun)
+
       // EC-2.4: Hotkey pressed during processing
       const unlistenBusy = await listen('hotkey-busy', () => {
         if (!mounted) return
@@ -496,6 +587,17 @@ export function useHotkey(events?: HotkeyEvents): UseHotkeyReturn {
     currentContextRef.current = null
   }, [])
 
+  // Pause or resume the running take; state follows the backend events
+  const togglePause = useCallback(async () => {
+    if (!isTauri || recordingStateRef.current !== 'recording') return
+
+    try {
+      await invoke(isPaused ? 'resume_audio_recording' : 'pause_audio_recording')
+    } catch (err) {
+      console.error('Failed to toggle pause:', err)
+    }
+  }, [isTauri, isPaused])
+
   // Toggle recording - start if idle, stop if recording
   const toggleRecording = useCallback(() => {
     if (recordingStateRef.current === 'idle') {
@@ -518,6 +620,8 @@ export function useHotkey(events?: HotkeyEvents): UseHotkeyReturn {
     requestAccessibilityPermission,
     recordingStartTime,
     recordingDuration,
+    isPaused,
+    togglePause,
     // PROJ-8/PROJ-9: Expose current context for context-aware processing
     currentContext,
     // Reset state after transcription completes
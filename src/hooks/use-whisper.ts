@@ -1,32 +1,72 @@
 'use client'
 
-import { useState, useEffect, useCallback, useRef } from 'react'
+import { useState, useEffect, useCallback } from 'react'
 import { invoke } from '@tauri-apps/api/core'
 import { listen, UnlistenFn } from '@tauri-apps/api/event'
 import { toast } from 'sonner'
 import { useTauri } from './use-tauri'
 import { showErrorByCode } from '@/lib/app-error'
+import type { PauseMarker, SpeechSegment, SystemTrack } from './use-audio-recording'
+
+/** Id of a Whisper model in the model registry ('small', 'large-v3-turbo-q5_0', ...) */
+export type WhisperModel = string
+
+/** A model from the bundled or user model manifest */
+export interface ModelInfo {
+  id: WhisperModel
+  display_name: string
+  description: string
+  filename: string
+  url: string | null
+  /** Approximate file size in MiB */
+  size_mb: number
+  min_ram_gb: number
+  /** ISO codes the model transcribes, empty for all languages */
+  languages: string[]
+  /** Quantization of the weights ('q5_0', 'q8_0'), null for full precision */
+  quantization: string | null
+  sha256: string | null
+  sha1: string | null
+  /** Imported from a local file, there is nothing to download */
+  custom: boolean
+}
+
+/** ISO code of the spoken language ('de', 'fr', ...) or AUTO_LANGUAGE */
+export type WhisperLanguage = string
 
-/** Available Whisper models */
-export type WhisperModel = 'Tiny' | 'Small' | 'Medium'
+/** Language setting that leaves the choice to auto-detection */
+export const AUTO_LANGUAGE = 'auto'
 
-/** Supported languages */
-export type WhisperLanguage = 'Auto' | 'German' | 'English'
+/** A language Whisper can transcribe, from its own table */
+export interface LanguageInfo {
+  code: string
+  /** English name as Whisper spells it (german, french, ...) */
+  name: string
+}
 
 /** Whisper settings from backend */
 export interface WhisperSettings {
   model: WhisperModel
   language: WhisperLanguage
   use_gpu: boolean
+  /** Transcribe while recording, partial text shows in the overlay */
+  streaming: boolean
+  /** Languages auto-detection may pick from (ISO codes, empty: any) */
+  auto_languages: string[]
+  /** Base URLs tried before Hugging Face, e.g. a local file server */
+  download_mirrors: string[]
 }
 
 /** Model status info */
 export interface ModelStatus {
   model: WhisperModel
+  info: ModelInfo
   downloaded: boolean
   file_size: number | null
   loaded: boolean
   downloading: boolean
+  /** Bytes of an interrupted download the next one resumes from */
+  partial_bytes: number | null
 }
 
 /** Download progress info */
@@ -37,13 +77,22 @@ export interface DownloadProgress {
   speed_bps: number
   complete: boolean
   error: string | null
+  /** Attempt at the current URL, above 1 after the connection broke */
+  attempt: number
+  /** The file is complete and its checksum is being checked */
+  verifying: boolean
 }
 
+/** Who spoke a segment of a take with a separate system audio track */
+export type Speaker = 'Me' | 'Them'
+
 /** Transcription segment with timestamp */
 export interface TranscriptionSegment {
   text: string
   start_ms: number
   end_ms: number
+  /** Track the segment came from (null for single-track takes) */
+  speaker: Speaker | null
 }
 
 /** Transcription result from backend */
@@ -52,6 +101,10 @@ export interface TranscriptionResult {
   language: string
   segments: TranscriptionSegment[]
   processing_time_ms: number
+  /** Probability of the detected language (null when the language was set) */
+  language_probability: number | null
+  /** Auto-detection was unsure, the language may be wrong */
+  language_uncertain: boolean
 }
 
 interface UseWhisperReturn {
@@ -82,46 +135,47 @@ interface UseWhisperReturn {
   /** Unload the current model from memory */
   unloadModel: () => Promise<void>
   /** Transcribe an audio file */
-  transcribe: (wavPath: string) => Promise<TranscriptionResult | null>
+  transcribe: (
+    wavPath: string,
+    speechSegments?: SpeechSegment[],
+    pauseMarkers?: PauseMarker[],
+    systemTrack?: SystemTrack | null,
+    trimOffsetMs?: number
+  ) => Promise<TranscriptionResult | null>
   /** Refresh model status */
   refreshModelStatus: () => Promise<void>
   /** Get display name for a model */
   getModelDisplayName: (model: WhisperModel) => string
+  /** Languages Whisper can transcribe */
+  languages: LanguageInfo[]
   /** Get display name for a language */
   getLanguageDisplayName: (language: WhisperLanguage) => string
 }
 
 const DEFAULT_SETTINGS: WhisperSettings = {
-  model: 'Small',
-  language: 'Auto',
+  model: 'small',
+  language: AUTO_LANGUAGE,
   use_gpu: true,
+  streaming: false,
+  auto_languages: [],
+  download_mirrors: [],
 }
 
-/** Get human-readable model name */
-export function getModelDisplayName(model: WhisperModel): string {
-  switch (model) {
-    case 'Tiny':
-      return 'Tiny (~75 MB, schnell)'
-    case 'Small':
-      return 'Small (~500 MB, empfohlen)'
-    case 'Medium':
-      return 'Medium (~1.5 GB, genau)'
-    default:
-      return model
-  }
+/** Get human-readable file size of a model */
+export function formatModelSize(info: ModelInfo): string {
+  return info.size_mb >= 1024
+    ? `~${(info.size_mb / 1024).toFixed(1)} GB`
+    : `~${info.size_mb} MB`
 }
 
 /** Get human-readable language name */
 export function getLanguageDisplayName(language: WhisperLanguage): string {
-  switch (language) {
-    case 'Auto':
-      return 'Automatisch erkennen'
-    case 'German':
-      return 'Deutsch'
-    case 'English':
-      return 'English'
-    default:
-      return language
+  if (language === AUTO_LANGUAGE) return 'Automatisch erkennen'
+  try {
+    return new Intl.DisplayNames(['de'], { type: 'language' }).of(language) ?? language
+  } catch {
+    // Codes the browser does not know (e.g. 'haw' in older engines)
+    return language
   }
 }
 
@@ -150,25 +204,25 @@ export function useWhisper(): UseWhisperReturn {
   const [isModelLoaded, setIsModelLoaded] = useState(false)
   const [isTranscribing, setIsTranscribing] = useState(false)
   const [lastTranscription, setLastTranscription] = useState<TranscriptionResult | null>(null)
+  const [languages, setLanguages] = useState<LanguageInfo[]>([])
   const [error, setError] = useState<string | null>(null)
 
-  // Polling interval for download progress
-  const progressPollRef = useRef<ReturnType<typeof setInterval> | null>(null)
-
   // Load initial data
   useEffect(() => {
     if (!isTauri) return
 
     const loadData = async () => {
       try {
-        const [whisperSettings, status, loaded] = await Promise.all([
+        const [whisperSettings, status, loaded, supported] = await Promise.all([
           invoke<WhisperSettings>('get_whisper_settings').catch(() => DEFAULT_SETTINGS),
           invoke<ModelStatus[]>('get_whisper_model_status').catch(() => []),
           invoke<boolean>('is_whisper_model_loaded').catch(() => false),
+          invoke<LanguageInfo[]>('get_whisper_languages').catch(() => []),
         ])
         setSettings(whisperSettings)
         setModelStatus(status)
         setIsModelLoaded(loaded)
+        setLanguages(supported)
       } catch (err) {
         console.error('Failed to load whisper data:', err)
       }
@@ -184,6 +238,15 @@ export function useWhisper(): UseWhisperReturn {
     const unlisteners: UnlistenFn[] = []
 
     const setupListeners = async () => {
+      // Download progress, pushed by the backend
+      const unlistenDownloadProgress = await listen<DownloadProgress>(
+        'whisper-download-progress',
+        (event) => {
+          setDownloadProgress(event.payload.error ? null : event.payload)
+        }
+      )
+      unlisteners.push(unlistenDownloadProgress)
+
       // Download complete
       const unlistenDownloadComplete = await listen<WhisperModel>(
         'whisper-download-complete',
@@ -242,39 +305,6 @@ export function useWhisper(): UseWhisperReturn {
     }
   }, [isTauri])
 
-  // Poll download progress while downloading
-  useEffect(() => {
-    if (!isTauri || !downloadProgress || downloadProgress.complete) {
-      if (progressPollRef.current) {
-        clearInterval(progressPollRef.current)
-        progressPollRef.current = null
-      }
-      return
-    }
-
-    progressPollRef.current = setInterval(async () => {
-      try {
-        const progress = await invoke<DownloadProgress | null>('get_whisper_download_progress')
-        if (progress) {
-          setDownloadProgress(progress)
-          if (progress.complete || progress.error) {
-            clearInterval(progressPollRef.current!)
-            progressPollRef.current = null
-          }
-        }
-      } catch {
-        // Ignore errors during polling
-      }
-    }, 200)
-
-    return () => {
-      if (progressPollRef.current) {
-        clearInterval(progressPollRef.current)
-        progressPollRef.current = null
-      }
-    }
-  }, [isTauri, downloadProgress?.complete])
-
   // Update settings
   const updateSettings = useCallback(
     async (newSettings: Partial<WhisperSettings>) => {
@@ -316,6 +346,8 @@ export function useWhisper(): UseWhisperReturn {
           speed_bps: 0,
           complete: false,
           error: null,
+          attempt: 1,
+          verifying: false,
         })
 
         await invoke('download_whisper_model', { model })
@@ -403,14 +435,26 @@ export function useWhisper(): UseWhisperReturn {
 
   // Transcribe audio
   const transcribe = useCallback(
-    async (wavPath: string): Promise<TranscriptionResult | null> => {
+    async (
+      wavPath: string,
+      speechSegments?: SpeechSegment[],
+      pauseMarkers?: PauseMarker[],
+      systemTrack?: SystemTrack | null,
+      trimOffsetMs?: number
+    ): Promise<TranscriptionResult | null> => {
       if (!isTauri) return null
 
       try {
         setError(null)
         setIsTranscribing(true)
 
-        const result = await invoke<TranscriptionResult>('transcribe_audio', { wavPath })
+        const result = await invoke<TranscriptionResult>('transcribe_audio', {
+          wavPath,
+          speechSegments: speechSegments ?? null,
+          pauseMarkers: pauseMarkers ?? null,
+          systemTrack: systemTrack ?? null,
+          trimOffsetMs: trimOffsetMs ?? null,
+        })
         setLastTranscription(result)
         setIsTranscribing(false)
         return result
@@ -421,7 +465,7 @@ export function useWhisper(): UseWhisperReturn {
         setIsTranscribing(false)
         showErrorByCode('ERR_TRANSCRIPTION_FAILED', 'whisper', {
           details: message,
-          action: async () => { await transcribe(wavPath) },
+          action: async () => { await transcribe(wavPath, speechSegments, pauseMarkers, systemTrack, trimOffsetMs) },
         })
         return null
       }
@@ -441,6 +485,15 @@ export function useWhisper(): UseWhisperReturn {
     }
   }, [isTauri])
 
+  // Get display name for a model from its manifest entry
+  const getModelDisplayName = useCallback(
+    (model: WhisperModel) => {
+      const info = modelStatus.find((status) => status.model === model)?.info
+      return info ? `${info.display_name} (${formatModelSize(info)})` : model
+    },
+    [modelStatus]
+  )
+
   return {
     settings,
     modelStatus,
@@ -458,6 +511,7 @@ export function useWhisper(): UseWhisperReturn {
     transcribe,
     refreshModelStatus,
     getModelDisplayName,
+    languages,
     getLanguageDisplayName,
   }
 }